@@ -0,0 +1,75 @@
+use std::fmt;
+use std::ops::Deref;
+
+/// A parsed answer, along with the text it was parsed from.
+///
+/// Returned by [`prompt_answer!`] and [`Prompter::answer`]. It dereferences
+/// to the parsed value, while [`Answer::as_str`] returns the original text,
+/// for example to echo it back or to log it. Keeping the text means the
+/// answer owns both a `T` and a `String`; call [`Answer::into_inner`] to drop
+/// the text once it's no longer needed.
+///
+/// [`prompt_answer!`]: crate::prompt_answer
+/// [`Prompter::answer`]: crate::Prompter::answer
+///
+/// # Examples
+///
+/// ```
+/// # fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+/// use io_prompt_prototype::Prompter;
+/// use std::io::Cursor;
+///
+/// let mut prompter = Prompter::new(Cursor::new(b"0042\n"), Vec::new());
+/// let num = prompter.answer::<u16>("Number? >")??;
+/// assert_eq!(*num, 42);
+/// assert_eq!(num.as_str(), "0042");
+/// # Ok(()) }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Answer<T> {
+    value: T,
+    text: String,
+}
+
+impl<T> Answer<T> {
+    pub(crate) fn new(value: T, text: String) -> Self {
+        Self { value, text }
+    }
+
+    /// Returns the text the answer was parsed from, without its line
+    /// terminator.
+    pub fn as_str(&self) -> &str {
+        &self.text
+    }
+
+    /// Consumes the answer, returning the parsed value.
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+
+    /// Consumes the answer, returning the parsed value and the text it was
+    /// parsed from.
+    pub fn into_parts(self) -> (T, String) {
+        (self.value, self.text)
+    }
+}
+
+impl<T> Deref for Answer<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.value
+    }
+}
+
+impl<T> AsRef<str> for Answer<T> {
+    fn as_ref(&self) -> &str {
+        &self.text
+    }
+}
+
+impl<T> fmt::Display for Answer<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.text)
+    }
+}
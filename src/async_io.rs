@@ -0,0 +1,93 @@
+//! Asynchronous line reading, built on `tokio`.
+
+use std::io;
+
+use tokio::io::{
+    stderr, stdin, stdout, AsyncBufRead, AsyncBufReadExt, AsyncWrite, AsyncWriteExt, BufReader,
+};
+
+use crate::{prompt_stream, trace, trim_newline, Stream};
+
+/// Reads a line of input from stdin asynchronously, removing the trailing
+/// newline.
+///
+/// This is the asynchronous counterpart to [`read_line_trimmed`], and removes
+/// line terminators in exactly the same way. Stdin is read one byte at a time,
+/// so that nothing past the end of the line is consumed.
+///
+/// [`read_line_trimmed`]: crate::read_line_trimmed
+///
+/// # Examples
+///
+/// ```no_run
+/// # async fn run() -> std::io::Result<()> {
+/// use io_prompt_prototype::read_line_async;
+///
+/// let name = read_line_async().await?;
+/// println!("Hello, {}!", name);
+/// # Ok(()) }
+/// ```
+pub async fn read_line_async() -> io::Result<String> {
+    read_line_async_from(&mut BufReader::with_capacity(1, stdin())).await
+}
+
+/// Reads a line of input from an asynchronous reader, removing the trailing
+/// newline.
+///
+/// This behaves exactly like [`read_line_async`], but reads from an arbitrary
+/// [`AsyncBufRead`] rather than from stdin.
+///
+/// # Examples
+///
+/// ```
+/// # async fn run() -> std::io::Result<()> {
+/// use io_prompt_prototype::read_line_async_from;
+///
+/// let mut input: &[u8] = b"hello\r\nworld\n";
+/// assert_eq!(read_line_async_from(&mut input).await?, "hello");
+/// assert_eq!(read_line_async_from(&mut input).await?, "world");
+/// # Ok(()) }
+/// ```
+pub async fn read_line_async_from<R>(reader: &mut R) -> io::Result<String>
+where
+    R: AsyncBufRead + Unpin,
+{
+    let mut line = String::new();
+    reader.read_line(&mut line).await?;
+    trim_newline(&mut line);
+    Ok(line)
+}
+
+/// Prints to the standard output asynchronously. Then reads a line of input.
+///
+/// This is the asynchronous counterpart to [`try_prompt!`], taking a message
+/// rather than a format string. Like it, this writes to the stream chosen with
+/// [`set_prompt_stream`].
+///
+/// [`try_prompt!`]: crate::try_prompt
+/// [`set_prompt_stream`]: crate::set_prompt_stream
+///
+/// # Examples
+///
+/// ```no_run
+/// # async fn run() -> std::io::Result<()> {
+/// use io_prompt_prototype::prompt_async;
+///
+/// let name = prompt_async("What's your name? >").await?;
+/// println!("Hello, {}!", name);
+/// # Ok(()) }
+/// ```
+pub async fn prompt_async(msg: &str) -> io::Result<String> {
+    trace::prompt(msg);
+    match prompt_stream() {
+        Stream::Stdout => write_async(&mut stdout(), msg).await?,
+        Stream::Stderr => write_async(&mut stderr(), msg).await?,
+    }
+    trace::read_answer(read_line_async().await)
+}
+
+/// Writes `msg` to `out` and flushes it.
+async fn write_async<W: AsyncWrite + Unpin>(out: &mut W, msg: &str) -> io::Result<()> {
+    out.write_all(msg.as_bytes()).await?;
+    out.flush().await
+}
@@ -0,0 +1,708 @@
+use std::fmt;
+use std::io::{self, BufRead, Write};
+use std::sync::{PoisonError, RwLock};
+use std::thread;
+use std::time::Duration;
+
+use crate::{Prompter, RetriesExhausted};
+
+/// A predicate an answer has to satisfy.
+type Validator = Box<dyn Fn(&str) -> bool>;
+
+/// A function waiting out the delay before a re-prompt.
+type Sleep = Box<dyn Fn(Duration)>;
+
+/// A function normalizing each answer.
+type Map = Box<dyn Fn(String) -> String>;
+
+/// A normalization shared by all prompts.
+type DefaultMap = fn(String) -> String;
+
+/// The normalization applied by prompts which weren't given their own.
+static DEFAULT_MAP: RwLock<Option<DefaultMap>> = RwLock::new(None);
+
+/// A builder for prompts which combine several options.
+///
+/// The macros each expose a single behavior, such as a default or a validation
+/// predicate. A `PromptBuilder` composes them: set the options you need, call
+/// [`build`](PromptBuilder::build), and then [`ask`](Prompt::ask) the
+/// resulting [`Prompt`] as many times as you like.
+///
+/// # Examples
+///
+/// ```no_run
+/// # fn main() -> std::io::Result<()> {
+/// use io_prompt_prototype::PromptBuilder;
+///
+/// let name = PromptBuilder::new()
+///     .message("Name? >")
+///     .default("Ferris")
+///     .trim(true)
+///     .build()
+///     .ask()?;
+/// println!("Hello, {}!", name);
+/// # Ok(()) }
+/// ```
+///
+/// Validating the answer, giving up after three retries:
+///
+/// ```
+/// # fn main() -> std::io::Result<()> {
+/// use io_prompt_prototype::{PromptBuilder, Prompter};
+/// use std::io::Cursor;
+///
+/// let prompt = PromptBuilder::new()
+///     .message("Age? >")
+///     .validate(|s| s.parse::<u8>().is_ok())
+///     .invalid("Please enter a number")
+///     .max_retries(3)
+///     .build();
+///
+/// let mut prompter = Prompter::new(Cursor::new(b"old\n42\n"), Vec::new());
+/// assert_eq!(prompt.ask_with(&mut prompter)?, "42");
+///
+/// let mut prompter = Prompter::new(Cursor::new(b"a\nb\nc\nd\n"), Vec::new());
+/// assert!(prompt.ask_with(&mut prompter).is_err());
+/// # Ok(()) }
+/// ```
+#[derive(Debug, Default)]
+pub struct PromptBuilder {
+    prompt: Prompt,
+}
+
+impl PromptBuilder {
+    /// Creates a new builder, for a prompt with an empty message and no
+    /// options set.
+    pub fn new() -> Self {
+        <Self as Default>::default()
+    }
+
+    /// Sets the message written before reading the answer.
+    pub fn message(mut self, message: impl Into<String>) -> Self {
+        self.prompt.message = message.into();
+        self
+    }
+
+    /// Sets a separator written after the message, such as `"> "` or `": "`.
+    ///
+    /// Trailing whitespace in the message is removed before the suffix is
+    /// added, as is the suffix itself if the message already ends with it, so
+    /// it's always written exactly once. Any default is shown before the
+    /// suffix.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # fn main() -> std::io::Result<()> {
+    /// use io_prompt_prototype::{PromptBuilder, Prompter};
+    /// use std::io::Cursor;
+    ///
+    /// let prompt = PromptBuilder::new()
+    ///     .message("Name")
+    ///     .default("Ferris")
+    ///     .suffix(": ")
+    ///     .build();
+    /// let mut prompter = Prompter::new(Cursor::new(b"\n"), Vec::new());
+    /// prompt.ask_with(&mut prompter)?;
+    /// assert_eq!(prompter.into_parts().1, b"Name [Ferris]: ");
+    /// # Ok(()) }
+    /// ```
+    pub fn suffix(mut self, suffix: impl Into<String>) -> Self {
+        self.prompt.suffix = Some(suffix.into());
+        self
+    }
+
+    /// Sets the answer returned when the user enters nothing.
+    ///
+    /// The default is shown after the message in the same way as
+    /// [`Prompter::prompt_default`] does, and is returned without being
+    /// validated.
+    pub fn default(mut self, default: impl Into<String>) -> Self {
+        self.prompt.default = Some(default.into());
+        self
+    }
+
+    /// Sets example answers shown after the message, to guide the user.
+    ///
+    /// The suggestions are rendered as ` (e.g. 8080, 3000)`: a space, then
+    /// `(e.g. ` followed by the suggestions separated by `, `, then `)`. This
+    /// is added after the message, with its trailing whitespace removed, and
+    /// before the default and the [`suffix`](PromptBuilder::suffix). Without a
+    /// suffix, a space is written after them. An empty list shows nothing.
+    /// The suggestions are only shown: answers aren't required to match one.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # fn main() -> std::io::Result<()> {
+    /// use io_prompt_prototype::{PromptBuilder, Prompter};
+    /// use std::io::Cursor;
+    ///
+    /// let prompt = PromptBuilder::new()
+    ///     .message("Port")
+    ///     .suggestions(&["8080", "3000"])
+    ///     .suffix(" >")
+    ///     .build();
+    /// let mut prompter = Prompter::new(Cursor::new(b"80\n"), Vec::new());
+    /// prompt.ask_with(&mut prompter)?;
+    /// assert_eq!(prompter.into_parts().1, b"Port (e.g. 8080, 3000) >");
+    /// # Ok(()) }
+    /// ```
+    pub fn suggestions(mut self, suggestions: &[&str]) -> Self {
+        self.prompt.suggestions = suggestions.iter().map(|s| s.to_string()).collect();
+        self
+    }
+
+    /// Sets whether surrounding whitespace is removed from the answer.
+    ///
+    /// Only the line terminator is removed when this is `false`, which is the
+    /// default.
+    pub fn trim(mut self, trim: bool) -> Self {
+        self.prompt.trim = trim;
+        self
+    }
+
+    /// Sets a function which normalizes each answer, such as by lowercasing
+    /// it.
+    ///
+    /// The function is applied to every answer read, after the line
+    /// terminator is removed and, if [`trim`](PromptBuilder::trim) is set,
+    /// after surrounding whitespace is removed. Its result is what's compared
+    /// against the empty answer to decide whether the default is used, what
+    /// the predicate is given, and what's returned. The default itself is
+    /// returned as given, without being mapped. A prompt without a function
+    /// of its own uses the one set with [`set_default_map`], if any.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # fn main() -> std::io::Result<()> {
+    /// use io_prompt_prototype::{PromptBuilder, Prompter};
+    /// use std::io::Cursor;
+    ///
+    /// let prompt = PromptBuilder::new()
+    ///     .message("Color? >")
+    ///     .map(|s| s.to_lowercase())
+    ///     .validate(|s| s == "red" || s == "blue")
+    ///     .build();
+    /// let mut prompter = Prompter::new(Cursor::new(b"Red\n"), Vec::new());
+    /// assert_eq!(prompt.ask_with(&mut prompter)?, "red");
+    /// # Ok(()) }
+    /// ```
+    pub fn map<F>(mut self, map: F) -> Self
+    where
+        F: Fn(String) -> String + 'static,
+    {
+        self.prompt.map = Some(Box::new(map));
+        self
+    }
+
+    /// Sets a predicate the answer has to satisfy.
+    ///
+    /// Answers which are rejected are asked for again.
+    pub fn validate<F>(mut self, is_valid: F) -> Self
+    where
+        F: Fn(&str) -> bool + 'static,
+    {
+        self.prompt.validate = Some(Box::new(is_valid));
+        self
+    }
+
+    /// Sets the message written, with a newline, each time an answer is
+    /// rejected.
+    pub fn invalid(mut self, invalid: impl Into<String>) -> Self {
+        self.prompt.invalid = Some(invalid.into());
+        self
+    }
+
+    /// Sets a more detailed message, written instead of the
+    /// [`invalid`](PromptBuilder::invalid) one once `after` answers have been
+    /// rejected.
+    ///
+    /// The first `after` rejected answers are each followed by the `invalid`
+    /// message, if any, and every rejected answer from then on by `hint`. With
+    /// `after` set to 2, the first and second failures show the `invalid`
+    /// message, and the third and later ones show `hint`. An `after` of 0
+    /// shows `hint` from the first failure. This keeps the prompt terse for a
+    /// single typo, while explaining itself to a user who is clearly stuck.
+    /// Combined with [`max_retries`](PromptBuilder::max_retries), the prompt
+    /// gives up after the rejected answer which exhausts the retries, having
+    /// written whichever message applies to it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # fn main() -> std::io::Result<()> {
+    /// use io_prompt_prototype::{PromptBuilder, Prompter};
+    /// use std::io::Cursor;
+    ///
+    /// let prompt = PromptBuilder::new()
+    ///     .message("Date? >")
+    ///     .validate(|s| s.len() == 10)
+    ///     .invalid("Invalid date")
+    ///     .escalated_hint(1, "Dates are written as YYYY-MM-DD, such as 2024-05-17")
+    ///     .build();
+    /// let mut prompter = Prompter::new(Cursor::new(b"May\n17th\n2024-05-17\n"), Vec::new());
+    /// prompt.ask_with(&mut prompter)?;
+    /// let output = String::from_utf8(prompter.into_parts().1).unwrap();
+    /// assert_eq!(
+    ///     output,
+    ///     "Date? >Invalid date\n\
+    ///      Date? >Dates are written as YYYY-MM-DD, such as 2024-05-17\n\
+    ///      Date? >",
+    /// );
+    /// # Ok(()) }
+    /// ```
+    pub fn escalated_hint(mut self, after: usize, hint: impl Into<String>) -> Self {
+        self.prompt.escalated = Some((after, hint.into()));
+        self
+    }
+
+    /// Sets how many times a rejected answer is asked for again before giving
+    /// up.
+    ///
+    /// By default the prompt is repeated until a valid answer is given. See
+    /// [`Prompter::max_retries`] for the error returned once the retries are
+    /// exhausted.
+    pub fn max_retries(mut self, max_retries: usize) -> Self {
+        self.prompt.max_retries = Some(max_retries);
+        self
+    }
+
+    /// Sets a delay inserted before each re-prompt.
+    ///
+    /// Only the re-prompt path is delayed: after an answer is rejected by the
+    /// predicate and its `invalid` message is written, the prompter waits for
+    /// `delay` before writing the message again. The first prompt, valid
+    /// answers and giving up after [`max_retries`](PromptBuilder::max_retries)
+    /// are never delayed. This throttles a loop fed an endless stream of
+    /// invalid input, such as a misbehaving pipe, which would otherwise spin
+    /// at full speed. There is no delay by default.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # fn main() -> std::io::Result<()> {
+    /// use io_prompt_prototype::PromptBuilder;
+    /// use std::time::Duration;
+    ///
+    /// let port = PromptBuilder::new()
+    ///     .message("Port? >")
+    ///     .validate(|s| s.parse::<u16>().is_ok())
+    ///     .retry_delay(Duration::from_millis(250))
+    ///     .build()
+    ///     .ask()?;
+    /// # Ok(()) }
+    /// ```
+    pub fn retry_delay(mut self, delay: Duration) -> Self {
+        self.prompt.retry_delay = Some(delay);
+        self
+    }
+
+    /// Sets the function called to wait out the [`retry_delay`].
+    ///
+    /// By default the current thread sleeps with [`thread::sleep`]. Replacing
+    /// it makes it possible to count the delays in tests without waiting, or
+    /// to wait in some other way. It's only called when a retry delay is set.
+    ///
+    /// [`retry_delay`]: PromptBuilder::retry_delay
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # fn main() -> std::io::Result<()> {
+    /// use io_prompt_prototype::{PromptBuilder, Prompter};
+    /// use std::cell::Cell;
+    /// use std::io::Cursor;
+    /// use std::rc::Rc;
+    /// use std::time::Duration;
+    ///
+    /// let slept = Rc::new(Cell::new(Duration::ZERO));
+    /// let total = Rc::clone(&slept);
+    /// let prompt = PromptBuilder::new()
+    ///     .validate(|s| s == "ok")
+    ///     .retry_delay(Duration::from_secs(1))
+    ///     .sleep_with(move |delay| total.set(total.get() + delay))
+    ///     .build();
+    /// let mut prompter = Prompter::new(Cursor::new(b"a\nb\nok\n"), Vec::new());
+    /// prompt.ask_with(&mut prompter)?;
+    /// assert_eq!(slept.get(), Duration::from_secs(2));
+    /// # Ok(()) }
+    /// ```
+    pub fn sleep_with<F>(mut self, sleep: F) -> Self
+    where
+        F: Fn(Duration) + 'static,
+    {
+        self.prompt.sleep = Some(Box::new(sleep));
+        self
+    }
+
+    /// Sets whether the terminal bell is rung when an answer is rejected.
+    ///
+    /// When enabled, the ASCII BEL character (`\x07`) is written each time the
+    /// predicate rejects an answer, before the `invalid` message, which makes
+    /// most terminals beep or flash. It's only written when the prompter's
+    /// output is a terminal, see [`Prompter::terminal`], so piped output and
+    /// logs don't collect stray control characters. It's off by default.
+    pub fn bell_on_error(mut self, bell: bool) -> Self {
+        self.prompt.bell = bell;
+        self
+    }
+
+    /// Sets whether the output is flushed before reading the answer.
+    ///
+    /// By default it is. Turning it off saves a flush per prompt when writing
+    /// to a buffered writer, at the risk of the prompt not being shown until
+    /// the caller flushes. See [`Prompter::auto_flush`] for the details.
+    pub fn auto_flush(mut self, auto_flush: bool) -> Self {
+        self.prompt.no_flush = !auto_flush;
+        self
+    }
+
+    /// Creates the configured prompt.
+    pub fn build(self) -> Prompt {
+        self.prompt
+    }
+}
+
+/// A prompt created by a [`PromptBuilder`].
+#[derive(Default)]
+pub struct Prompt {
+    message: String,
+    suffix: Option<String>,
+    suggestions: Vec<String>,
+    default: Option<String>,
+    trim: bool,
+    validate: Option<Validator>,
+    invalid: Option<String>,
+    escalated: Option<(usize, String)>,
+    max_retries: Option<usize>,
+    no_flush: bool,
+    retry_delay: Option<Duration>,
+    sleep: Option<Sleep>,
+    bell: bool,
+    map: Option<Map>,
+}
+
+impl Prompt {
+    /// Prints the message to the standard output. Then reads an answer from
+    /// stdin.
+    ///
+    /// This is a shorthand for calling [`ask_with`](Prompt::ask_with) on
+    /// [`Prompter::stdio`].
+    pub fn ask(&self) -> io::Result<String> {
+        self.ask_with(&mut Prompter::stdio())
+    }
+
+    /// Writes the message to a prompter's output. Then reads an answer from
+    /// its input.
+    ///
+    /// If the input ends before an answer is given the default is returned,
+    /// or an empty string if there is neither a default nor a predicate. An
+    /// error of kind [`io::ErrorKind::UnexpectedEof`] is returned if there is
+    /// a predicate but no default, and one of kind
+    /// [`io::ErrorKind::InvalidInput`] if the maximum number of retries is
+    /// exceeded.
+    pub fn ask_with<R: BufRead, W: Write>(
+        &self,
+        prompter: &mut Prompter<R, W>,
+    ) -> io::Result<String> {
+        let auto_flush = prompter.auto_flush;
+        prompter.auto_flush &= !self.no_flush;
+        let answer = self.ask_until_valid(prompter);
+        prompter.auto_flush = auto_flush;
+        answer
+    }
+
+    /// Asks until a valid answer is given, see [`Prompt::ask_with`].
+    fn ask_until_valid<R: BufRead, W: Write>(
+        &self,
+        prompter: &mut Prompter<R, W>,
+    ) -> io::Result<String> {
+        let message = self.render();
+        let mut retries = 0;
+        loop {
+            let answer = match prompter.prompt_opt(&message)? {
+                Some(answer) if self.trim => self.map(answer.trim().to_string()),
+                Some(answer) => self.map(answer),
+                None => match (&self.default, &self.validate) {
+                    (Some(default), _) => return Ok(default.clone()),
+                    (None, None) => return Ok(String::new()),
+                    (None, Some(_)) => {
+                        return Err(io::Error::new(
+                            io::ErrorKind::UnexpectedEof,
+                            "input ended before a valid answer was given",
+                        ))
+                    }
+                },
+            };
+            if let (Some(default), "") = (&self.default, answer.trim()) {
+                return Ok(default.clone());
+            }
+            match &self.validate {
+                Some(is_valid) if !is_valid(&answer) => {}
+                _ => return Ok(answer),
+            }
+            if self.bell && prompter.terminal {
+                prompter.writer.write_all(b"\x07")?;
+            }
+            let invalid = match &self.escalated {
+                Some((after, hint)) if retries >= *after => Some(hint),
+                _ => self.invalid.as_ref(),
+            };
+            if let Some(invalid) = invalid {
+                writeln!(prompter.writer, "{}", invalid)?;
+            }
+            if self.max_retries == Some(retries) {
+                return Err(RetriesExhausted::new(retries + 1, &answer).into());
+            }
+            retries += 1;
+            self.delay();
+        }
+    }
+
+    /// Normalizes an answer with the prompt's map, or the default one.
+    fn map(&self, answer: String) -> String {
+        if let Some(map) = &self.map {
+            return map(answer);
+        }
+        match *DEFAULT_MAP.read().unwrap_or_else(PoisonError::into_inner) {
+            Some(map) => map(answer),
+            None => answer,
+        }
+    }
+
+    /// Waits for the retry delay, if any.
+    fn delay(&self) {
+        if let Some(delay) = self.retry_delay {
+            match &self.sleep {
+                Some(sleep) => sleep(delay),
+                None => thread::sleep(delay),
+            }
+        }
+    }
+}
+
+impl Prompt {
+    /// Renders the message, along with the suggestions, default and suffix.
+    fn render(&self) -> String {
+        let suffix = match &self.suffix {
+            Some(suffix) => suffix,
+            None if self.default.is_none() && self.suggestions.is_empty() => {
+                return self.message.clone()
+            }
+            None => {
+                let mut message = self.message.trim_end().to_string();
+                self.push_hints(&mut message);
+                message.push(' ');
+                return message;
+            }
+        };
+        let message = self.message.trim_end();
+        let mut message = message
+            .strip_suffix(suffix.trim_end())
+            .unwrap_or(message)
+            .trim_end()
+            .to_string();
+        self.push_hints(&mut message);
+        message.push_str(suffix);
+        message
+    }
+
+    /// Appends the suggestions and the default to `message`.
+    fn push_hints(&self, message: &mut String) {
+        if !self.suggestions.is_empty() {
+            message.push_str(&format!(" (e.g. {})", self.suggestions.join(", ")));
+        }
+        if let Some(default) = &self.default {
+            message.push_str(&format!(" [{}]", default));
+        }
+    }
+}
+
+impl fmt::Debug for Prompt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Prompt")
+            .field("message", &self.message)
+            .field("suffix", &self.suffix)
+            .field("suggestions", &self.suggestions)
+            .field("default", &self.default)
+            .field("trim", &self.trim)
+            .field("validate", &self.validate.as_ref().map(|_| ".."))
+            .field("invalid", &self.invalid)
+            .field("escalated", &self.escalated)
+            .field("max_retries", &self.max_retries)
+            .field("no_flush", &self.no_flush)
+            .field("retry_delay", &self.retry_delay)
+            .field("sleep", &self.sleep.as_ref().map(|_| ".."))
+            .field("bell", &self.bell)
+            .field("map", &self.map.as_ref().map(|_| ".."))
+            .finish()
+    }
+}
+
+/// Sets the function which normalizes the answers to every [`Prompt`] that
+/// wasn't given one with [`PromptBuilder::map`], for the whole process.
+///
+/// This centralizes a normalization which applies to all prompts, such as
+/// lowercasing or Unicode normalization, instead of repeating it at each call
+/// site. It's applied at the same point as [`PromptBuilder::map`], see there
+/// for the order. Passing `None` removes it. Like [`set_locale`], this is
+/// meant to be called once at startup.
+///
+/// [`set_locale`]: crate::set_locale
+///
+/// # Examples
+///
+/// ```
+/// # fn main() -> std::io::Result<()> {
+/// use io_prompt_prototype::{set_default_map, PromptBuilder, Prompter};
+/// use std::io::Cursor;
+///
+/// set_default_map(Some(|s| s.trim().to_lowercase()));
+///
+/// let mut prompter = Prompter::new(Cursor::new(b" YES \n"), Vec::new());
+/// let answer = PromptBuilder::new().build().ask_with(&mut prompter)?;
+/// assert_eq!(answer, "yes");
+/// # set_default_map(None);
+/// # Ok(()) }
+/// ```
+pub fn set_default_map(map: Option<fn(String) -> String>) {
+    *DEFAULT_MAP.write().unwrap_or_else(PoisonError::into_inner) = map;
+}
+
+/// Prints a message followed by a separator to the standard output. Then reads
+/// a line of input.
+///
+/// The separator defaults to `"> "`, and can be changed by passing
+/// `suffix = <str>` before the message, including to an empty string. It's
+/// written exactly once, so messages don't need to include it consistently.
+/// See [`PromptBuilder::suffix`] for the details. This panics on I/O errors.
+///
+/// # Examples
+///
+/// ```no_run
+/// use io_prompt_prototype::prompt_fmt;
+///
+/// // Renders as `What's your name> `.
+/// let name = prompt_fmt!("What's your name");
+///
+/// // Renders as `How old are you, Ferris: `.
+/// let age = prompt_fmt!(suffix = ": ", "How old are you, {}", name);
+/// ```
+#[macro_export]
+macro_rules! prompt_fmt {
+    (suffix = $suffix:expr, $($arg:tt)*) => {
+        $crate::PromptBuilder::new()
+            .message(format!($($arg)*))
+            .suffix($suffix)
+            .build()
+            .ask()
+            .unwrap_or_else(|e| panic!("failed prompting on stdin/stdout: {}", e))
+    };
+    ($($arg:tt)*) => {
+        $crate::prompt_fmt!(suffix = "> ", $($arg)*)
+    };
+}
+
+/// Prints to the standard output. Then reads a line of input, re-prompting
+/// until it satisfies a predicate, with a more detailed hint once the user
+/// keeps getting it wrong.
+///
+/// Each of the first `after = <n>` rejected answers is followed by the
+/// `invalid = <msg>` message, and every rejected answer from then on by the
+/// `hint = <msg>` one. With `after = 2` the third failure is the first to show
+/// the hint. See [`PromptBuilder::escalated_hint`] for the details.
+///
+/// This panics on I/O errors, or if stdin is closed before an answer is
+/// accepted. Passing `max_retries = <n>` limits how many times an invalid
+/// answer is asked for again. The macro then returns an `io::Result` instead
+/// of panicking, holding a [`RetriesExhausted`] error once the retries run
+/// out.
+///
+/// [`RetriesExhausted`]: crate::RetriesExhausted
+///
+/// # Examples
+///
+/// ```no_run
+/// use io_prompt_prototype::prompt_retry_with_hint;
+///
+/// let date = prompt_retry_with_hint!(
+///     "Date? >",
+///     |s: &str| s.len() == 10,
+///     invalid = "Invalid date",
+///     after = 2,
+///     hint = "Dates are written as YYYY-MM-DD, such as 2024-05-17",
+///     max_retries = 5,
+/// );
+/// if date.is_err() {
+///     println!("Giving up");
+/// }
+/// ```
+#[macro_export]
+macro_rules! prompt_retry_with_hint {
+    (
+        $msg:expr,
+        $is_valid:expr,
+        invalid = $invalid:expr,
+        after = $after:expr,
+        hint = $hint:expr,
+        max_retries = $max:expr $(,)?
+    ) => {
+        $crate::PromptBuilder::new()
+            .message($msg)
+            .validate($is_valid)
+            .invalid($invalid)
+            .escalated_hint($after, $hint)
+            .max_retries($max)
+            .build()
+            .ask()
+    };
+    (
+        $msg:expr,
+        $is_valid:expr,
+        invalid = $invalid:expr,
+        after = $after:expr,
+        hint = $hint:expr $(,)?
+    ) => {
+        $crate::PromptBuilder::new()
+            .message($msg)
+            .validate($is_valid)
+            .invalid($invalid)
+            .escalated_hint($after, $hint)
+            .build()
+            .ask()
+            .unwrap_or_else(|e| panic!("failed prompting on stdin/stdout: {}", e))
+    };
+}
+
+/// Prints a message followed by example answers to the standard output. Then
+/// reads a line of input.
+///
+/// The suggestions are given as a slice of strings before the message, and
+/// rendered between the message and a trailing ` >`, which is written exactly
+/// once whether or not the message ends with `>`: both `"Port"` and
+/// `"Port >"` render as `Port (e.g. 8080, 3000) >`. See
+/// [`PromptBuilder::suggestions`] for the format. The answer isn't required to
+/// match a suggestion. This panics on I/O errors.
+///
+/// # Examples
+///
+/// ```no_run
+/// use io_prompt_prototype::prompt_with_suggestions;
+///
+/// // Renders as `Port (e.g. 8080, 3000) >`.
+/// let port = prompt_with_suggestions!(&["8080", "3000"], "Port >");
+/// ```
+#[macro_export]
+macro_rules! prompt_with_suggestions {
+    ($suggestions:expr, $($arg:tt)*) => {
+        $crate::PromptBuilder::new()
+            .message(format!($($arg)*))
+            .suggestions($suggestions)
+            .suffix(" >")
+            .build()
+            .ask()
+            .unwrap_or_else(|e| panic!("failed prompting on stdin/stdout: {}", e))
+    };
+}
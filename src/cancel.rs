@@ -0,0 +1,118 @@
+use std::io::{self, Read, Write};
+
+use crate::editor::Editor;
+use crate::input::{interrupted, next_key, Key};
+use crate::{prompt_stream, read_line_trimmed, script, term, trace};
+
+/// Prints to the standard output. Then reads a line of input, returning an
+/// error if Ctrl-C is pressed rather than letting it end the process.
+///
+/// Pressing Ctrl-C returns an error of kind [`io::ErrorKind::Interrupted`],
+/// which lets a program cancel only the current prompt, for example to return
+/// to a menu. Other keys edit the line as in [`prompt_complete`].
+///
+/// Rather than installing a `SIGINT` handler, which is process-global and
+/// can't be done from safe code, the terminal is put into raw mode for the
+/// duration of the read. This stops it from turning Ctrl-C into a signal, so
+/// it's read as an ordinary keypress instead. The previous terminal mode is
+/// restored afterwards, including when the read fails or panics, so Ctrl-C
+/// outside the prompt ends the process as usual. The terminal mode is shared
+/// by the whole process, so Ctrl-C doesn't raise a signal in any thread while
+/// the read is in progress. If the process is killed during the read, the
+/// terminal isn't restored.
+///
+/// When stdin is not a terminal there is no Ctrl-C to intercept, and this
+/// behaves exactly like [`read_line_trimmed`].
+///
+/// [`prompt_complete`]: crate::prompt_complete
+///
+/// # Examples
+///
+/// ```no_run
+/// # fn main() -> std::io::Result<()> {
+/// use io_prompt_prototype::prompt_cancelable;
+/// use std::io::ErrorKind;
+///
+/// loop {
+///     match prompt_cancelable("Command? >") {
+///         Ok(command) => println!("Running {}", command),
+///         Err(err) if err.kind() == ErrorKind::Interrupted => continue,
+///         Err(err) => return Err(err),
+///     }
+/// }
+/// # }
+/// ```
+pub fn prompt_cancelable(msg: &str) -> io::Result<String> {
+    let mut out = prompt_stream();
+    if !term::stdin_is_tty() {
+        trace::prompt(msg);
+        out.write_all(msg.as_bytes())?;
+        out.flush()?;
+        return trace::read_answer(read_line_trimmed());
+    }
+    let _guard = term::enable_raw()?;
+    prompt_cancelable_from(&mut script::input(), &mut out, msg)
+}
+
+/// Writes `msg` to a writer. Then reads raw keypresses from a reader until
+/// Enter is pressed, returning an error on Ctrl-C.
+///
+/// This implements the input handling of [`prompt_cancelable`] without
+/// touching the terminal mode.
+///
+/// # Examples
+///
+/// ```
+/// use io_prompt_prototype::prompt_cancelable_from;
+/// use std::io::{Cursor, ErrorKind};
+///
+/// let mut output = Vec::new();
+/// let mut input = Cursor::new(b"rm -rf /\x03ls\r");
+/// let err = prompt_cancelable_from(&mut input, &mut output, "$ ").unwrap_err();
+/// assert_eq!(err.kind(), ErrorKind::Interrupted);
+/// assert_eq!(prompt_cancelable_from(&mut input, &mut output, "$ ").unwrap(), "ls");
+/// ```
+pub fn prompt_cancelable_from<R, W>(reader: &mut R, writer: &mut W, msg: &str) -> io::Result<String>
+where
+    R: Read,
+    W: Write,
+{
+    trace::prompt(msg);
+    writer.write_all(msg.as_bytes())?;
+    writer.flush()?;
+    let mut editor = Editor::new(writer, msg);
+    loop {
+        match next_key(reader)? {
+            None | Some(Key::Enter) => break,
+            Some(Key::Eof) if editor.line().is_empty() => break,
+            Some(Key::Interrupt) => return Err(interrupted()),
+            Some(key) => editor.edit(key)?,
+        }
+    }
+    trace::read_answer(editor.finish())
+}
+
+/// Prints to the standard output. Then reads a line of input, returning an
+/// error if Ctrl-C is pressed.
+///
+/// This is a shorthand for calling [`prompt_cancelable`] with a formatted
+/// message. Unlike most prompt macros it returns an `io::Result`, since
+/// cancellation is reported as an error of kind
+/// [`io::ErrorKind::Interrupted`].
+///
+/// # Examples
+///
+/// ```no_run
+/// use io_prompt_prototype::prompt_cancelable;
+///
+/// match prompt_cancelable!("Delete {}? >", "file") {
+///     Ok(answer) => println!("You said {}", answer),
+///     Err(_) => println!("Cancelled"),
+/// }
+/// ```
+#[macro_export]
+macro_rules! prompt_cancelable {
+    ($($arg:tt)*) => {
+        $crate::prompt_cancelable(&format!($($arg)*))
+    };
+}
@@ -0,0 +1,124 @@
+use std::io::{self, Read, Write};
+
+use crate::editor::Editor;
+use crate::input::{interrupted, next_key, Key};
+use crate::{prompt_stream, read_line_trimmed, script, term, trace};
+
+/// Prints to the standard output. Then reads a line of input, completing it
+/// when Tab is pressed.
+///
+/// When Tab is pressed, `completer` is called with the line typed so far and
+/// returns the candidates it could be completed to. If the candidates share a
+/// longer prefix than what was typed, the line is extended to that prefix.
+/// Otherwise, if there is more than one candidate, they are listed below the
+/// prompt. A completer listing directory entries is a natural fit.
+///
+/// The line can be edited as it's typed: the left and right arrows, Home and
+/// End move the cursor, and Backspace and Delete erase the character before
+/// and under it. Completion always applies to the whole line.
+///
+/// The terminal is put into raw mode for the duration of the read. When stdin
+/// is not a terminal completion is disabled, and this behaves exactly like
+/// [`read_line_trimmed`].
+///
+/// # Examples
+///
+/// ```no_run
+/// # fn main() -> std::io::Result<()> {
+/// use io_prompt_prototype::prompt_complete;
+///
+/// let commands = ["build", "bench", "check", "clean"];
+/// let command = prompt_complete("$ cargo ", |line| {
+///     commands
+///         .iter()
+///         .filter(|c| c.starts_with(line))
+///         .map(|c| c.to_string())
+///         .collect()
+/// })?;
+/// # Ok(()) }
+/// ```
+pub fn prompt_complete<F>(msg: &str, completer: F) -> io::Result<String>
+where
+    F: Fn(&str) -> Vec<String>,
+{
+    let mut out = prompt_stream();
+    if !term::stdin_is_tty() {
+        trace::prompt(msg);
+        out.write_all(msg.as_bytes())?;
+        out.flush()?;
+        return trace::read_answer(read_line_trimmed());
+    }
+    let _guard = term::enable_raw()?;
+    prompt_complete_from(&mut script::input(), &mut out, msg, completer)
+}
+
+/// Writes `msg` to a writer. Then reads raw keypresses from a reader,
+/// completing the line when Tab is pressed.
+///
+/// This implements the input handling of [`prompt_complete`] without touching
+/// the terminal mode.
+///
+/// # Examples
+///
+/// ```
+/// # fn main() -> std::io::Result<()> {
+/// use io_prompt_prototype::prompt_complete_from;
+/// use std::io::Cursor;
+///
+/// let completer = |line: &str| vec![format!("{}llo", line)];
+/// let mut output = Vec::new();
+/// let line = prompt_complete_from(&mut Cursor::new(b"he\t\r"), &mut output, "> ", completer)?;
+/// assert_eq!(line, "hello");
+/// # Ok(()) }
+/// ```
+pub fn prompt_complete_from<R, W, F>(
+    reader: &mut R,
+    writer: &mut W,
+    msg: &str,
+    completer: F,
+) -> io::Result<String>
+where
+    R: Read,
+    W: Write,
+    F: Fn(&str) -> Vec<String>,
+{
+    trace::prompt(msg);
+    writer.write_all(msg.as_bytes())?;
+    writer.flush()?;
+    let mut editor = Editor::new(writer, msg);
+    loop {
+        match next_key(reader)? {
+            None | Some(Key::Enter) => break,
+            Some(Key::Eof) if editor.line().is_empty() => break,
+            Some(Key::Interrupt) => return Err(interrupted()),
+            Some(Key::Tab) => {
+                let candidates = completer(editor.line());
+                let prefix = common_prefix(&candidates);
+                if prefix.len() > editor.line().len() {
+                    editor.set_line(prefix.to_string())?;
+                } else if candidates.len() > 1 {
+                    editor.show_below(&candidates.join("  "))?;
+                }
+            }
+            Some(key) => editor.edit(key)?,
+        }
+    }
+    trace::read_answer(editor.finish())
+}
+
+/// Returns the longest prefix shared by all candidates.
+fn common_prefix(candidates: &[String]) -> &str {
+    let (first, rest) = match candidates.split_first() {
+        Some(split) => split,
+        None => return "",
+    };
+    let mut len = first.len();
+    for candidate in rest {
+        len = first
+            .char_indices()
+            .zip(candidate.chars())
+            .find(|((_, a), b)| a != b)
+            .map_or(len.min(candidate.len()), |((i, _), _)| i.min(len));
+    }
+    &first[..len]
+}
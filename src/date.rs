@@ -0,0 +1,92 @@
+use std::convert::TryFrom;
+use std::io::{self, BufRead, Write};
+
+use time::{Date, Month};
+
+use crate::Prompter;
+
+impl<R: BufRead, W: Write> Prompter<R, W> {
+    /// Writes `msg` to the output. Then reads a date, re-prompting until it
+    /// parses and exists in the calendar.
+    ///
+    /// Surrounding whitespace is trimmed, and the date has to be written in
+    /// the ISO 8601 calendar date format `YYYY-MM-DD`: a four digit year, a two
+    /// digit month and a two digit day, separated by dashes, such as
+    /// `2024-05-17`. Other ISO 8601 forms, such as week dates, ordinal dates,
+    /// or dates with a time or a sign, are rejected. The date must exist in
+    /// the proleptic Gregorian calendar, so `2023-02-30` and `2023-13-01` are
+    /// rejected, while `2024-02-29` is accepted since 2024 is a leap year.
+    /// `Please enter a date as YYYY-MM-DD, such as 2024-05-17` is written
+    /// each time an answer is rejected. If the input ends before a valid
+    /// answer is given, an error of kind [`io::ErrorKind::UnexpectedEof`] is
+    /// returned.
+    ///
+    /// This requires the `time` feature.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # fn main() -> std::io::Result<()> {
+    /// use io_prompt_prototype::Prompter;
+    /// use std::io::Cursor;
+    ///
+    /// let input = Cursor::new(b"2023-02-30\n2023-02-28\n");
+    /// let mut prompter = Prompter::new(input, Vec::new());
+    /// let date = prompter.date("Date (YYYY-MM-DD)? >")?;
+    /// assert_eq!(date.to_string(), "2023-02-28");
+    /// # Ok(()) }
+    /// ```
+    pub fn date(&mut self, msg: &str) -> io::Result<Date> {
+        let invalid = self.locale.enter_date;
+        self.prompt_with(msg, |answer| parse_date(answer.trim()).ok_or(invalid))
+    }
+}
+
+/// Parses a `YYYY-MM-DD` date, returning `None` unless it's in the calendar.
+fn parse_date(s: &str) -> Option<Date> {
+    let mut parts = s.split('-');
+    let year = parse_digits(parts.next()?, 4)?;
+    let month = parse_digits(parts.next()?, 2)?;
+    let day = parse_digits(parts.next()?, 2)?;
+    if parts.next().is_some() {
+        return None;
+    }
+    let month = Month::try_from(u8::try_from(month).ok()?).ok()?;
+    Date::from_calendar_date(year as i32, month, u8::try_from(day).ok()?).ok()
+}
+
+/// Parses a number written with exactly `len` ASCII digits.
+fn parse_digits(s: &str, len: usize) -> Option<u32> {
+    if s.len() != len || !s.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    s.parse().ok()
+}
+
+/// Prints to the standard output. Then reads a date written as `YYYY-MM-DD`,
+/// re-prompting until it exists in the calendar.
+///
+/// The date is returned as a [`time::Date`](https://docs.rs/time). See
+/// [`Prompter::date`] for the accepted format. This panics on I/O errors, or
+/// if stdin is closed before a valid date is given.
+///
+/// This requires the `time` feature.
+///
+/// [`Prompter::date`]: crate::Prompter::date
+///
+/// # Examples
+///
+/// ```no_run
+/// use io_prompt_prototype::prompt_date;
+///
+/// let date = prompt_date!("Date (YYYY-MM-DD)? >");
+/// println!("Scheduled for {}", date);
+/// ```
+#[macro_export]
+macro_rules! prompt_date {
+    ($($arg:tt)*) => {
+        $crate::Prompter::stdio()
+            .date(&format!($($arg)*))
+            .unwrap_or_else(|e| panic!("failed prompting on stdin/stdout: {}", e))
+    };
+}
@@ -0,0 +1,90 @@
+use std::io::{self, BufRead};
+
+use crate::{append_line, script, trim_newline};
+
+/// A line of input, along with how reading it ended.
+///
+/// Returned by [`read_line_detailed`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LineRead {
+    /// The line, with its trailing newline removed as by
+    /// [`read_line_trimmed`].
+    ///
+    /// [`read_line_trimmed`]: crate::read_line_trimmed
+    pub line: String,
+    /// The number of bytes read, including the line terminator. Like the count
+    /// returned by [`BufRead::read_line`], this is `0` once the input has
+    /// ended.
+    pub bytes_read: usize,
+    /// Whether the end of the input was reached while reading the line, rather
+    /// than a `\n`. This is the case for a last line which isn't terminated,
+    /// and for a read at the very end of the input, where `line` is empty and
+    /// `bytes_read` is `0`. Any further read returns an empty line.
+    pub hit_eof: bool,
+}
+
+/// Reads a line of input from stdin, reporting how many bytes were read and
+/// whether the input ended.
+///
+/// This combines what [`read_line_trimmed`], [`read_line_into`] and
+/// [`read_line_opt`] each report in part: the trimmed line, the byte count and
+/// whether input has ended. In particular it tells a last line which lacks a
+/// trailing newline apart from a complete one. See [`LineRead`] for the
+/// details of each field.
+///
+/// [`read_line_trimmed`]: crate::read_line_trimmed
+/// [`read_line_into`]: crate::read_line_into
+/// [`read_line_opt`]: crate::read_line_opt
+///
+/// # Examples
+///
+/// ```no_run
+/// # fn main() -> std::io::Result<()> {
+/// use io_prompt_prototype::read_line_detailed;
+///
+/// loop {
+///     let read = read_line_detailed()?;
+///     if read.bytes_read > 0 {
+///         println!("Read: {}", read.line);
+///     }
+///     if read.hit_eof {
+///         break;
+///     }
+/// }
+/// # Ok(()) }
+/// ```
+pub fn read_line_detailed() -> io::Result<LineRead> {
+    read_line_detailed_from(&mut script::input())
+}
+
+/// Reads a line of input from a reader, reporting how many bytes were read and
+/// whether the input ended.
+///
+/// This behaves exactly like [`read_line_detailed`], but reads from an
+/// arbitrary [`BufRead`] rather than from stdin.
+///
+/// # Examples
+///
+/// ```
+/// # fn main() -> std::io::Result<()> {
+/// use io_prompt_prototype::read_line_detailed_from;
+/// use std::io::Cursor;
+///
+/// let mut input = Cursor::new(b"yes\r\nno");
+/// let read = read_line_detailed_from(&mut input)?;
+/// assert_eq!((&*read.line, read.bytes_read, read.hit_eof), ("yes", 5, false));
+/// let read = read_line_detailed_from(&mut input)?;
+/// assert_eq!((&*read.line, read.bytes_read, read.hit_eof), ("no", 2, true));
+/// # Ok(()) }
+/// ```
+pub fn read_line_detailed_from<R: BufRead>(reader: &mut R) -> io::Result<LineRead> {
+    let mut line = String::new();
+    let bytes_read = append_line(reader, &mut line)?;
+    let hit_eof = !line.ends_with('\n');
+    trim_newline(&mut line);
+    Ok(LineRead {
+        line,
+        bytes_read,
+        hit_eof,
+    })
+}
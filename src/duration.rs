@@ -0,0 +1,83 @@
+use std::io::{self, BufRead, Write};
+use std::time::Duration;
+
+use crate::Prompter;
+
+impl<R: BufRead, W: Write> Prompter<R, W> {
+    /// Writes `msg` to the output. Then reads a duration, re-prompting until it
+    /// parses.
+    ///
+    /// A duration is a whole number followed by a unit: `ms` for milliseconds,
+    /// `s` for seconds, `m` for minutes, `h` for hours or `d` for days, such as
+    /// `30s` or `5m`. A bare number without a unit is a number of **seconds**,
+    /// so `90` is a minute and a half. Surrounding whitespace is trimmed, and
+    /// whitespace between the number and the unit is allowed. Fractions and
+    /// combinations like `1h30m` aren't supported. `Please enter a duration,
+    /// such as 30s, 5m or 2h` is written each time an answer is rejected. If
+    /// the input ends before a valid answer is given, an error of kind
+    /// [`io::ErrorKind::UnexpectedEof`] is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # fn main() -> std::io::Result<()> {
+    /// use io_prompt_prototype::Prompter;
+    /// use std::io::Cursor;
+    /// use std::time::Duration;
+    ///
+    /// let input = Cursor::new(b"soon\n5m\n");
+    /// let mut prompter = Prompter::new(input, Vec::new());
+    /// assert_eq!(prompter.duration("Timeout? >")?, Duration::from_secs(300));
+    /// # Ok(()) }
+    /// ```
+    pub fn duration(&mut self, msg: &str) -> io::Result<Duration> {
+        let invalid = self.locale.enter_duration;
+        self.prompt_with(msg, |answer| parse_duration(answer.trim()).ok_or(invalid))
+    }
+}
+
+/// Parses a whole number with an optional unit, which defaults to seconds.
+fn parse_duration(s: &str) -> Option<Duration> {
+    let split = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+    let (number, unit) = s.split_at(split);
+    if number.is_empty() {
+        return None;
+    }
+    let number: u64 = number.parse().ok()?;
+    let secs = match unit.trim_start() {
+        "ms" => return Some(Duration::from_millis(number)),
+        "" | "s" => 1,
+        "m" => 60,
+        "h" => 60 * 60,
+        "d" => 24 * 60 * 60,
+        _ => return None,
+    };
+    number.checked_mul(secs).map(Duration::from_secs)
+}
+
+/// Prints to the standard output. Then reads a duration such as `30s`, `5m`
+/// or `2h`, re-prompting until it parses.
+///
+/// The units `ms`, `s`, `m`, `h` and `d` are accepted, and a bare number is a
+/// number of seconds. See [`Prompter::duration`] for the exact format. This
+/// panics on I/O errors, or if stdin is closed before a valid duration is
+/// given.
+///
+/// [`Prompter::duration`]: crate::Prompter::duration
+///
+/// # Examples
+///
+/// ```no_run
+/// use io_prompt_prototype::prompt_duration;
+///
+/// let timeout = prompt_duration!("Timeout? >");
+/// println!("Waiting for up to {:?}", timeout);
+/// ```
+#[macro_export]
+macro_rules! prompt_duration {
+    ($($arg:tt)*) => {
+        $crate::Prompter::stdio()
+            .duration(&format!($($arg)*))
+            .unwrap_or_else(|e| panic!("failed prompting on stdin/stdout: {}", e))
+    };
+}
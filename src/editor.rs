@@ -0,0 +1,204 @@
+//! A minimal line editor for raw mode input.
+
+use std::io::{self, Write};
+
+use crate::input::Key;
+
+/// The line being edited, along with the writer it's echoed to.
+///
+/// The cursor is a byte offset into the line, always on a character boundary.
+/// It's moved back by writing `\x08`, and forward by writing the characters it
+/// passes over again, which every terminal supports. Each character is assumed
+/// to take up a single column.
+#[derive(Debug)]
+pub(crate) struct Editor<'a, W> {
+    writer: &'a mut W,
+    prompt: &'a str,
+    line: String,
+    cursor: usize,
+    mask: Option<char>,
+    reveal_last: bool,
+    revealed: bool,
+}
+
+impl<'a, W: Write> Editor<'a, W> {
+    /// Creates an editor for a line following `prompt`, which has already been
+    /// written.
+    pub(crate) fn new(writer: &'a mut W, prompt: &'a str) -> Self {
+        Self {
+            writer,
+            prompt,
+            line: String::new(),
+            cursor: 0,
+            mask: None,
+            reveal_last: false,
+            revealed: false,
+        }
+    }
+
+    /// Creates an editor which echoes every character of the line as `mask`.
+    pub(crate) fn masked(writer: &'a mut W, prompt: &'a str, mask: char) -> Self {
+        Self {
+            mask: Some(mask),
+            ..Self::new(writer, prompt)
+        }
+    }
+
+    /// Shows each character typed as is until the next key, before masking
+    /// it like the others.
+    pub(crate) fn reveal_last(self) -> Self {
+        Self {
+            reveal_last: true,
+            ..self
+        }
+    }
+
+    /// Returns the current contents of the line.
+    pub(crate) fn line(&self) -> &str {
+        &self.line
+    }
+
+    /// Applies a key which edits the line or moves the cursor. Other keys are
+    /// ignored.
+    pub(crate) fn edit(&mut self, key: Key) -> io::Result<()> {
+        self.conceal()?;
+        match key {
+            Key::Char(c) => {
+                self.line.insert(self.cursor, c);
+                let start = self.cursor;
+                self.cursor += c.len_utf8();
+                self.revealed = self.reveal_last && self.mask.is_some();
+                self.write_from(start)?;
+                self.move_back(self.tail_width())?;
+            }
+            Key::Backspace => match self.line[..self.cursor].chars().next_back() {
+                Some(c) => {
+                    self.cursor -= c.len_utf8();
+                    self.line.remove(self.cursor);
+                    self.writer.write_all(b"\x08")?;
+                    self.erase_tail()?;
+                }
+                None => return Ok(()),
+            },
+            Key::Delete => {
+                if self.cursor == self.line.len() {
+                    return Ok(());
+                }
+                self.line.remove(self.cursor);
+                self.erase_tail()?;
+            }
+            Key::Left => match self.line[..self.cursor].chars().next_back() {
+                Some(c) => {
+                    self.cursor -= c.len_utf8();
+                    self.move_back(1)?;
+                }
+                None => return Ok(()),
+            },
+            Key::Right => match self.line[self.cursor..].chars().next() {
+                Some(c) => {
+                    let start = self.cursor;
+                    self.cursor += c.len_utf8();
+                    self.write_range(start, self.cursor)?;
+                }
+                None => return Ok(()),
+            },
+            Key::Home => {
+                let width = self.line[..self.cursor].chars().count();
+                self.cursor = 0;
+                self.move_back(width)?;
+            }
+            Key::End => {
+                let start = self.cursor;
+                self.cursor = self.line.len();
+                self.write_from(start)?;
+            }
+            _ => return Ok(()),
+        }
+        self.writer.flush()
+    }
+
+    /// Replaces the contents of the line, redrawing it with the cursor at the
+    /// end.
+    pub(crate) fn set_line(&mut self, line: String) -> io::Result<()> {
+        self.line = line;
+        self.cursor = self.line.len();
+        write!(self.writer, "\r{}", self.prompt)?;
+        self.write_from(0)?;
+        self.writer.write_all(b"\x1b[K")?;
+        self.writer.flush()
+    }
+
+    /// Writes `text` on its own line, then redraws the prompt and line below it.
+    pub(crate) fn show_below(&mut self, text: &str) -> io::Result<()> {
+        write!(self.writer, "\r\n{}\r\n{}", text, self.prompt)?;
+        self.write_from(0)?;
+        self.move_back(self.tail_width())?;
+        self.writer.flush()
+    }
+
+    /// Masks the character revealed before the cursor, if any.
+    pub(crate) fn conceal(&mut self) -> io::Result<()> {
+        if !self.revealed {
+            return Ok(());
+        }
+        self.revealed = false;
+        self.move_back(1)?;
+        let start = self.line[..self.cursor]
+            .char_indices()
+            .next_back()
+            .map_or(0, |(i, _)| i);
+        self.write_range(start, self.cursor)?;
+        self.writer.flush()
+    }
+
+    /// Moves to the next line, returning the edited line.
+    pub(crate) fn finish(mut self) -> io::Result<String> {
+        self.conceal()?;
+        self.writer.write_all(b"\r\n")?;
+        self.writer.flush()?;
+        Ok(self.line)
+    }
+
+    /// Returns the number of characters after the cursor.
+    fn tail_width(&self) -> usize {
+        self.line[self.cursor..].chars().count()
+    }
+
+    /// Redraws the line from the cursor onwards after a character before the
+    /// cursor was removed, blanking out the column it leaves behind.
+    fn erase_tail(&mut self) -> io::Result<()> {
+        self.write_from(self.cursor)?;
+        self.writer.write_all(b" ")?;
+        self.move_back(self.tail_width() + 1)
+    }
+
+    /// Writes the line from byte offset `start` to the end.
+    fn write_from(&mut self, start: usize) -> io::Result<()> {
+        self.write_range(start, self.line.len())
+    }
+
+    /// Writes the characters of the line between two byte offsets, or the mask
+    /// for each of them. A revealed character before the cursor is written as
+    /// is.
+    fn write_range(&mut self, start: usize, end: usize) -> io::Result<()> {
+        let text = &self.line[start..end];
+        let mask = match self.mask {
+            Some(mask) => mask,
+            None => return self.writer.write_all(text.as_bytes()),
+        };
+        let revealed = match self.line[..self.cursor].char_indices().next_back() {
+            Some((i, _)) if self.revealed => Some(i),
+            _ => None,
+        };
+        let masked: String = text
+            .char_indices()
+            .map(|(i, c)| if Some(start + i) == revealed { c } else { mask })
+            .collect();
+        self.writer.write_all(masked.as_bytes())
+    }
+
+    /// Moves the cursor back by `width` columns.
+    fn move_back(&mut self, width: usize) -> io::Result<()> {
+        self.writer.write_all(&b"\x08".repeat(width))
+    }
+}
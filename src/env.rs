@@ -0,0 +1,118 @@
+use std::env::{self, VarError};
+use std::io::{self, Write};
+
+use crate::{prompt_stream, read_password, trace, Prompter};
+
+/// Returns the value of the environment variable `var`, or prompts for it if
+/// it's unset or empty.
+///
+/// This suits configuration which is normally provided through the
+/// environment, but which a developer running the program by hand may not
+/// have set up. The sources are tried in order:
+///
+/// 1. If `var` is set to a non-empty value, that value is returned as is.
+///    Nothing is printed, and nothing is read from stdin. This is the case
+///    whether or not stdin is a terminal, and during a scripted session too.
+/// 2. Otherwise `msg` is printed, and a line is read from stdin with its
+///    newline removed, as with [`Prompter::prompt`]. The answer is returned
+///    even if it's empty, and isn't stored in the environment.
+///
+/// Unlike [`prompt_secret_env_fallback`], which only looks at the environment
+/// when stdin isn't a terminal, the environment always takes precedence here.
+/// If `var` is set but isn't valid unicode, an error of kind
+/// [`io::ErrorKind::InvalidData`] is returned rather than prompting. If the
+/// input ends before a line is read, an error of kind
+/// [`io::ErrorKind::UnexpectedEof`] is returned.
+///
+/// [`prompt_secret_env_fallback`]: crate::prompt_secret_env_fallback()
+///
+/// # Examples
+///
+/// ```no_run
+/// # fn main() -> std::io::Result<()> {
+/// use io_prompt_prototype::prompt_env_or;
+///
+/// let region = prompt_env_or("AWS_REGION", "Region? >")?;
+/// # Ok(()) }
+/// ```
+pub fn prompt_env_or(var: &str, msg: &str) -> io::Result<String> {
+    match env_value(var)? {
+        Some(value) => Ok(value),
+        None => Prompter::stdio().ask(msg),
+    }
+}
+
+/// Returns the value of the environment variable `var`, or prompts for it
+/// without echoing the answer if it's unset or empty.
+///
+/// This behaves like [`prompt_env_or`](fn@prompt_env_or), except that the
+/// answer is read with [`read_password`], which suits secrets such as API
+/// keys.
+///
+/// # Examples
+///
+/// ```no_run
+/// # fn main() -> std::io::Result<()> {
+/// use io_prompt_prototype::prompt_env_or_hidden;
+///
+/// let api_key = prompt_env_or_hidden("API_KEY", "API key? >")?;
+/// # Ok(()) }
+/// ```
+pub fn prompt_env_or_hidden(var: &str, msg: &str) -> io::Result<String> {
+    if let Some(value) = env_value(var)? {
+        return Ok(value);
+    }
+    trace::prompt(msg);
+    let mut out = prompt_stream();
+    out.write_all(msg.as_bytes())?;
+    out.flush()?;
+    trace::read_secret(read_password())
+}
+
+/// Returns the value of the environment variable `var`, or `None` if it's
+/// unset or empty.
+fn env_value(var: &str) -> io::Result<Option<String>> {
+    match env::var(var) {
+        Ok(value) if !value.is_empty() => Ok(Some(value)),
+        Ok(_) | Err(VarError::NotPresent) => Ok(None),
+        Err(VarError::NotUnicode(_)) => Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("the `{}` environment variable is not valid unicode", var),
+        )),
+    }
+}
+
+/// Returns the value of an environment variable, or prompts for it if it's
+/// unset or empty.
+///
+/// The environment variable is preferred even when stdin is a terminal, so
+/// setting it skips the prompt. The answer is echoed as it's typed, unless
+/// `hidden = true` is passed, in which case it's read like a password. See
+/// [`prompt_env_or`] for the precedence, and for how the answer is read. This
+/// panics on I/O errors, if the variable isn't valid unicode, or if stdin is
+/// closed before an answer is given.
+///
+/// [`prompt_env_or`]: fn@crate::prompt_env_or
+///
+/// # Examples
+///
+/// ```no_run
+/// use io_prompt_prototype::prompt_env_or;
+///
+/// let user = prompt_env_or!("DB_USER", "Database user? >");
+/// let api_key = prompt_env_or!("API_KEY", "API key? >", hidden = true);
+/// ```
+#[macro_export]
+macro_rules! prompt_env_or {
+    ($var:expr, $msg:expr, hidden = $hidden:expr $(,)?) => {
+        if $hidden {
+            $crate::prompt_env_or_hidden(&$var, &$msg)
+        } else {
+            $crate::prompt_env_or(&$var, &$msg)
+        }
+        .unwrap_or_else(|e| panic!("failed prompting on stdin/stdout: {}", e))
+    };
+    ($var:expr, $msg:expr $(,)?) => {
+        $crate::prompt_env_or!($var, $msg, hidden = false)
+    };
+}
@@ -0,0 +1,155 @@
+use std::error::Error;
+use std::fmt;
+use std::io;
+
+/// The error returned when a re-prompting loop runs out of retries.
+///
+/// It's returned wrapped in an [`io::Error`] of kind
+/// [`io::ErrorKind::InvalidInput`], and can be recovered from it with
+/// [`io::Error::get_ref`] and `downcast_ref`.
+///
+/// # Examples
+///
+/// ```
+/// use io_prompt_prototype::{Prompter, RetriesExhausted};
+/// use std::io::Cursor;
+///
+/// let mut prompter = Prompter::new(Cursor::new(b"\n \n"), Vec::new()).max_retries(1);
+/// let err = prompter.prompt_until("Name? >", |s| !s.trim().is_empty(), None).unwrap_err();
+/// let err = err.get_ref().unwrap().downcast_ref::<RetriesExhausted>().unwrap();
+/// assert_eq!(err.attempts(), 2);
+/// assert_eq!(err.last_answer(), " ");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RetriesExhausted {
+    attempts: usize,
+    last_answer: String,
+}
+
+impl RetriesExhausted {
+    pub(crate) fn new(attempts: usize, last_answer: &str) -> Self {
+        Self {
+            attempts,
+            last_answer: last_answer.to_string(),
+        }
+    }
+
+    /// Returns how many answers were given, including the first one.
+    pub fn attempts(&self) -> usize {
+        self.attempts
+    }
+
+    /// Returns the last answer given, which was invalid.
+    pub fn last_answer(&self) -> &str {
+        &self.last_answer
+    }
+}
+
+impl fmt::Display for RetriesExhausted {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "no valid answer was given after {} attempts, the last answer was {:?}",
+            self.attempts, self.last_answer
+        )
+    }
+}
+
+impl Error for RetriesExhausted {}
+
+impl From<RetriesExhausted> for io::Error {
+    fn from(err: RetriesExhausted) -> Self {
+        io::Error::new(io::ErrorKind::InvalidInput, err)
+    }
+}
+
+/// The error returned when an answer can't be parsed, along with the answer
+/// itself.
+///
+/// The error returned by [`FromStr`](std::str::FromStr) rarely mentions the
+/// string it failed to parse, so it's bundled here with the answer that was
+/// given, and how many answers were read, so that a message shown to the user
+/// can quote what they typed.
+///
+/// It's returned by [`prompt_parse!`] and [`Prompter::parse`], which read a
+/// single answer. The re-prompting [`Prompter::parse_loop`] and
+/// [`Prompter::number`], and the macros built on them such as
+/// [`prompt_number!`], return it once their retries run out, holding the last
+/// answer. It's then wrapped in an [`io::Error`] of kind
+/// [`io::ErrorKind::InvalidInput`], like a [`RetriesExhausted`].
+///
+/// [`prompt_parse!`]: crate::prompt_parse!
+/// [`prompt_number!`]: crate::prompt_number!
+/// [`Prompter::parse`]: crate::Prompter::parse
+/// [`Prompter::parse_loop`]: crate::Prompter::parse_loop
+/// [`Prompter::number`]: crate::Prompter::number
+///
+/// # Examples
+///
+/// ```
+/// use io_prompt_prototype::{PromptParseError, Prompter};
+/// use std::io::Cursor;
+/// use std::num::ParseIntError;
+///
+/// let mut prompter = Prompter::new(Cursor::new(b"forty-two\n"), Vec::new());
+/// let err = prompter.parse::<u8>("Age? >").unwrap().unwrap_err();
+/// assert_eq!(err.input(), "forty-two");
+/// assert_eq!(err.attempts(), 1);
+/// assert_eq!(
+///     err.to_string(),
+///     "invalid answer \"forty-two\": invalid digit found in string"
+/// );
+///
+/// let input = Cursor::new(b"old\nolder\n");
+/// let mut prompter = Prompter::new(input, Vec::new()).max_retries(1);
+/// let err = prompter.number::<u8>("Age? >").unwrap_err();
+/// let err = err.get_ref().unwrap().downcast_ref::<PromptParseError<ParseIntError>>();
+/// assert_eq!(err.unwrap().input(), "older");
+/// assert_eq!(err.unwrap().attempts(), 2);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PromptParseError<E> {
+    input: String,
+    attempts: usize,
+    error: E,
+}
+
+impl<E> PromptParseError<E> {
+    pub(crate) fn new(input: String, attempts: usize, error: E) -> Self {
+        Self {
+            input,
+            attempts,
+            error,
+        }
+    }
+
+    /// Returns the answer which couldn't be parsed, with its trailing newline
+    /// removed.
+    pub fn input(&self) -> &str {
+        &self.input
+    }
+
+    /// Returns how many answers were read, including the one which couldn't
+    /// be parsed.
+    pub fn attempts(&self) -> usize {
+        self.attempts
+    }
+
+    /// Returns the error returned by the parser.
+    pub fn error(&self) -> &E {
+        &self.error
+    }
+
+    /// Consumes the error, returning the error returned by the parser.
+    pub fn into_error(self) -> E {
+        self.error
+    }
+}
+
+impl<E: fmt::Display> fmt::Display for PromptParseError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid answer {:?}: {}", self.input, self.error)
+    }
+}
+
+impl<E: fmt::Debug + fmt::Display> Error for PromptParseError<E> {}
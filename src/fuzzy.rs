@@ -0,0 +1,160 @@
+use std::fmt::Display;
+use std::io::{self, BufRead, Write};
+
+use crate::locale::fill;
+use crate::Prompter;
+
+/// How many candidates are listed when a query is ambiguous.
+const MAX_CANDIDATES: usize = 5;
+
+/// How closely an option matches a query, from weakest to strongest.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Tier {
+    /// The characters of the query appear in the option in order, with other
+    /// characters in between.
+    Subsequence,
+    /// The query appears in the option as is.
+    Substring,
+    /// The query is the whole option.
+    Exact,
+}
+
+impl<R: BufRead, W: Write> Prompter<R, W> {
+    /// Writes `msg` to the output. Then reads a query after a `> ` prompt, and
+    /// selects the option it matches, re-prompting until it matches a single
+    /// one.
+    ///
+    /// This suits lists which are too long for [`Prompter::select`]'s numbered
+    /// menu: instead of a number, the user types part of an option. Queries
+    /// are matched ignoring case and surrounding whitespace against each
+    /// option's `Display` output, and each option matches in one of three
+    /// tiers, from strongest to weakest:
+    ///
+    /// 1. the query is the whole option,
+    /// 2. the query appears in the option, as `west` does in `eu-west-1`,
+    /// 3. the characters of the query appear in the option in order, as `euw1`
+    ///    does in `eu-west-1`.
+    ///
+    /// Only the options in the strongest tier any option matches in are
+    /// considered. If there's exactly one, its zero-based index is returned.
+    /// Otherwise the user is asked again: if nothing matches, `No option
+    /// matches <query>` is written, and if several options do, `Several
+    /// options match:` is written, followed by up to five of them, shortest
+    /// first, each on its own line after `  - `. An empty query matches
+    /// every option. If the input ends before an option is selected, or
+    /// `options` is empty, an error is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # fn main() -> std::io::Result<()> {
+    /// use io_prompt_prototype::Prompter;
+    /// use std::io::Cursor;
+    ///
+    /// let regions = ["us-east-1", "us-west-1", "eu-west-1", "eu-central-1"];
+    /// let mut prompter = Prompter::new(Cursor::new(b"west\neuw\n"), Vec::new());
+    /// let index = prompter.select_fuzzy("Pick a region:", &regions)?;
+    /// assert_eq!(regions[index], "eu-west-1");
+    ///
+    /// let (_, output) = prompter.into_parts();
+    /// assert_eq!(
+    ///     String::from_utf8(output).unwrap(),
+    ///     "Pick a region:\n> Several options match:\n  - us-west-1\n  - eu-west-1\n> ",
+    /// );
+    /// # Ok(()) }
+    /// ```
+    pub fn select_fuzzy<T: Display>(&mut self, msg: &str, options: &[T]) -> io::Result<usize> {
+        if options.is_empty() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "no options to select from",
+            ));
+        }
+        let labels: Vec<String> = options
+            .iter()
+            .map(|option| option.to_string().to_lowercase())
+            .collect();
+        writeln!(self.writer, "{}", msg)?;
+        let mut retries = 0;
+        loop {
+            let answer = self.ask("> ")?;
+            let query = answer.trim().to_lowercase();
+            let matches = best_matches(&labels, &query);
+            if let [index] = matches[..] {
+                return Ok(index);
+            }
+            if matches.is_empty() {
+                writeln!(
+                    self.writer,
+                    "{}",
+                    fill(self.locale.no_option_matches, answer.trim())
+                )?;
+            } else {
+                writeln!(self.writer, "{}", self.locale.several_options_match)?;
+                for &index in matches.iter().take(MAX_CANDIDATES) {
+                    writeln!(self.writer, "  - {}", options[index])?;
+                }
+            }
+            self.retry(&mut retries, &answer)?;
+        }
+    }
+}
+
+/// Returns the indices of the labels in the strongest tier `query` matches
+/// in, shortest label first, keeping the original order among labels of the
+/// same length.
+fn best_matches(labels: &[String], query: &str) -> Vec<usize> {
+    let tiers: Vec<_> = labels.iter().map(|label| tier(label, query)).collect();
+    let best = match tiers.iter().flatten().max() {
+        Some(best) => *best,
+        None => return Vec::new(),
+    };
+    let mut matches: Vec<_> = (0..labels.len())
+        .filter(|&i| tiers[i] == Some(best))
+        .collect();
+    matches.sort_by_key(|&i| labels[i].chars().count());
+    matches
+}
+
+/// Returns how closely `label` matches `query`, if at all.
+fn tier(label: &str, query: &str) -> Option<Tier> {
+    if label == query {
+        return Some(Tier::Exact);
+    }
+    if label.contains(query) {
+        return Some(Tier::Substring);
+    }
+    let mut chars = label.chars();
+    if query.chars().all(|q| chars.any(|c| c == q)) {
+        return Some(Tier::Subsequence);
+    }
+    None
+}
+
+/// Prints to the standard output. Then reads a query, and selects the option
+/// it matches, re-prompting until it matches a single one.
+///
+/// Returns the zero-based index of the selected option. Typing any part of an
+/// option selects it, as long as no other option matches as well. See
+/// [`Prompter::select_fuzzy`] for how queries are matched. This panics on I/O
+/// errors, or if stdin is closed before an option is selected.
+///
+/// [`Prompter::select_fuzzy`]: crate::Prompter::select_fuzzy
+///
+/// # Examples
+///
+/// ```no_run
+/// use io_prompt_prototype::select_fuzzy;
+///
+/// let timezones = ["Europe/Amsterdam", "Europe/Berlin", "America/New_York"];
+/// let index = select_fuzzy!("Pick a timezone:", &timezones);
+/// println!("Using {}", timezones[index]);
+/// ```
+#[macro_export]
+macro_rules! select_fuzzy {
+    ($msg:expr, $options:expr $(,)?) => {
+        $crate::Prompter::stdio()
+            .select_fuzzy(&$msg, $options)
+            .unwrap_or_else(|e| panic!("failed prompting on stdin/stdout: {}", e))
+    };
+}
@@ -0,0 +1,154 @@
+use std::io::{self, BufRead, Write};
+
+use crate::editor::Editor;
+use crate::input::{interrupted, next_key, Key};
+use crate::script::{self, Input};
+use crate::{prompt_stream, read_line_trimmed_from, term, trace, Stream};
+
+/// A prompt which remembers previous answers, and lets the user recall them
+/// with the up and down arrows.
+///
+/// Reading keypresses one at a time requires the terminal to be in raw mode.
+/// [`HistoryPrompter::stdio`] takes care of this when stdin is a terminal, and
+/// falls back to reading whole lines otherwise. A `HistoryPrompter` created
+/// with [`HistoryPrompter::new`] reads keypresses from the reader as a terminal
+/// in raw mode would deliver them, and echoes the line being edited to the
+/// writer.
+///
+/// # Examples
+///
+/// ```
+/// # fn main() -> std::io::Result<()> {
+/// use io_prompt_prototype::HistoryPrompter;
+/// use std::io::Cursor;
+///
+/// // Answer "ls", then press the up arrow and Enter.
+/// let input = Cursor::new(b"ls\r\x1b[A\r");
+/// let mut prompter = HistoryPrompter::new(input, Vec::new());
+/// assert_eq!(prompter.prompt("$ ")?, "ls");
+/// assert_eq!(prompter.prompt("$ ")?, "ls");
+/// assert_eq!(prompter.history(), ["ls", "ls"]);
+/// # Ok(()) }
+/// ```
+#[derive(Debug)]
+pub struct HistoryPrompter<R, W> {
+    reader: R,
+    writer: W,
+    mode: Mode,
+    history: Vec<String>,
+}
+
+/// How a `HistoryPrompter` reads its input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Mode {
+    /// Read keypresses from the reader.
+    Keys,
+    /// Put stdin into raw mode, then read keypresses from the reader.
+    RawKeys,
+    /// Read whole lines from the reader.
+    Lines,
+}
+
+impl HistoryPrompter<Input, Stream> {
+    /// Creates a new `HistoryPrompter` which reads from stdin and writes to
+    /// stdout.
+    ///
+    /// If stdin is not a terminal, answers are read a line at a time and
+    /// history recall is unavailable.
+    pub fn stdio() -> Self {
+        let mode = if term::stdin_is_tty() {
+            Mode::RawKeys
+        } else {
+            Mode::Lines
+        };
+        Self {
+            mode,
+            ..Self::new(script::input(), prompt_stream())
+        }
+    }
+}
+
+impl<R: BufRead, W: Write> HistoryPrompter<R, W> {
+    /// Creates a new `HistoryPrompter` from a reader and a writer.
+    pub fn new(reader: R, writer: W) -> Self {
+        Self {
+            reader,
+            writer,
+            mode: Mode::Keys,
+            history: Vec::new(),
+        }
+    }
+
+    /// Writes `msg` to the output. Then reads a line of input, allowing
+    /// earlier answers to be recalled.
+    ///
+    /// The up arrow replaces the line with the previous answer, and the down
+    /// arrow with the next one. Moving down past the newest answer restores
+    /// the line as it was before recalling. Any line can be edited further as
+    /// in [`prompt_complete`]. The final line is added to the history, unless
+    /// it's empty.
+    ///
+    /// Pressing Ctrl-C returns an error of kind [`io::ErrorKind::Interrupted`].
+    ///
+    /// [`prompt_complete`]: crate::prompt_complete
+    pub fn prompt(&mut self, msg: &str) -> io::Result<String> {
+        trace::prompt(msg);
+        self.writer.write_all(msg.as_bytes())?;
+        self.writer.flush()?;
+        let line = match self.mode {
+            Mode::Lines => read_line_trimmed_from(&mut self.reader)?,
+            Mode::RawKeys => {
+                let _guard = term::enable_raw()?;
+                self.read_keys(msg)?
+            }
+            Mode::Keys => self.read_keys(msg)?,
+        };
+        trace::answer(&line);
+        if !line.is_empty() {
+            self.history.push(line.clone());
+        }
+        Ok(line)
+    }
+
+    /// Returns the answers given so far, oldest first.
+    pub fn history(&self) -> &[String] {
+        &self.history
+    }
+
+    /// Consumes the `HistoryPrompter`, returning the underlying reader and
+    /// writer.
+    pub fn into_parts(self) -> (R, W) {
+        (self.reader, self.writer)
+    }
+
+    /// Reads keypresses until Enter is pressed, handling history recall.
+    fn read_keys(&mut self, msg: &str) -> io::Result<String> {
+        let mut editor = Editor::new(&mut self.writer, msg);
+        // The entry currently shown, and the line as it was before recalling.
+        let mut index = self.history.len();
+        let mut draft = String::new();
+        loop {
+            match next_key(&mut self.reader)? {
+                None | Some(Key::Enter) => break,
+                Some(Key::Eof) if editor.line().is_empty() => break,
+                Some(Key::Interrupt) => return Err(interrupted()),
+                Some(Key::Up) if index > 0 => {
+                    if index == self.history.len() {
+                        draft = editor.line().to_string();
+                    }
+                    index -= 1;
+                    editor.set_line(self.history[index].clone())?;
+                }
+                Some(Key::Down) if index < self.history.len() => {
+                    index += 1;
+                    match self.history.get(index) {
+                        Some(entry) => editor.set_line(entry.clone())?,
+                        None => editor.set_line(std::mem::take(&mut draft))?,
+                    }
+                }
+                Some(key) => editor.edit(key)?,
+            }
+        }
+        editor.finish()
+    }
+}
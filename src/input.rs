@@ -0,0 +1,145 @@
+//! Decoding of keypresses read from a terminal in raw mode.
+
+use std::io::{self, Read};
+
+/// A single keypress.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Key {
+    /// A printable character.
+    Char(char),
+    /// Enter or Return.
+    Enter,
+    /// Tab.
+    Tab,
+    /// Backspace.
+    Backspace,
+    /// Delete.
+    Delete,
+    /// The left arrow.
+    Left,
+    /// The right arrow.
+    Right,
+    /// The up arrow.
+    Up,
+    /// The down arrow.
+    Down,
+    /// Home.
+    Home,
+    /// End.
+    End,
+    /// Ctrl-C.
+    Interrupt,
+    /// Ctrl-D.
+    Eof,
+    /// Any other control character or escape sequence.
+    Unknown,
+}
+
+/// Reads the next keypress, returning `None` at the end of the input.
+pub(crate) fn next_key<R: Read>(reader: &mut R) -> io::Result<Option<Key>> {
+    let c = match next_char(reader)? {
+        Some(c) => c,
+        None => return Ok(None),
+    };
+    let key = match c {
+        '\r' | '\n' => Key::Enter,
+        '\t' => Key::Tab,
+        '\x7f' | '\x08' => Key::Backspace,
+        '\x03' => Key::Interrupt,
+        '\x04' => Key::Eof,
+        '\x1b' => escape_sequence(reader)?,
+        c if c.is_control() => Key::Unknown,
+        c => Key::Char(c),
+    };
+    Ok(Some(key))
+}
+
+/// Decodes the remainder of an escape sequence, after the leading `ESC`.
+fn escape_sequence<R: Read>(reader: &mut R) -> io::Result<Key> {
+    match next_byte(reader)? {
+        Some(b'[') => {}
+        Some(b'O') => {
+            return Ok(match next_byte(reader)? {
+                Some(b'A') => Key::Up,
+                Some(b'B') => Key::Down,
+                Some(b'C') => Key::Right,
+                Some(b'D') => Key::Left,
+                Some(b'H') => Key::Home,
+                Some(b'F') => Key::End,
+                _ => Key::Unknown,
+            })
+        }
+        _ => return Ok(Key::Unknown),
+    }
+
+    // A control sequence: parameter bytes followed by a single final byte.
+    let mut param = Vec::new();
+    let last = loop {
+        match next_byte(reader)? {
+            Some(b @ 0x40..=0x7e) => break b,
+            Some(b) => param.push(b),
+            None => return Ok(Key::Unknown),
+        }
+    };
+    Ok(match (last, param.as_slice()) {
+        (b'A', _) => Key::Up,
+        (b'B', _) => Key::Down,
+        (b'C', _) => Key::Right,
+        (b'D', _) => Key::Left,
+        (b'H', _) => Key::Home,
+        (b'F', _) => Key::End,
+        (b'~', b"1") | (b'~', b"7") => Key::Home,
+        (b'~', b"4") | (b'~', b"8") => Key::End,
+        (b'~', b"3") => Key::Delete,
+        _ => Key::Unknown,
+    })
+}
+
+/// Reads a single UTF-8 encoded character, returning `None` at the end of the
+/// input.
+pub(crate) fn next_char<R: Read>(reader: &mut R) -> io::Result<Option<char>> {
+    let mut buf = [0; 4];
+    buf[0] = match next_byte(reader)? {
+        Some(b) => b,
+        None => return Ok(None),
+    };
+    let len = match buf[0] {
+        0x00..=0x7f => 1,
+        0xc2..=0xdf => 2,
+        0xe0..=0xef => 3,
+        0xf0..=0xf4 => 4,
+        _ => return Err(invalid_utf8()),
+    };
+    for b in &mut buf[1..len] {
+        *b = next_byte(reader)?.ok_or_else(invalid_utf8)?;
+    }
+    match std::str::from_utf8(&buf[..len]) {
+        Ok(s) => Ok(s.chars().next()),
+        Err(_) => Err(invalid_utf8()),
+    }
+}
+
+/// Reads a single byte, retrying if the read was interrupted.
+fn next_byte<R: Read>(reader: &mut R) -> io::Result<Option<u8>> {
+    let mut byte = 0;
+    loop {
+        match reader.read(std::slice::from_mut(&mut byte)) {
+            Ok(0) => return Ok(None),
+            Ok(_) => return Ok(Some(byte)),
+            Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+            Err(err) => return Err(err),
+        }
+    }
+}
+
+/// The error returned when a raw mode read is aborted with Ctrl-C.
+pub(crate) fn interrupted() -> io::Error {
+    io::Error::new(io::ErrorKind::Interrupted, "read interrupted by Ctrl-C")
+}
+
+fn invalid_utf8() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        "stream did not contain valid UTF-8",
+    )
+}
@@ -0,0 +1,95 @@
+use std::io::{self, BufRead, Write};
+
+use serde::de::DeserializeOwned;
+
+use crate::locale::fill;
+use crate::{read_line_opt_from, trace, Prompter};
+
+impl<R: BufRead, W: Write> Prompter<R, W> {
+    /// Writes `msg` to the output. Then reads a JSON value and deserializes
+    /// it, re-prompting until it deserializes.
+    ///
+    /// A full JSON value is read, which may span several lines: as long as
+    /// the lines read so far are an incomplete value, such as an object whose
+    /// closing brace hasn't been entered yet, another line is read without
+    /// writing `msg` again. This makes it possible to paste formatted JSON.
+    /// Once the value is complete it must deserialize into `T`, and nothing
+    /// but whitespace may follow it on its last line. Otherwise the error is
+    /// written, with a newline, and `msg` is written again. If the input ends
+    /// before a valid value is given, an error of kind
+    /// [`io::ErrorKind::UnexpectedEof`] is returned.
+    ///
+    /// This requires the `json` feature.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # fn main() -> std::io::Result<()> {
+    /// use io_prompt_prototype::Prompter;
+    /// use std::collections::HashMap;
+    /// use std::io::Cursor;
+    ///
+    /// let input = Cursor::new(b"{\"port\": 80,\n \"workers\": 4}\n");
+    /// let mut prompter = Prompter::new(input, Vec::new());
+    /// let config: HashMap<String, u32> = prompter.json("Config JSON? >")?;
+    /// assert_eq!(config["workers"], 4);
+    /// # Ok(()) }
+    /// ```
+    pub fn json<T: DeserializeOwned>(&mut self, msg: &str) -> io::Result<T> {
+        let mut retries = 0;
+        loop {
+            let mut text = self.ask(msg)?;
+            let err = loop {
+                match serde_json::from_str(&text) {
+                    Ok(value) => return Ok(value),
+                    Err(err) if err.is_eof() => {
+                        match trace::read_answer(read_line_opt_from(&mut self.reader))? {
+                            Some(line) => {
+                                self.log_answer(&line)?;
+                                text.push('\n');
+                                text.push_str(&line);
+                            }
+                            None => break err,
+                        }
+                    }
+                    Err(err) => break err,
+                }
+            };
+            writeln!(
+                self.writer,
+                "{}",
+                fill(self.locale.invalid_json, &err.to_string())
+            )?;
+            self.retry(&mut retries, &text)?;
+        }
+    }
+}
+
+/// Prints to the standard output. Then reads a JSON value, which may span
+/// several lines, and deserializes it, re-prompting until it deserializes.
+///
+/// The type to deserialize into is inferred, and must implement
+/// `serde::de::DeserializeOwned`. See [`Prompter::json`] for how the lines
+/// making up the value are read. This panics on I/O errors, or if stdin is
+/// closed before a valid value is given.
+///
+/// This requires the `json` feature.
+///
+/// [`Prompter::json`]: crate::Prompter::json
+///
+/// # Examples
+///
+/// ```no_run
+/// use io_prompt_prototype::prompt_json;
+///
+/// let tags: Vec<String> = prompt_json!("Tags, as a JSON array? >");
+/// println!("Tagged with {}", tags.join(", "));
+/// ```
+#[macro_export]
+macro_rules! prompt_json {
+    ($($arg:tt)*) => {
+        $crate::Prompter::stdio()
+            .json(&format!($($arg)*))
+            .unwrap_or_else(|e| panic!("failed prompting on stdin/stdout: {}", e))
+    };
+}
@@ -0,0 +1,95 @@
+use std::io::{self, Read};
+
+use crate::input::{interrupted, next_char};
+use crate::{read_line, script, term};
+
+/// Reads a single character from stdin, without waiting for Enter.
+///
+/// The terminal is put into raw mode for the duration of the read, so the
+/// character is returned as soon as its key is pressed, and it isn't echoed.
+/// Characters encoded in several UTF-8 bytes are read in full. Pressing Enter
+/// returns `'\r'`, and Ctrl-C aborts the read with an error of kind
+/// [`io::ErrorKind::Interrupted`]. The original terminal mode is restored
+/// afterwards.
+///
+/// When stdin is not a terminal a whole line is read instead, and its first
+/// character is returned. An empty line returns `'\n'`.
+///
+/// # Examples
+///
+/// ```no_run
+/// # fn main() -> std::io::Result<()> {
+/// use io_prompt_prototype::read_char;
+///
+/// println!("Press any key to continue");
+/// read_char()?;
+/// # Ok(()) }
+/// ```
+pub fn read_char() -> io::Result<char> {
+    if !term::stdin_is_tty() {
+        return read_line()?.chars().next().ok_or_else(unexpected_eof);
+    }
+    let _guard = term::enable_raw()?;
+    read_char_from(&mut script::input())
+}
+
+/// Reads a single character from a reader.
+///
+/// This implements the input handling of [`read_char`] without touching the
+/// terminal mode. If the input has ended, an error of kind
+/// [`io::ErrorKind::UnexpectedEof`] is returned.
+///
+/// # Examples
+///
+/// ```
+/// # fn main() -> std::io::Result<()> {
+/// use io_prompt_prototype::read_char_from;
+/// use std::io::Cursor;
+///
+/// let mut input = Cursor::new("yes");
+/// assert_eq!(read_char_from(&mut input)?, 'y');
+/// assert_eq!(read_char_from(&mut input)?, 'e');
+/// # Ok(()) }
+/// ```
+pub fn read_char_from<R: Read>(reader: &mut R) -> io::Result<char> {
+    match next_char(reader)? {
+        Some('\x03') => Err(interrupted()),
+        Some(c) => Ok(c),
+        None => Err(unexpected_eof()),
+    }
+}
+
+fn unexpected_eof() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::UnexpectedEof,
+        "input ended before a character was read",
+    )
+}
+
+/// Prints to the standard output. Then reads a single character, without
+/// waiting for Enter.
+///
+/// This is a shorthand for calling [`print!`] and [`read_char`].
+///
+/// # Examples
+///
+/// ```no_run
+/// use io_prompt_prototype::prompt_char;
+///
+/// let answer = prompt_char!("Continue? (y/n) >");
+/// if answer == 'y' {
+///     println!("Continuing");
+/// }
+/// ```
+#[macro_export]
+macro_rules! prompt_char {
+    ($($arg:tt)*) => {{
+        use std::io::Write;
+        let mut out = $crate::prompt_stream();
+        $crate::__private::write_prompt(&mut out, format_args!($($arg)*))
+            .unwrap_or_else(|e| panic!("failed writing to {}: {}", out, e));
+        out.flush().unwrap_or_else(|e| panic!("failed writing to {}: {}", out, e));
+        $crate::__private::read_answer($crate::read_char())
+            .unwrap_or_else(|e| panic!("failed reading from stdin: {}", e))
+    }};
+}
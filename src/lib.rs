@@ -22,36 +22,187 @@
 //! This library makes several tradeoffs in its design:
 //!
 //! - Just like the `std::io::println!` family of macros, the `prompt` macros
-//!     panic in the case of an error.
+//!   panic in the case of an error. The panic message includes the
+//!   underlying I/O error. Each of them has a `try_` counterpart which returns
+//!   an `io::Result` instead, for code that needs to recover from I/O
+//!   failures.
 //! - The prompt macros don't support parsing of values in-place. Users are
-//!     encouraged to `.parse` instead.
+//!   encouraged to `.parse` instead, or to use [`prompt_parse!`] which does so
+//!   in a single step.
 //! - The prompt family of macros only support reading a single line at the time
-//!     and assigning it to a value.
+//!   and assigning it to a value. Answers spanning multiple lines can be read
+//!   with [`prompt_multiline!`], which stops at a sentinel line, or with
+//!   [`prompt_until_complete`], which stops once a closure accepts the text.
 //! - The prompt family of macros doesn't support rich input types such
-//!     as passwords or dropdowns. This functionality is expected to be provided
-//!     through crates.io.
+//!   as dropdowns. This functionality is expected to be provided through
+//!   crates.io. Hidden input is the exception, since reading a password is
+//!   common enough to warrant [`prompt_password!`].
 //!
 //! This library is split into two parts: a convenient `read_line` function
 //! which is a shorthand for calling `Stdin::read_line` and reading into a new
 //! string. And the `prompt!` family of macros which support reading from
-//! writing to stdout/stderr, and reading a value from stdin.
+//! writing to stdout/stderr, and reading a value from stdin. For prompting over
+//! streams other than stdin and stdout, the [`Prompter`] type can be used.
+//! Prompts which combine several options, such as a default and a validation
+//! predicate, can be composed with [`PromptBuilder`]. Prompt flows built from
+//! the macros can be tested by queueing answers with
+//! [`push_scripted_answer`].
 //!
 //! The focus for the `prompt` family of macros is on simplicity: its goal is to
 //! make it convenient to write quick prompts inside Rust programs in a way that feels similar to using `println!`. It does not
 //! try and cover parsing rules by introducing a new DSL. Such a DSL almost
 //! certainly needs to have regex-like capabilities, and would be nearly
 //! impossible to stabilize.
+//!
+//! # Features
+//!
+//! - `async`: read lines asynchronously on a [`tokio`](https://docs.rs/tokio)
+//!   runtime, through `read_line_async` and `prompt_async`.
+//! - `color`: render [`Style`]s applied through [`prompt_styled!`] using ANSI
+//!   escape codes.
+//! - `json`: read answers as JSON into any type implementing `Deserialize`
+//!   from [`serde`](https://docs.rs/serde), through `prompt_json!`.
+//! - `log`: trace each prompt message and answer through the
+//!   [`log`](https://docs.rs/log) crate, at the debug level and under the
+//!   `io_prompt_prototype` target. Answers to password and secret prompts are
+//!   logged as `<redacted>`.
+//! - `smallvec`: read short answers without allocating, through
+//!   `read_line_small`, which stores answers of up to 32 bytes inline using
+//!   the [`smallvec`](https://docs.rs/smallvec) crate.
+//! - `time`: read dates into a `Date` from the [`time`](https://docs.rs/time)
+//!   crate, through `prompt_date!`.
+//! - `url`: validate the answers to [`prompt_url!`] with the
+//!   [`url`](https://docs.rs/url) crate rather than a heuristic.
+//! - `zeroize`: read passwords into a `Secret`, which is overwritten with
+//!   zeros when dropped, through `read_secret` and `prompt_secret!`, using the
+//!   [`zeroize`](https://docs.rs/zeroize) crate.
 
 #![forbid(unsafe_code, future_incompatible, rust_2018_idioms)]
 #![deny(missing_debug_implementations, nonstandard_style)]
-#![warn(missing_docs, missing_doc_code_examples, unreachable_pub)]
+#![warn(missing_docs, unreachable_pub)]
+
+use std::fmt::Display;
+use std::io::{self, BufRead};
+
+mod answer;
+#[cfg(feature = "async")]
+mod async_io;
+mod builder;
+mod cancel;
+mod complete;
+#[cfg(feature = "time")]
+mod date;
+mod detailed;
+mod duration;
+mod editor;
+mod env;
+mod error;
+mod fuzzy;
+mod history;
+mod input;
+#[cfg(feature = "json")]
+mod json;
+mod keypress;
+mod limit;
+mod locale;
+mod multiline;
+mod password;
+mod path;
+mod prefill;
+mod prompter;
+mod redraw;
+mod sanitize;
+mod script;
+#[cfg(feature = "zeroize")]
+mod secret;
+#[cfg(feature = "smallvec")]
+mod small;
+mod stream;
+mod style;
+mod tee;
+mod term;
+mod timeout;
+mod toggle;
+mod trace;
+mod tty;
+mod validate;
+mod yes_no_quit;
 
-use std::io::{self, stdin};
+pub use answer::Answer;
+#[cfg(feature = "async")]
+pub use async_io::{prompt_async, read_line_async, read_line_async_from};
+pub use builder::{set_default_map, Prompt, PromptBuilder};
+pub use cancel::{prompt_cancelable, prompt_cancelable_from};
+pub use complete::{prompt_complete, prompt_complete_from};
+pub use detailed::{read_line_detailed, read_line_detailed_from, LineRead};
+pub use env::{prompt_env_or, prompt_env_or_hidden};
+pub use error::{PromptParseError, RetriesExhausted};
+pub use history::HistoryPrompter;
+pub use keypress::{read_char, read_char_from};
+pub use limit::{read_line_limited, read_line_limited_from, Overflow};
+pub use locale::{locale, set_locale, Locale};
+pub use multiline::{read_multiline, read_multiline_from, read_until_complete_from};
+pub use password::{
+    prompt_password_confirm, prompt_password_confirm_from, prompt_secret_env_fallback,
+    prompt_secret_file_fallback, read_password, read_password_masked, read_password_masked_from,
+    read_password_masked_with, read_password_masked_with_from, set_password_newline, Masking,
+};
+pub use prefill::{prompt_prefill, prompt_prefill_from};
+pub use prompter::Prompter;
+pub use redraw::{prompt_redraw, prompt_redraw_from, Redraw};
+pub use sanitize::{ControlCharError, ControlChars};
+pub use script::{
+    end_script, is_scripted, push_scripted_answer, set_strip_bom, take_scripted_output, Input,
+};
+#[cfg(feature = "zeroize")]
+pub use secret::{read_secret, read_secret_from, Secret};
+#[cfg(feature = "smallvec")]
+pub use small::{read_line_small, read_line_small_from, SmallLine, SMALL_LINE_CAPACITY};
+pub use stream::{prompt_stream, set_prompt_stream, Stream};
+pub use style::Style;
+pub use tee::Tee;
+pub use term::{is_interactive, RawModeGuard};
+pub use timeout::{
+    poll_line, prompt_deadline, prompt_timeout, prompt_timeout_default, read_line_cancelable,
+    read_line_timeout,
+};
+pub use toggle::{prompt_toggle, prompt_toggle_from};
+pub use tty::{prompt_tty, read_line_tty};
+pub use yes_no_quit::YesNoQuit;
+
+/// Implementation details of the macros, which aren't part of the public API.
+#[doc(hidden)]
+pub mod __private {
+    pub use crate::trace::{
+        prompt as trace_prompt, read_answer, read_secret, write_prompt, Answered,
+    };
+}
 
 /// Reads a line of input from stdin.
 ///
 /// This is a shorthand for calling [`Stdin::read_line`] and reading
-/// it into a new string.
+/// it into a new string. The trailing newline is kept, so the exact bytes the
+/// user entered are returned. This is useful for byte-accurate protocols or
+/// when passing the line on to another program. Use [`read_line_trimmed`] to
+/// have the newline removed, which is what most prompts want.
+///
+/// If the line isn't valid UTF-8, an error of kind
+/// [`io::ErrorKind::InvalidData`] is returned. [`read_line_lossy`] replaces the
+/// invalid sequences instead.
+///
+/// This is equivalent to calling [`read_line_from`] with a locked handle to
+/// stdin.
+///
+/// # Platform-specific behavior
+///
+/// On Windows, a line consisting only of Ctrl-Z (`\x1a`) and its terminator is
+/// treated as the end of the input, and an empty string is returned. A Windows
+/// console produces such a line when the user presses Ctrl-Z followed by
+/// Enter, which is how the end of input is signaled there, and redirected DOS
+/// text files may end with one as well. This also applies to the `_trimmed`,
+/// `_opt` and `_limited` variants, so answers parse identically on all
+/// platforms. A Ctrl-Z elsewhere in a line is kept, as is any Ctrl-Z read with
+/// [`read_line_bytes`]. On other platforms Ctrl-Z has no special meaning.
 ///
 /// # Examples
 ///
@@ -60,136 +211,1715 @@ use std::io::{self, stdin};
 /// use io_prompt_prototype::read_line;
 ///
 /// print!("What's your favorite number? >");
-/// let num: u16 = read_line()?.parse()?;
+/// let num: u16 = read_line()?.trim_end().parse()?;
 /// println!("Oh, cool: {}!", num);
 /// # Ok(()) }
 /// ```
 ///
 /// [`Stdin::read_line`]: https://doc.rust-lang.org/std/io/struct.Stdin.html#method.read_line
 pub fn read_line() -> io::Result<String> {
+    read_line_from(&mut script::input())
+}
+
+/// Reads a line of input from a reader.
+///
+/// This behaves exactly like [`read_line`], but reads from an arbitrary
+/// [`BufRead`] rather than from stdin. The trailing newline, if any, is kept.
+/// This is useful to drive prompt logic from an in-memory buffer in tests.
+///
+/// Reads which fail with [`io::ErrorKind::Interrupted`], for example because a
+/// signal such as `SIGWINCH` arrived, are retried.
+///
+/// # Examples
+///
+/// ```
+/// # fn main() -> std::io::Result<()> {
+/// use io_prompt_prototype::read_line_from;
+/// use std::io::Cursor;
+///
+/// let mut input = Cursor::new(b"hello\nworld\n");
+/// assert_eq!(read_line_from(&mut input)?, "hello\n");
+/// assert_eq!(read_line_from(&mut input)?, "world\n");
+/// # Ok(()) }
+/// ```
+pub fn read_line_from<R: BufRead>(reader: &mut R) -> io::Result<String> {
     let mut input = String::new();
-    stdin().read_line(&mut input)?;
+    append_line(reader, &mut input)?;
     Ok(input)
 }
 
-/// Prints to the standard output. Then reads a line of input.
+/// Appends a line to `buf`, retrying interrupted reads. Returns the number of
+/// bytes read.
+///
+/// A Ctrl-Z line on Windows isn't appended, and counts as reading nothing. See
+/// [`read_line`] for details.
+pub(crate) fn append_line<R: BufRead>(reader: &mut R, buf: &mut String) -> io::Result<usize> {
+    let start = buf.len();
+    loop {
+        match reader.read_line(buf) {
+            Ok(_) => break,
+            Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+            Err(err) => return Err(err),
+        }
+    }
+    if is_eof_marker(&buf.as_bytes()[start..]) {
+        buf.truncate(start);
+    }
+    Ok(buf.len() - start)
+}
+
+/// Returns `true` if `line` is a Ctrl-Z end of input marker on Windows, with or
+/// without its line terminator.
+pub(crate) fn is_eof_marker(line: &[u8]) -> bool {
+    let marker = match line {
+        [marker, b'\r', b'\n'] | [marker, b'\n'] | [marker, b'\r'] | [marker] => marker,
+        _ => return false,
+    };
+    cfg!(windows) && *marker == 0x1a
+}
+
+/// Reads a line of input from stdin, removing the trailing newline.
 ///
-/// This is a shorthand for calling [`print!`], [`read_line`], and removing
-/// any trailing newlines from the output.
+/// Exactly one trailing line terminator is removed. The following sequences are
+/// treated as line terminators:
+///
+/// - `\r\n` (carriage return followed by line feed)
+/// - `\n` (line feed)
+/// - `\r` (a lone carriage return)
+/// - `U+2028` (line separator)
+/// - `U+2029` (paragraph separator)
+///
+/// Reading still stops at the first `\n`, so the other terminators are only
+/// recognized at the very end of the input. Other trailing whitespace is kept,
+/// since it may be a legitimate part of the answer. A byte order mark at the
+/// start of the input is kept too, unless [`set_strip_bom`] was called.
 ///
 /// # Examples
 ///
 /// ```no_run
 /// # fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
-/// use io_prompt_prototype::prompt;
+/// use io_prompt_prototype::read_line_trimmed;
 ///
-/// let num: u16 = prompt!("What's your favorite number? >").parse()?;
+/// print!("What's your favorite number? >");
+/// let num: u16 = read_line_trimmed()?.parse()?;
 /// println!("Oh, cool: {}!", num);
 /// # Ok(()) }
 /// ```
-#[macro_export]
-macro_rules! prompt {
-    ($($arg:tt)*) => {{
-        use std::io::{stdout, Write};
-        print!($($arg)*);
-        stdout().flush().expect("failed writing to stdout");
-        let mut s = $crate::read_line().expect("failed reading from stdin");
-        if s.ends_with('\n') {
-            s.pop();
+pub fn read_line_trimmed() -> io::Result<String> {
+    read_line_trimmed_from(&mut script::input())
+}
+
+/// Reads a line of input from a reader, removing the trailing newline.
+///
+/// This behaves exactly like [`read_line_trimmed`], but reads from an
+/// arbitrary [`BufRead`] rather than from stdin.
+///
+/// # Examples
+///
+/// ```
+/// # fn main() -> std::io::Result<()> {
+/// use io_prompt_prototype::read_line_trimmed_from;
+/// use std::io::Cursor;
+///
+/// let mut input = Cursor::new(b"hello \r\nworld\n");
+/// assert_eq!(read_line_trimmed_from(&mut input)?, "hello ");
+/// assert_eq!(read_line_trimmed_from(&mut input)?, "world");
+/// # Ok(()) }
+/// ```
+pub fn read_line_trimmed_from<R: BufRead>(reader: &mut R) -> io::Result<String> {
+    let mut line = read_line_from(reader)?;
+    trim_newline(&mut line);
+    Ok(line)
+}
+
+/// Reads a line of input from stdin into an existing buffer, removing the
+/// trailing newline.
+///
+/// The buffer is cleared first, so afterwards it holds only the new line, as
+/// [`read_line_trimmed`] would have returned it. Reusing one buffer across
+/// calls saves allocating a new `String` for every line in a loop. Like
+/// [`BufRead::read_line`], the number of bytes read is returned, including
+/// the line terminator, so `0` means the input has ended.
+///
+/// # Examples
+///
+/// ```no_run
+/// # fn main() -> std::io::Result<()> {
+/// use io_prompt_prototype::read_line_into;
+///
+/// let mut line = String::new();
+/// while read_line_into(&mut line)? != 0 {
+///     println!("Read: {}", line);
+/// }
+/// # Ok(()) }
+/// ```
+pub fn read_line_into(buf: &mut String) -> io::Result<usize> {
+    read_line_into_from(&mut script::input(), buf)
+}
+
+/// Reads a line of input from a reader into an existing buffer, removing the
+/// trailing newline.
+///
+/// This behaves exactly like [`read_line_into`], but reads from an arbitrary
+/// [`BufRead`] rather than from stdin.
+///
+/// # Examples
+///
+/// ```
+/// # fn main() -> std::io::Result<()> {
+/// use io_prompt_prototype::read_line_into_from;
+/// use std::io::Cursor;
+///
+/// let mut input = Cursor::new(b"hello\r\nworld");
+/// let mut line = String::from("stale");
+/// assert_eq!(read_line_into_from(&mut input, &mut line)?, 7);
+/// assert_eq!(line, "hello");
+/// assert_eq!(read_line_into_from(&mut input, &mut line)?, 5);
+/// assert_eq!(line, "world");
+/// # Ok(()) }
+/// ```
+pub fn read_line_into_from<R: BufRead>(reader: &mut R, buf: &mut String) -> io::Result<usize> {
+    buf.clear();
+    let read = append_line(reader, buf)?;
+    trim_newline(buf);
+    Ok(read)
+}
+
+/// Reads a line of input from stdin, distinguishing an empty line from the end
+/// of the input.
+///
+/// Returns `Ok(None)` if stdin has reached its end, for example because the
+/// user pressed Ctrl-D, and `Ok(Some(line))` otherwise. The line is trimmed
+/// like [`read_line_trimmed`] does, so an empty line returns `Ok(Some(""))`.
+/// This mirrors [`BufRead::read_line`] returning `0` at the end of the input.
+///
+/// # Examples
+///
+/// ```no_run
+/// # fn main() -> std::io::Result<()> {
+/// use io_prompt_prototype::read_line_opt;
+///
+/// while let Some(line) = read_line_opt()? {
+///     println!("Read: {}", line);
+/// }
+/// # Ok(()) }
+/// ```
+pub fn read_line_opt() -> io::Result<Option<String>> {
+    read_line_opt_from(&mut script::input())
+}
+
+/// Reads a line of input from a reader, distinguishing an empty line from the
+/// end of the input.
+///
+/// This behaves exactly like [`read_line_opt`], but reads from an arbitrary
+/// [`BufRead`] rather than from stdin.
+///
+/// # Examples
+///
+/// ```
+/// # fn main() -> std::io::Result<()> {
+/// use io_prompt_prototype::read_line_opt_from;
+/// use std::io::Cursor;
+///
+/// let mut input = Cursor::new(b"\n");
+/// assert_eq!(read_line_opt_from(&mut input)?, Some(String::new()));
+/// assert_eq!(read_line_opt_from(&mut input)?, None);
+/// # Ok(()) }
+/// ```
+pub fn read_line_opt_from<R: BufRead>(reader: &mut R) -> io::Result<Option<String>> {
+    let mut line = String::new();
+    if append_line(reader, &mut line)? == 0 {
+        return Ok(None);
+    }
+    trim_newline(&mut line);
+    Ok(Some(line))
+}
+
+/// Reads `n` lines of input from stdin, removing their trailing newlines.
+///
+/// Each line is read as with [`read_line_trimmed`]. If stdin ends before `n`
+/// lines have been read, an error of kind [`io::ErrorKind::UnexpectedEof`] is
+/// returned and the lines read so far are discarded. An empty line still
+/// counts as a line.
+///
+/// # Examples
+///
+/// ```no_run
+/// # fn main() -> std::io::Result<()> {
+/// use io_prompt_prototype::read_lines;
+///
+/// println!("Enter your name, email and phone number, one per line:");
+/// let fields = read_lines(3)?;
+/// # Ok(()) }
+/// ```
+pub fn read_lines(n: usize) -> io::Result<Vec<String>> {
+    read_lines_from(&mut script::input(), n)
+}
+
+/// Reads `n` lines of input from a reader, removing their trailing newlines.
+///
+/// This behaves exactly like [`read_lines`], but reads from an arbitrary
+/// [`BufRead`] rather than from stdin.
+///
+/// # Examples
+///
+/// ```
+/// # fn main() -> std::io::Result<()> {
+/// use io_prompt_prototype::read_lines_from;
+/// use std::io::Cursor;
+///
+/// let mut input = Cursor::new(b"Ferris\n\nferris@example.com\n");
+/// assert_eq!(read_lines_from(&mut input, 2)?, ["Ferris", ""]);
+/// assert!(read_lines_from(&mut input, 2).is_err());
+/// # Ok(()) }
+/// ```
+pub fn read_lines_from<R: BufRead>(reader: &mut R, n: usize) -> io::Result<Vec<String>> {
+    let mut lines = Vec::with_capacity(n);
+    while lines.len() < n {
+        match read_line_opt_from(reader)? {
+            Some(line) => lines.push(line),
+            None => {
+                return Err(io::Error::new(
+                    io::ErrorKind::UnexpectedEof,
+                    format!("input ended after {} of {} lines", lines.len(), n),
+                ))
+            }
         }
-        if s.ends_with('\r') {
-            s.pop();
+    }
+    Ok(lines)
+}
+
+/// Reads every line of input from stdin until it ends, removing their trailing
+/// newlines.
+///
+/// Each line is read as with [`read_line_trimmed`], and a last line without a
+/// trailing newline is included. This suits tools which are usually run
+/// interactively but also accept a whole batch of answers piped in, as in
+/// `my-cli < answers.txt`. If stdin is a terminal, this only returns once the
+/// user ends the input, by pressing Ctrl-D on Unix or Ctrl-Z and Enter on
+/// Windows.
+///
+/// All of the input is held in memory at once, so piping in a huge file uses
+/// as much memory as the file is large, with no upper bound. For input which
+/// can't be trusted, read the lines one at a time with [`read_line_limited`]
+/// instead, stopping once enough has been read.
+///
+/// # Examples
+///
+/// ```no_run
+/// # fn main() -> std::io::Result<()> {
+/// use io_prompt_prototype::read_all_lines;
+///
+/// for name in read_all_lines()? {
+///     println!("Hello, {}!", name);
+/// }
+/// # Ok(()) }
+/// ```
+pub fn read_all_lines() -> io::Result<Vec<String>> {
+    read_all_lines_from(&mut script::input())
+}
+
+/// Reads every line of input from a reader until it ends, removing their
+/// trailing newlines.
+///
+/// This behaves exactly like [`read_all_lines`], but reads from an arbitrary
+/// [`BufRead`] rather than from stdin.
+///
+/// # Examples
+///
+/// ```
+/// # fn main() -> std::io::Result<()> {
+/// use io_prompt_prototype::read_all_lines_from;
+/// use std::io::Cursor;
+///
+/// let mut input = Cursor::new(b"Ferris\r\n\nCorro");
+/// assert_eq!(read_all_lines_from(&mut input)?, ["Ferris", "", "Corro"]);
+/// # Ok(()) }
+/// ```
+pub fn read_all_lines_from<R: BufRead>(reader: &mut R) -> io::Result<Vec<String>> {
+    let mut lines = Vec::new();
+    while let Some(line) = read_line_opt_from(reader)? {
+        lines.push(line);
+    }
+    Ok(lines)
+}
+
+/// Reads a line of input from stdin as raw bytes, removing the trailing
+/// newline.
+///
+/// Unlike [`read_line`], the input isn't required to be valid UTF-8. This is
+/// useful for binary-ish protocols or terminals using another encoding, which
+/// can then be decoded by the caller. A trailing `\n` or `\r\n` is removed, as
+/// is a lone trailing `\r`.
+///
+/// # Examples
+///
+/// ```no_run
+/// # fn main() -> std::io::Result<()> {
+/// use io_prompt_prototype::read_line_bytes;
+///
+/// print!("Latin-1 text: >");
+/// let text: String = read_line_bytes()?.into_iter().map(char::from).collect();
+/// # Ok(()) }
+/// ```
+pub fn read_line_bytes() -> io::Result<Vec<u8>> {
+    read_line_bytes_from(&mut script::input())
+}
+
+/// Reads a line of input from a reader as raw bytes, removing the trailing
+/// newline.
+///
+/// This behaves exactly like [`read_line_bytes`], but reads from an arbitrary
+/// [`BufRead`] rather than from stdin.
+///
+/// # Examples
+///
+/// ```
+/// # fn main() -> std::io::Result<()> {
+/// use io_prompt_prototype::read_line_bytes_from;
+/// use std::io::Cursor;
+///
+/// let mut input = Cursor::new(b"caf\xe9\r\nend");
+/// assert_eq!(read_line_bytes_from(&mut input)?, b"caf\xe9");
+/// assert_eq!(read_line_bytes_from(&mut input)?, b"end");
+/// # Ok(()) }
+/// ```
+pub fn read_line_bytes_from<R: BufRead>(reader: &mut R) -> io::Result<Vec<u8>> {
+    let mut line = read_until_byte_from(reader, b'\n')?;
+    if line.last() == Some(&b'\r') {
+        line.pop();
+    }
+    Ok(line)
+}
+
+/// Reads a line of input from stdin, replacing invalid UTF-8 instead of
+/// failing.
+///
+/// [`read_line`] returns an error of kind [`io::ErrorKind::InvalidData`] if a
+/// line isn't valid UTF-8, and the line is lost. This reads it as bytes
+/// instead, and decodes them with [`String::from_utf8_lossy`]: each invalid
+/// sequence is replaced with `U+FFFD REPLACEMENT CHARACTER`, and the rest of
+/// the line is kept as is. The trailing newline, if any, is kept too.
+///
+/// This loses data. The replaced bytes can't be recovered, and a line which
+/// contains `U+FFFD` can't be told apart from one which was decoded lossily.
+/// Use it where a mangled answer is more useful than an error, such as for
+/// free-form text typed on a terminal with a legacy encoding. Where the exact
+/// bytes matter, such as for file names, use [`read_line_bytes`] instead.
+///
+/// # Examples
+///
+/// ```no_run
+/// # fn main() -> std::io::Result<()> {
+/// use io_prompt_prototype::read_line_lossy;
+///
+/// print!("Comment? >");
+/// let comment = read_line_lossy()?;
+/// # Ok(()) }
+/// ```
+pub fn read_line_lossy() -> io::Result<String> {
+    read_line_lossy_from(&mut script::input())
+}
+
+/// Reads a line of input from a reader, replacing invalid UTF-8 instead of
+/// failing.
+///
+/// This behaves exactly like [`read_line_lossy`], but reads from an arbitrary
+/// [`BufRead`] rather than from stdin.
+///
+/// # Examples
+///
+/// ```
+/// # fn main() -> std::io::Result<()> {
+/// use io_prompt_prototype::read_line_lossy_from;
+/// use std::io::Cursor;
+///
+/// let mut input = Cursor::new(b"caf\xe9\nok\n");
+/// assert_eq!(read_line_lossy_from(&mut input)?, "caf\u{FFFD}\n");
+/// assert_eq!(read_line_lossy_from(&mut input)?, "ok\n");
+/// # Ok(()) }
+/// ```
+pub fn read_line_lossy_from<R: BufRead>(reader: &mut R) -> io::Result<String> {
+    let mut line = Vec::new();
+    loop {
+        match reader.read_until(b'\n', &mut line) {
+            Ok(_) => break,
+            Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+            Err(err) => return Err(err),
         }
-        s
-    }};
+    }
+    if is_eof_marker(&line) {
+        line.clear();
+    }
+    match String::from_utf8(line) {
+        Ok(line) => Ok(line),
+        Err(err) => Ok(String::from_utf8_lossy(err.as_bytes()).into_owned()),
+    }
 }
 
-/// Prints to the standard output, with a newline. Then reads a line of input.
+/// Reads input from stdin up to a delimiter byte, removing the delimiter.
 ///
-/// This is a shorthand for calling [`println!`], [`read_line`], and removing
-/// any trailing newlines from the output.
+/// This generalizes [`read_line_bytes`] to records which aren't terminated by
+/// a newline, such as the NUL separated output of `find -print0`. Input is
+/// read up to and including the first `delim`, which is then removed. If the
+/// input ends before a delimiter is found, everything up to the end is
+/// returned. At the end of the input an empty `Vec` is returned.
 ///
 /// # Examples
 ///
 /// ```no_run
-/// # fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
-/// use io_prompt_prototype::promptln;
+/// # fn main() -> std::io::Result<()> {
+/// use io_prompt_prototype::read_until_byte;
 ///
-/// let num: u16 = promptln!("What's your favorite number? >").parse()?;
-/// println!("Oh, cool: {}!", num);
+/// let path = read_until_byte(b'\0')?;
 /// # Ok(()) }
 /// ```
-#[macro_export]
-macro_rules! promptln {
-    ($($arg:tt)*) => {{
-        use std::io::{stdout, Write};
-        println!($($arg)*);
-        stdout().flush().expect("failed writing to stdout");
-        let mut s = $crate::read_line().expect("failed reading from stdin");
-        if let Some(_) = s.strip_suffix('\n') {
-            let _ = s.strip_suffix('\r');
+pub fn read_until_byte(delim: u8) -> io::Result<Vec<u8>> {
+    read_until_byte_from(&mut script::input(), delim)
+}
+
+/// Reads input from a reader up to a delimiter byte, removing the delimiter.
+///
+/// This behaves exactly like [`read_until_byte`], but reads from an arbitrary
+/// [`BufRead`] rather than from stdin.
+///
+/// # Examples
+///
+/// ```
+/// # fn main() -> std::io::Result<()> {
+/// use io_prompt_prototype::read_until_byte_from;
+///
+/// let mut input = std::io::Cursor::new(b"./a.txt\0./b c.txt\0");
+/// assert_eq!(read_until_byte_from(&mut input, b'\0')?, b"./a.txt");
+/// assert_eq!(read_until_byte_from(&mut input, b'\0')?, b"./b c.txt");
+/// assert_eq!(read_until_byte_from(&mut input, b'\0')?, b"");
+/// # Ok(()) }
+/// ```
+pub fn read_until_byte_from<R: BufRead>(reader: &mut R, delim: u8) -> io::Result<Vec<u8>> {
+    let mut record = Vec::new();
+    loop {
+        match reader.read_until(delim, &mut record) {
+            Ok(_) => break,
+            Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+            Err(err) => return Err(err),
         }
-        s
-    }};
+    }
+    if record.last() == Some(&delim) {
+        record.pop();
+    }
+    Ok(record)
 }
 
-/// Prints to the standard error. Then reads a line of input.
+/// Reads UTF-8 input from stdin up to a delimiter byte, removing the
+/// delimiter.
+///
+/// This behaves like [`read_until_byte`], but returns a `String`. The record
+/// is validated after the delimiter is removed, and if it isn't valid UTF-8 an
+/// error of kind [`io::ErrorKind::InvalidData`] is returned. The record is
+/// still consumed in that case, so the next read starts after its delimiter.
 ///
-/// This is a shorthand for calling [`eprint!`], [`read_line`], and removing
-/// any trailing newlines from the output.
+/// Every byte of a multi-byte UTF-8 character is at least `0x80`, so an ASCII
+/// delimiter never splits a character. A non-ASCII delimiter can, which then
+/// makes the records fail validation.
 ///
 /// # Examples
 ///
 /// ```no_run
-/// # fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
-/// use io_prompt_prototype::eprompt;
+/// # fn main() -> std::io::Result<()> {
+/// use io_prompt_prototype::read_until_byte_string;
 ///
-/// let num: u16 = eprompt!("What's your favorite number? >").parse()?;
-/// println!("Oh, cool: {}!", num);
+/// let path = read_until_byte_string(b'\0')?;
 /// # Ok(()) }
 /// ```
-#[macro_export]
-macro_rules! eprompt {
-    ($($arg:tt)*) => {{
-        use std::io::{stdout, Write};
-        print!($($arg)*);
-        stdout().flush().expect("failed writing to stdout");
-        let mut s = $crate::read_line().expect("failed reading from stderr");
-        if s.ends_with('\n') {
+pub fn read_until_byte_string(delim: u8) -> io::Result<String> {
+    read_until_byte_string_from(&mut script::input(), delim)
+}
+
+/// Reads UTF-8 input from a reader up to a delimiter byte, removing the
+/// delimiter.
+///
+/// This behaves exactly like [`read_until_byte_string`], but reads from an
+/// arbitrary [`BufRead`] rather than from stdin.
+///
+/// # Examples
+///
+/// ```
+/// # fn main() -> std::io::Result<()> {
+/// use io_prompt_prototype::read_until_byte_string_from;
+///
+/// let mut input = std::io::Cursor::new("crème\0brûlée");
+/// assert_eq!(read_until_byte_string_from(&mut input, b'\0')?, "crème");
+/// assert_eq!(read_until_byte_string_from(&mut input, b'\0')?, "brûlée");
+/// # Ok(()) }
+/// ```
+pub fn read_until_byte_string_from<R: BufRead>(reader: &mut R, delim: u8) -> io::Result<String> {
+    let record = read_until_byte_from(reader, delim)?;
+    String::from_utf8(record).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+}
+
+/// Prints to the standard output. Then reads a line of input and parses it
+/// with a closure, re-prompting until parsing succeeds.
+///
+/// Each error returned by `parse` is printed before asking again, which gives
+/// full control over validation without needing [`FromStr`](std::str::FromStr).
+/// This is a shorthand for calling [`Prompter::prompt_with`] on
+/// [`Prompter::stdio`], which documents the details.
+///
+/// # Examples
+///
+/// ```no_run
+/// # fn main() -> std::io::Result<()> {
+/// use io_prompt_prototype::prompt_with;
+/// use std::error::Error;
+///
+/// let (x, y) = prompt_with("Point (x, y)? >", |s| -> Result<(i32, i32), Box<dyn Error>> {
+///     let (x, y) = s.split_once(',').ok_or("expected two numbers separated by a comma")?;
+///     Ok((x.trim().parse()?, y.trim().parse()?))
+/// })?;
+/// println!("x = {}, y = {}", x, y);
+/// # Ok(()) }
+/// ```
+pub fn prompt_with<T, E, F>(msg: &str, parse: F) -> io::Result<T>
+where
+    F: FnMut(&str) -> Result<T, E>,
+    E: Display,
+{
+    Prompter::stdio().prompt_with(msg, parse)
+}
+
+/// Prints to the standard output, with a newline. Then reads lines of input
+/// until `is_complete` accepts the text read so far.
+///
+/// The lines are joined with `\n`, and `is_complete` is called with the
+/// joined text after each line. If stdin is closed first, the lines read until
+/// then are returned. This is a shorthand for calling
+/// [`Prompter::prompt_until_complete`] on [`Prompter::stdio`], and
+/// [`read_until_complete_from`] documents the details.
+///
+/// # Examples
+///
+/// ```no_run
+/// # fn main() -> std::io::Result<()> {
+/// use io_prompt_prototype::prompt_until_complete;
+///
+/// let json = prompt_until_complete("Paste a JSON object:", |text| {
+///     text.matches('{').count() == text.matches('}').count()
+/// })?;
+/// # Ok(()) }
+/// ```
+pub fn prompt_until_complete<F>(msg: &str, is_complete: F) -> io::Result<String>
+where
+    F: FnMut(&str) -> bool,
+{
+    Prompter::stdio().prompt_until_complete(msg, is_complete)
+}
+
+/// Removes a single trailing line terminator from a string.
+///
+/// See [`read_line_trimmed`] for the sequences treated as line terminators.
+pub(crate) fn trim_newline(s: &mut String) {
+    match s.chars().next_back() {
+        Some('\n') => {
             s.pop();
+            if s.ends_with('\r') {
+                s.pop();
+            }
         }
-        if s.ends_with('\r') {
+        Some('\r') | Some('\u{2028}') | Some('\u{2029}') => {
             s.pop();
         }
-        s
-    }};
+        _ => {}
+    }
 }
 
-/// Prints to the standard error, with a newline. Then reads a line of input.
+/// Prints to the standard output. Then reads a line of input.
+///
+/// This is a shorthand for calling [`print!`] and [`read_line_trimmed`]. The
+/// prompt can be printed to the standard error instead by calling
+/// [`set_prompt_stream`].
 ///
-/// This is a shorthand for calling [`eprintln!`], [`read_line`], and removing
-/// any trailing newlines from the output.
+/// A literal is treated as a format string, just like with [`print!`]. A
+/// single argument of any other kind, such as a variable, is printed through
+/// its `Display` implementation instead, which saves wrapping a computed
+/// prompt in `"{}"`. [`prompt_raw!`], [`promptln!`], [`eprompt!`],
+/// [`epromptln!`], [`try_prompt!`], [`try_promptln!`], [`try_eprompt!`] and
+/// [`try_epromptln!`] accept a single value the same way. The other macros
+/// which take format arguments, such as [`confirm!`] and [`prompt_number!`],
+/// need a format string.
+///
+/// # Panics
+///
+/// Panics if writing the prompt or reading the answer fails. A common cause is
+/// stdout being a pipe whose reader has exited, as in `my-cli | head -1`, in
+/// which case the message says that the pipe was closed. Use [`try_prompt!`]
+/// to handle this instead.
 ///
 /// # Examples
 ///
 /// ```no_run
 /// # fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
-/// use io_prompt_prototype::epromptln;
+/// use io_prompt_prototype::prompt;
 ///
-/// let num: u16 = epromptln!("What's your favorite number? >").parse()?;
+/// let num: u16 = prompt!("What's your favorite number? >").parse()?;
 /// println!("Oh, cool: {}!", num);
+///
+/// let question = format!("Is {} your favorite number? >", num);
+/// let answer = prompt!(question);
 /// # Ok(()) }
 /// ```
 #[macro_export]
-macro_rules! epromptln {
-    ($($arg:tt)*) => {{
-        use std::io::{stdout, Write};
-        println!($($arg)*);
-        stdout().flush().expect("failed writing to stdout");
-        let mut s = $crate::read_line().expect("failed reading from stderr");
-        if let Some(_) = s.strip_suffix('\n') {
-            let _ = s.strip_suffix('\r');
-        }
-        s
+macro_rules! prompt {
+    ($fmt:literal $($arg:tt)*) => {{
+        use std::io::Write;
+        let mut out = $crate::prompt_stream();
+        $crate::__private::write_prompt(&mut out, format_args!($fmt $($arg)*))
+            .unwrap_or_else(|e| panic!("failed writing to {}: {}", out, e));
+        out.flush().unwrap_or_else(|e| panic!("failed writing to {}: {}", out, e));
+        $crate::__private::read_answer($crate::read_line_trimmed())
+            .unwrap_or_else(|e| panic!("failed reading from stdin: {}", e))
+    }};
+    ($msg:expr $(,)?) => {
+        $crate::prompt!("{}", $msg)
+    };
+}
+
+/// Prints to the standard output. Then reads a line of input, keeping the
+/// trailing newline.
+///
+/// This is a shorthand for calling [`print!`] and [`read_line`]. Unlike
+/// [`prompt!`], nothing is removed from the end of the line, so the answer is
+/// exactly what was entered. Like [`prompt!`], this honors
+/// [`set_prompt_stream`].
+///
+/// # Examples
+///
+/// ```no_run
+/// use io_prompt_prototype::prompt_raw;
+///
+/// let line = prompt_raw!("Enter a line >");
+/// assert!(line.ends_with('\n') || line.is_empty());
+/// ```
+#[macro_export]
+macro_rules! prompt_raw {
+    ($fmt:literal $($arg:tt)*) => {{
+        use std::io::Write;
+        let mut out = $crate::prompt_stream();
+        $crate::__private::write_prompt(&mut out, format_args!($fmt $($arg)*))
+            .unwrap_or_else(|e| panic!("failed writing to {}: {}", out, e));
+        out.flush().unwrap_or_else(|e| panic!("failed writing to {}: {}", out, e));
+        $crate::__private::read_answer($crate::read_line())
+            .unwrap_or_else(|e| panic!("failed reading from stdin: {}", e))
+    }};
+    ($msg:expr $(,)?) => {
+        $crate::prompt_raw!("{}", $msg)
+    };
+}
+
+/// Prints to the standard output. Then reads a line of input, removing all
+/// surrounding whitespace.
+///
+/// [`prompt!`] only removes the line terminator, so an answer like `"  hi  "`
+/// keeps its spaces, since they may be a legitimate part of it. This macro
+/// removes all leading and trailing Unicode whitespace instead, which suits
+/// answers like usernames or file paths that should never contain any.
+///
+/// # Examples
+///
+/// ```no_run
+/// use io_prompt_prototype::prompt_trimmed;
+///
+/// let username = prompt_trimmed!("Username? >");
+/// println!("Welcome, {}!", username);
+/// ```
+#[macro_export]
+macro_rules! prompt_trimmed {
+    ($($arg:tt)*) => {
+        $crate::prompt!($($arg)*).trim().to_string()
+    };
+}
+
+/// Prints to the standard output. Then reads a line of input as raw bytes.
+///
+/// This is a shorthand for calling [`print!`] and [`read_line_bytes`], and
+/// accepts input which isn't valid UTF-8.
+///
+/// # Examples
+///
+/// ```no_run
+/// use io_prompt_prototype::prompt_bytes;
+///
+/// let bytes = prompt_bytes!("Enter some bytes >");
+/// println!("Read {} bytes", bytes.len());
+/// ```
+#[macro_export]
+macro_rules! prompt_bytes {
+    ($($arg:tt)*) => {{
+        use std::io::Write;
+        let mut out = $crate::prompt_stream();
+        $crate::__private::write_prompt(&mut out, format_args!($($arg)*))
+            .unwrap_or_else(|e| panic!("failed writing to {}: {}", out, e));
+        out.flush().unwrap_or_else(|e| panic!("failed writing to {}: {}", out, e));
+        $crate::__private::read_answer($crate::read_line_bytes())
+            .unwrap_or_else(|e| panic!("failed reading from stdin: {}", e))
+    }};
+}
+
+/// Prints to the standard output, with a newline. Then reads a line of input.
+///
+/// This is a shorthand for calling [`println!`] and [`read_line_trimmed`]. The
+/// prompt can be printed to the standard error instead by calling
+/// [`set_prompt_stream`].
+///
+/// # Examples
+///
+/// ```no_run
+/// # fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+/// use io_prompt_prototype::promptln;
+///
+/// let num: u16 = promptln!("What's your favorite number? >").parse()?;
+/// println!("Oh, cool: {}!", num);
+/// # Ok(()) }
+/// ```
+#[macro_export]
+macro_rules! promptln {
+    () => {
+        $crate::promptln!("")
+    };
+    ($fmt:literal $($arg:tt)*) => {{
+        use std::io::Write;
+        let mut out = $crate::prompt_stream();
+        $crate::__private::write_prompt(&mut out, format_args!(concat!($fmt, "\n") $($arg)*))
+            .unwrap_or_else(|e| panic!("failed writing to {}: {}", out, e));
+        out.flush().unwrap_or_else(|e| panic!("failed writing to {}: {}", out, e));
+        $crate::__private::read_answer($crate::read_line_trimmed())
+            .unwrap_or_else(|e| panic!("failed reading from stdin: {}", e))
+    }};
+    ($msg:expr $(,)?) => {
+        $crate::promptln!("{}", $msg)
+    };
+}
+
+/// Prints to the standard error. Then reads a line of input.
+///
+/// This is a shorthand for calling [`eprint!`] and [`read_line_trimmed`].
+///
+/// # Examples
+///
+/// ```no_run
+/// # fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+/// use io_prompt_prototype::eprompt;
+///
+/// let num: u16 = eprompt!("What's your favorite number? >").parse()?;
+/// println!("Oh, cool: {}!", num);
+/// # Ok(()) }
+/// ```
+#[macro_export]
+macro_rules! eprompt {
+    ($fmt:literal $($arg:tt)*) => {{
+        use std::io::Write;
+        let mut out = $crate::Stream::Stderr;
+        $crate::__private::write_prompt(&mut out, format_args!($fmt $($arg)*))
+            .unwrap_or_else(|e| panic!("failed writing to {}: {}", out, e));
+        out.flush().unwrap_or_else(|e| panic!("failed writing to {}: {}", out, e));
+        $crate::__private::read_answer($crate::read_line_trimmed())
+            .unwrap_or_else(|e| panic!("failed reading from stdin: {}", e))
+    }};
+    ($msg:expr $(,)?) => {
+        $crate::eprompt!("{}", $msg)
+    };
+}
+
+/// Prints to the standard error, with a newline. Then reads a line of input.
+///
+/// This is a shorthand for calling [`eprintln!`] and [`read_line_trimmed`].
+///
+/// # Examples
+///
+/// ```no_run
+/// # fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+/// use io_prompt_prototype::epromptln;
+///
+/// let num: u16 = epromptln!("What's your favorite number? >").parse()?;
+/// println!("Oh, cool: {}!", num);
+/// # Ok(()) }
+/// ```
+#[macro_export]
+macro_rules! epromptln {
+    () => {
+        $crate::epromptln!("")
+    };
+    ($fmt:literal $($arg:tt)*) => {{
+        use std::io::Write;
+        let mut out = $crate::Stream::Stderr;
+        $crate::__private::write_prompt(&mut out, format_args!(concat!($fmt, "\n") $($arg)*))
+            .unwrap_or_else(|e| panic!("failed writing to {}: {}", out, e));
+        out.flush().unwrap_or_else(|e| panic!("failed writing to {}: {}", out, e));
+        $crate::__private::read_answer($crate::read_line_trimmed())
+            .unwrap_or_else(|e| panic!("failed reading from stdin: {}", e))
+    }};
+    ($msg:expr $(,)?) => {
+        $crate::epromptln!("{}", $msg)
+    };
+}
+
+/// Writes to a writer. Then reads a line of input from stdin.
+///
+/// This is a shorthand for calling [`write!`], flushing the writer and calling
+/// [`read_line_trimmed`]. The writer can be anything implementing
+/// [`Write`](std::io::Write), such as a TUI surface or a log-wrapped writer.
+/// See [`Prompter`] for reading from a custom reader as well.
+///
+/// # Examples
+///
+/// ```no_run
+/// use io_prompt_prototype::prompt_to;
+/// use std::io::stderr;
+///
+/// let name = prompt_to!(stderr(), "What's your {}? >", "name");
+/// println!("Hello, {}!", name);
+/// ```
+#[macro_export]
+macro_rules! prompt_to {
+    ($writer:expr, $($arg:tt)*) => {{
+        use std::io::Write;
+        let mut writer = $writer;
+        $crate::__private::write_prompt(&mut writer, format_args!($($arg)*))
+            .unwrap_or_else(|e| panic!("failed writing to writer: {}", e));
+        writer.flush().unwrap_or_else(|e| panic!("failed writing to writer: {}", e));
+        $crate::__private::read_answer($crate::read_line_trimmed())
+            .unwrap_or_else(|e| panic!("failed reading from stdin: {}", e))
+    }};
+}
+
+/// Writes to a writer, with a newline. Then reads a line of input from stdin.
+///
+/// This is a shorthand for calling [`writeln!`], flushing the writer and
+/// calling [`read_line_trimmed`]. See [`prompt_to!`] for details.
+///
+/// # Examples
+///
+/// ```no_run
+/// use io_prompt_prototype::promptln_to;
+/// use std::io::stderr;
+///
+/// let name = promptln_to!(stderr(), "What's your {}?", "name");
+/// println!("Hello, {}!", name);
+/// ```
+#[macro_export]
+macro_rules! promptln_to {
+    ($writer:expr, $($arg:tt)*) => {{
+        use std::io::Write;
+        let mut writer = $writer;
+        $crate::__private::write_prompt(&mut writer, format_args!("{}\n", format_args!($($arg)*)))
+            .unwrap_or_else(|e| panic!("failed writing to writer: {}", e));
+        writer.flush().unwrap_or_else(|e| panic!("failed writing to writer: {}", e));
+        $crate::__private::read_answer($crate::read_line_trimmed())
+            .unwrap_or_else(|e| panic!("failed reading from stdin: {}", e))
+    }};
+}
+
+/// Prints to the standard output. Then reads a line of input, returning any
+/// I/O errors.
+///
+/// This is the non-panicking counterpart to [`prompt!`]. The panicking macros
+/// are convenient for quick scripts in the same way [`println!`] is, while
+/// this variant is meant for libraries and long-running programs which need
+/// to recover from a closed or broken stdin/stdout. When stdout is a pipe
+/// whose reader has exited, an error of kind [`io::ErrorKind::BrokenPipe`] is
+/// returned, which a program can check for to exit quietly.
+///
+/// # Examples
+///
+/// ```no_run
+/// # fn main() -> std::io::Result<()> {
+/// use io_prompt_prototype::try_prompt;
+///
+/// let name = try_prompt!("What's your name? >")?;
+/// println!("Hello, {}!", name);
+/// # Ok(()) }
+/// ```
+#[macro_export]
+macro_rules! try_prompt {
+    ($fmt:literal $($arg:tt)*) => {
+        (|| -> std::io::Result<String> {
+            use std::io::Write;
+            let mut out = $crate::prompt_stream();
+            $crate::__private::write_prompt(&mut out, format_args!($fmt $($arg)*))?;
+            out.flush()?;
+            $crate::__private::read_answer($crate::read_line_trimmed())
+        })()
+    };
+    ($msg:expr $(,)?) => {
+        $crate::try_prompt!("{}", $msg)
+    };
+}
+
+/// Prints to the standard output, with a newline. Then reads a line of input,
+/// returning any I/O errors.
+///
+/// This is the non-panicking counterpart to [`promptln!`].
+///
+/// # Examples
+///
+/// ```no_run
+/// # fn main() -> std::io::Result<()> {
+/// use io_prompt_prototype::try_promptln;
+///
+/// let name = try_promptln!("What's your name? >")?;
+/// println!("Hello, {}!", name);
+/// # Ok(()) }
+/// ```
+#[macro_export]
+macro_rules! try_promptln {
+    () => {
+        $crate::try_promptln!("")
+    };
+    ($fmt:literal $($arg:tt)*) => {
+        (|| -> std::io::Result<String> {
+            use std::io::Write;
+            let mut out = $crate::prompt_stream();
+            $crate::__private::write_prompt(&mut out, format_args!(concat!($fmt, "\n") $($arg)*))?;
+            out.flush()?;
+            $crate::__private::read_answer($crate::read_line_trimmed())
+        })()
+    };
+    ($msg:expr $(,)?) => {
+        $crate::try_promptln!("{}", $msg)
+    };
+}
+
+/// Prints to the standard error. Then reads a line of input,
+/// returning any I/O errors.
+///
+/// This is the non-panicking counterpart to [`eprompt!`].
+///
+/// # Examples
+///
+/// ```no_run
+/// # fn main() -> std::io::Result<()> {
+/// use io_prompt_prototype::try_eprompt;
+///
+/// let name = try_eprompt!("What's your name? >")?;
+/// println!("Hello, {}!", name);
+/// # Ok(()) }
+/// ```
+#[macro_export]
+macro_rules! try_eprompt {
+    ($fmt:literal $($arg:tt)*) => {
+        (|| -> std::io::Result<String> {
+            use std::io::Write;
+            let mut out = $crate::Stream::Stderr;
+            $crate::__private::write_prompt(&mut out, format_args!($fmt $($arg)*))?;
+            out.flush()?;
+            $crate::__private::read_answer($crate::read_line_trimmed())
+        })()
+    };
+    ($msg:expr $(,)?) => {
+        $crate::try_eprompt!("{}", $msg)
+    };
+}
+
+/// Prints to the standard error, with a newline. Then reads a line of input,
+/// returning any I/O errors.
+///
+/// This is the non-panicking counterpart to [`epromptln!`].
+///
+/// # Examples
+///
+/// ```no_run
+/// # fn main() -> std::io::Result<()> {
+/// use io_prompt_prototype::try_epromptln;
+///
+/// let name = try_epromptln!("What's your name? >")?;
+/// println!("Hello, {}!", name);
+/// # Ok(()) }
+/// ```
+#[macro_export]
+macro_rules! try_epromptln {
+    () => {
+        $crate::try_epromptln!("")
+    };
+    ($fmt:literal $($arg:tt)*) => {
+        (|| -> std::io::Result<String> {
+            use std::io::Write;
+            let mut out = $crate::Stream::Stderr;
+            $crate::__private::write_prompt(&mut out, format_args!(concat!($fmt, "\n") $($arg)*))?;
+            out.flush()?;
+            $crate::__private::read_answer($crate::read_line_trimmed())
+        })()
+    };
+    ($msg:expr $(,)?) => {
+        $crate::try_epromptln!("{}", $msg)
+    };
+}
+
+/// Prints a yes/no question to the standard output. Then reads a line of input
+/// and returns it as a `bool`.
+///
+/// The answer is matched case-insensitively, ignoring surrounding whitespace:
+///
+/// - `y` and `yes` return `true`.
+/// - `n` and `no` return `false`.
+/// - An empty answer returns the default, which is `false` unless specified
+///   with `default = <bool>`.
+///
+/// Any other answer causes the question to be asked again. The accepted
+/// answers can be translated with [`set_locale`]. See [`Prompter::confirm`]
+/// for the underlying implementation.
+///
+/// # Examples
+///
+/// ```no_run
+/// use io_prompt_prototype::confirm;
+///
+/// if confirm!("Delete file? [y/N] >") {
+///     println!("Deleting file");
+/// }
+///
+/// if confirm!("Continue? [Y/n] >", default = true) {
+///     println!("Continuing");
+/// }
+/// ```
+#[macro_export]
+macro_rules! confirm {
+    ($msg:expr, default = $default:expr) => {
+        $crate::Prompter::stdio()
+            .confirm(&$msg, Some($default))
+            .unwrap_or_else(|e| panic!("failed prompting on stdin/stdout: {}", e))
+    };
+    ($($arg:tt)*) => {
+        $crate::Prompter::stdio()
+            .confirm(&format!($($arg)*), None)
+            .unwrap_or_else(|e| panic!("failed prompting on stdin/stdout: {}", e))
+    };
+}
+
+/// Prints to the standard output. Then reads a line of input and returns it as
+/// a `bool`, accepting a wide range of words.
+///
+/// Answers such as `yes`, `true`, `1`, and `on` return `true`, and answers
+/// such as `no`, `false`, `0`, and `off` return `false`. Matching is
+/// case-insensitive; see [`Prompter::prompt_bool`] for the full token table.
+/// Custom tokens can be passed with `true = [..], false = [..]`. Any other
+/// answer causes the question to be asked again. This panics on I/O errors, or
+/// if stdin is closed before a recognized answer is given.
+///
+/// # Examples
+///
+/// ```no_run
+/// use io_prompt_prototype::prompt_bool;
+///
+/// if prompt_bool!("Enable logging? >") {
+///     println!("Logging enabled");
+/// }
+///
+/// let weiter = prompt_bool!("Weiter? >", true = ["j", "ja"], false = ["n", "nein"]);
+/// ```
+#[macro_export]
+macro_rules! prompt_bool {
+    ($msg:expr, true = [$($truthy:expr),* $(,)?], false = [$($falsy:expr),* $(,)?] $(,)?) => {
+        $crate::Prompter::stdio()
+            .prompt_bool_with(&$msg, &[$($truthy),*], &[$($falsy),*])
+            .unwrap_or_else(|e| panic!("failed prompting on stdin/stdout: {}", e))
+    };
+    ($($arg:tt)*) => {
+        $crate::Prompter::stdio()
+            .prompt_bool(&format!($($arg)*))
+            .unwrap_or_else(|e| panic!("failed prompting on stdin/stdout: {}", e))
+    };
+}
+
+/// Prints to the standard output. Then reads a line of input, echoing it back
+/// and asking for confirmation until it's confirmed.
+///
+/// This suits critical answers such as an email or IP address. A rejected
+/// answer causes the original question to be asked again. This panics on I/O
+/// errors, or if stdin is closed before an answer is confirmed. See
+/// [`Prompter::prompt_confirmed`] for the underlying implementation.
+///
+/// # Examples
+///
+/// ```no_run
+/// use io_prompt_prototype::prompt_confirmed;
+///
+/// let email = prompt_confirmed!("Email? >");
+/// println!("Sending the invitation to {}", email);
+/// ```
+#[macro_export]
+macro_rules! prompt_confirmed {
+    ($($arg:tt)*) => {
+        $crate::Prompter::stdio()
+            .prompt_confirmed(&format!($($arg)*))
+            .unwrap_or_else(|e| panic!("failed prompting on stdin/stdout: {}", e))
+    };
+}
+
+/// Prints to the standard output. Then reads a line of input and parses it.
+///
+/// This is a shorthand for calling [`prompt!`] followed by [`str::parse`].
+/// The trailing newline is removed before parsing. Like [`prompt!`] this
+/// panics on I/O errors, while parse errors are returned as a
+/// [`PromptParseError`], which includes the answer that was given. See
+/// [`prompt_parse_loop!`] for a variant which re-prompts instead.
+///
+/// # Examples
+///
+/// ```no_run
+/// # fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+/// use io_prompt_prototype::prompt_parse;
+///
+/// let num: u16 = prompt_parse!("What's your favorite number? >")?;
+/// println!("Oh, cool: {}!", num);
+/// # Ok(()) }
+/// ```
+///
+/// Any type implementing [`FromStr`](std::str::FromStr) can be parsed:
+///
+/// ```no_run
+/// # fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+/// use io_prompt_prototype::prompt_parse;
+/// use std::str::FromStr;
+///
+/// #[derive(Debug)]
+/// enum Color {
+///     Red,
+///     Blue,
+/// }
+///
+/// impl FromStr for Color {
+///     type Err = String;
+///
+///     fn from_str(s: &str) -> Result<Self, Self::Err> {
+///         match s {
+///             "red" => Ok(Color::Red),
+///             "blue" => Ok(Color::Blue),
+///             _ => Err(format!("unknown color: {}", s)),
+///         }
+///     }
+/// }
+///
+/// let color: Color = prompt_parse!("What's your favorite color? >")?;
+/// println!("Oh, cool: {:?}!", color);
+/// # Ok(()) }
+/// ```
+#[macro_export]
+macro_rules! prompt_parse {
+    ($($arg:tt)*) => {
+        $crate::Prompter::stdio()
+            .parse(&format!($($arg)*))
+            .unwrap_or_else(|e| panic!("failed prompting on stdin/stdout: {}", e))
+    };
+}
+
+/// Prints to the standard output. Then reads a line of input and parses it as
+/// a list of delimited values.
+///
+/// Each element is trimmed, and empty elements are skipped. This panics on I/O
+/// errors, while the first parse error is returned. See [`Prompter::list`]
+/// for the underlying implementation.
+///
+/// # Examples
+///
+/// ```no_run
+/// # fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+/// use io_prompt_prototype::prompt_list;
+///
+/// let ports: Vec<u32> = prompt_list!("Ports (comma separated) >", ',')?;
+/// println!("Listening on {} ports", ports.len());
+/// # Ok(()) }
+/// ```
+///
+/// ```no_run
+/// # fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+/// use io_prompt_prototype::prompt_list;
+///
+/// let tags: Vec<String> = prompt_list!("Tags (space separated) >", ' ')?;
+/// println!("Tagged with {}", tags.join(", "));
+/// # Ok(()) }
+/// ```
+#[macro_export]
+macro_rules! prompt_list {
+    ($msg:expr, $delimiter:expr $(,)?) => {
+        $crate::Prompter::stdio()
+            .list(&$msg, $delimiter)
+            .unwrap_or_else(|e| panic!("failed prompting on stdin/stdout: {}", e))
+    };
+}
+
+/// Prints to the standard output and reads a line of input, repeatedly,
+/// collecting the answers until a blank one is given.
+///
+/// The prompt is printed again before each answer. Answers are trimmed, and
+/// collecting stops at the first blank answer or when stdin is closed, neither
+/// of which is included in the returned `Vec`. See [`Prompter::prompt_repeat`]
+/// for the underlying implementation. This panics on I/O errors.
+///
+/// # Examples
+///
+/// ```no_run
+/// use io_prompt_prototype::prompt_repeat;
+///
+/// let hosts = prompt_repeat!("Add host (blank to finish) >");
+/// println!("Deploying to {} hosts", hosts.len());
+/// ```
+#[macro_export]
+macro_rules! prompt_repeat {
+    ($($arg:tt)*) => {
+        $crate::Prompter::stdio()
+            .prompt_repeat(&format!($($arg)*))
+            .unwrap_or_else(|e| panic!("failed prompting on stdin/stdout: {}", e))
+    };
+}
+
+/// Prints to the standard output. Then reads a line of input and parses it,
+/// keeping the original text.
+///
+/// This behaves like [`prompt_parse!`], but returns an [`Answer`] holding both
+/// the parsed value and the text it was parsed from.
+///
+/// # Examples
+///
+/// ```no_run
+/// # fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+/// use io_prompt_prototype::{prompt_answer, Answer};
+///
+/// let num: Answer<u32> = prompt_answer!("What's your favorite number? >")?;
+/// let doubled = *num * 2;
+/// println!("{} doubled is {}", num.as_str(), doubled);
+/// # Ok(()) }
+/// ```
+#[macro_export]
+macro_rules! prompt_answer {
+    ($($arg:tt)*) => {
+        $crate::Prompter::stdio()
+            .answer(&format!($($arg)*))
+            .unwrap_or_else(|e| panic!("failed prompting on stdin/stdout: {}", e))
+    };
+}
+
+/// Prints to the standard output. Then reads a line of input and parses it,
+/// re-prompting until parsing succeeds.
+///
+/// Each parse error is printed before asking again. This panics on I/O
+/// errors, or if stdin is closed before a valid answer is given. See
+/// [`Prompter::parse_loop`] for the underlying implementation.
+///
+/// Passing `max_retries = <n>` before the message limits how many times an
+/// invalid answer is asked for again. The macro then returns an `io::Result`
+/// instead of panicking, holding a [`PromptParseError`] with the last answer
+/// once the retries run out.
+///
+/// # Examples
+///
+/// ```no_run
+/// # fn main() -> std::io::Result<()> {
+/// use io_prompt_prototype::prompt_parse_loop;
+///
+/// let num: u16 = prompt_parse_loop!("What's your favorite number? >");
+/// println!("Oh, cool: {}!", num);
+///
+/// let num: u16 = prompt_parse_loop!(max_retries = 3, "And your least favorite? >")?;
+/// # Ok(()) }
+/// ```
+#[macro_export]
+macro_rules! prompt_parse_loop {
+    (max_retries = $max:expr, $($arg:tt)*) => {
+        $crate::Prompter::stdio()
+            .max_retries($max)
+            .parse_loop(&format!($($arg)*))
+    };
+    ($($arg:tt)*) => {
+        $crate::Prompter::stdio()
+            .parse_loop(&format!($($arg)*))
+            .unwrap_or_else(|e| panic!("failed prompting on stdin/stdout: {}", e))
+    };
+}
+
+/// Prints several prompts to the standard output in turn, parsing each answer
+/// into its own type, and returns the values as a tuple.
+///
+/// Each field is written as `<message> => <type>`, separated by commas, where
+/// the message is a string and the type implements
+/// [`FromStr`](std::str::FromStr) with an error implementing
+/// [`Display`](std::fmt::Display). The prompts are asked in order, and each
+/// answer is parsed like [`prompt_parse_loop!`] does: a parse error is printed
+/// and only the prompt for that field is asked again, keeping the answers
+/// given so far. The returned tuple has one element per field, so a single
+/// field returns a one-element tuple. This panics on I/O errors, or if stdin
+/// is closed before every field is answered.
+///
+/// # Examples
+///
+/// ```
+/// use io_prompt_prototype::{end_script, multi_prompt, push_scripted_answer};
+///
+/// push_scripted_answer("Ferris");
+/// push_scripted_answer("old");
+/// push_scripted_answer("7");
+///
+/// let (name, age): (String, u8) = multi_prompt! {
+///     "Name? >" => String,
+///     "Age? >" => u8,
+/// };
+/// assert_eq!(name, "Ferris");
+/// assert_eq!(age, 7);
+/// # end_script();
+/// ```
+#[macro_export]
+macro_rules! multi_prompt {
+    ($($msg:expr => $ty:ty),+ $(,)?) => {{
+        let mut prompter = $crate::Prompter::stdio();
+        ($(
+            prompter
+                .parse_loop::<$ty>(&$msg)
+                .unwrap_or_else(|e| panic!("failed prompting on stdin/stdout: {}", e)),
+        )+)
+    }};
+}
+
+/// Prints to the standard output. Then reads a line of input and parses it as
+/// a number, re-prompting until parsing succeeds.
+///
+/// The type of number is inferred from context, and can be any of the standard
+/// integer and float types. `Please enter a number` is printed each time the
+/// answer can't be parsed. This panics on I/O errors. See
+/// [`Prompter::number`] for a non-panicking variant.
+///
+/// Passing `max_retries = <n>` before the message limits how many times an
+/// invalid answer is asked for again. The macro then returns an `io::Result`
+/// instead of panicking, holding a [`PromptParseError`] with the last answer
+/// once the retries run out.
+///
+/// # Examples
+///
+/// ```no_run
+/// use io_prompt_prototype::prompt_number;
+///
+/// let age: u8 = prompt_number!("Age? >");
+/// println!("Next year you'll be {}", age + 1);
+/// ```
+///
+/// ```no_run
+/// use io_prompt_prototype::prompt_number;
+///
+/// let celsius: f64 = prompt_number!("Temperature in {}? >", "Celsius");
+/// println!("That's {}°F", celsius * 1.8 + 32.0);
+/// ```
+///
+/// ```no_run
+/// use io_prompt_prototype::prompt_number;
+///
+/// let count: Result<u32, _> = prompt_number!(max_retries = 2, "How many? >");
+/// ```
+#[macro_export]
+macro_rules! prompt_number {
+    (max_retries = $max:expr, $($arg:tt)*) => {
+        $crate::Prompter::stdio()
+            .max_retries($max)
+            .number(&format!($($arg)*))
+    };
+    ($($arg:tt)*) => {
+        $crate::Prompter::stdio()
+            .number(&format!($($arg)*))
+            .unwrap_or_else(|e| panic!("failed prompting on stdin/stdout: {}", e))
+    };
+}
+
+/// Prints to the standard output. Then reads a line of input and parses it,
+/// re-prompting until it parses to a value within a range.
+///
+/// The range can be any of Rust's range types, such as `1..=65535` or `0..`,
+/// and the answer is parsed into the type of its bounds. Parse errors and
+/// out-of-range values are printed before asking again. This panics on I/O
+/// errors. See [`Prompter::range`] for a non-panicking variant.
+///
+/// Passing `max_retries = <n>` limits how many times an invalid answer is asked
+/// for again. The macro then returns an `io::Result` instead of panicking,
+/// holding a [`RetriesExhausted`] error once the retries run out.
+///
+/// # Examples
+///
+/// ```no_run
+/// use io_prompt_prototype::prompt_range;
+///
+/// let port: u16 = prompt_range!("Port? >", 1..=65535);
+/// let percentage = prompt_range!("Percentage? >", 0.0..=100.0);
+/// println!("Listening on port {} at {}%", port, percentage);
+///
+/// let lucky: Result<u8, _> = prompt_range!("Lucky number? >", 1..=9, max_retries = 3);
+/// ```
+#[macro_export]
+macro_rules! prompt_range {
+    ($msg:expr, $range:expr, max_retries = $max:expr $(,)?) => {
+        $crate::Prompter::stdio()
+            .max_retries($max)
+            .range(&$msg, $range)
+    };
+    ($msg:expr, $range:expr $(,)?) => {
+        $crate::Prompter::stdio()
+            .range(&$msg, $range)
+            .unwrap_or_else(|e| panic!("failed prompting on stdin/stdout: {}", e))
+    };
+}
+
+/// Prints to the standard output. Then reads a line of input, re-prompting
+/// until the answer is accepted by a predicate.
+///
+/// An optional message can be passed with `invalid = <msg>`, which is printed
+/// each time an answer is rejected.
+///
+/// This panics on I/O errors. Reaching the end of stdin before an answer is
+/// accepted is treated as an error too, so a closed stdin can never cause an
+/// infinite loop. See [`Prompter::prompt_until`] for a non-panicking variant.
+///
+/// Passing `max_retries = <n>` limits how many times an invalid answer is asked
+/// for again. The macro then returns an `io::Result` instead of panicking,
+/// holding a [`RetriesExhausted`] error once the retries run out.
+///
+/// # Examples
+///
+/// ```no_run
+/// use io_prompt_prototype::prompt_until;
+///
+/// let name = prompt_until!("Name? >", |s: &str| !s.is_empty());
+/// let age = prompt_until!(
+///     "Age? >",
+///     |s: &str| s.parse::<u8>().is_ok(),
+///     invalid = "Please enter a number between 0 and 255",
+/// );
+/// println!("{} is {} years old", name, age);
+///
+/// let code = prompt_until!("Code? >", |s: &str| s.len() == 6, max_retries = 2);
+/// if code.is_err() {
+///     println!("Too many invalid codes");
+/// }
+/// ```
+#[macro_export]
+macro_rules! prompt_until {
+    ($msg:expr, $is_valid:expr, invalid = $invalid:expr, max_retries = $max:expr $(,)?) => {
+        $crate::Prompter::stdio()
+            .max_retries($max)
+            .prompt_until(&$msg, $is_valid, Some(&$invalid))
+    };
+    ($msg:expr, $is_valid:expr, max_retries = $max:expr $(,)?) => {
+        $crate::Prompter::stdio()
+            .max_retries($max)
+            .prompt_until(&$msg, $is_valid, None)
+    };
+    ($msg:expr, $is_valid:expr, invalid = $invalid:expr $(,)?) => {
+        $crate::Prompter::stdio()
+            .prompt_until(&$msg, $is_valid, Some(&$invalid))
+            .unwrap_or_else(|e| panic!("failed prompting on stdin/stdout: {}", e))
+    };
+    ($msg:expr, $is_valid:expr $(,)?) => {
+        $crate::Prompter::stdio()
+            .prompt_until(&$msg, $is_valid, None)
+            .unwrap_or_else(|e| panic!("failed prompting on stdin/stdout: {}", e))
+    };
+}
+
+/// Prints to the standard output, with a hint showing the default. Then reads a
+/// line of input, returning the default if the answer is empty.
+///
+/// The default can be any value implementing `Into<String>`. Surrounding
+/// whitespace is trimmed from the answer. See [`Prompter::prompt_default`]
+/// for how the hint is rendered, and [`prompt_or!`] for a variant without a
+/// hint.
+///
+/// # Examples
+///
+/// ```no_run
+/// use io_prompt_prototype::prompt_default;
+///
+/// // Prints "What's your name? > [anonymous] "
+/// let name = prompt_default!("What's your name? >", "anonymous");
+/// println!("Hello, {}!", name);
+/// ```
+#[macro_export]
+macro_rules! prompt_default {
+    ($msg:expr, $default:expr $(,)?) => {
+        $crate::Prompter::stdio()
+            .prompt_default(&$msg, $default)
+            .unwrap_or_else(|e| panic!("failed prompting on stdin/stdout: {}", e))
+    };
+}
+
+/// Prints to the standard output. Then reads a line of input, returning a
+/// default if the answer is empty.
+///
+/// Unlike [`prompt_default!`] the default isn't shown in the prompt.
+///
+/// # Examples
+///
+/// ```no_run
+/// use io_prompt_prototype::prompt_or;
+///
+/// let name = prompt_or!("What's your name? [anonymous] >", "anonymous");
+/// println!("Hello, {}!", name);
+/// ```
+#[macro_export]
+macro_rules! prompt_or {
+    ($msg:expr, $default:expr $(,)?) => {
+        $crate::Prompter::stdio()
+            .prompt_or(&$msg, $default)
+            .unwrap_or_else(|e| panic!("failed prompting on stdin/stdout: {}", e))
+    };
+}
+
+/// Prints a numbered list of options to the standard output. Then reads the
+/// number of the chosen option, re-prompting until a valid one is given.
+///
+/// Returns the zero-based index of the chosen option. This panics on I/O
+/// errors, or if stdin is closed before a valid option is chosen. See
+/// [`Prompter::select`] for how the options are rendered.
+///
+/// # Examples
+///
+/// ```no_run
+/// use io_prompt_prototype::select;
+///
+/// let colors = ["red", "green", "blue"];
+/// let index = select!("Pick a color:", &colors);
+/// println!("You picked {}", colors[index]);
+/// ```
+#[macro_export]
+macro_rules! select {
+    ($msg:expr, $options:expr $(,)?) => {
+        $crate::Prompter::stdio()
+            .select(&$msg, $options)
+            .unwrap_or_else(|e| panic!("failed prompting on stdin/stdout: {}", e))
+    };
+}
+
+/// Prints a numbered list of the entries of a map to the standard output.
+/// Then reads the number of the chosen entry, re-prompting until a valid one
+/// is given.
+///
+/// Returns the key and value of the chosen entry, as references into the map.
+/// The entries are always listed sorted by key, so a `HashMap` is numbered the
+/// same way on every run. This panics on I/O errors, or if stdin is closed
+/// before a valid entry is chosen. See [`Prompter::select_map`] for how the
+/// entries are rendered.
+///
+/// # Examples
+///
+/// ```no_run
+/// use io_prompt_prototype::prompt_indexed_map;
+/// use std::collections::BTreeMap;
+///
+/// let sizes = BTreeMap::from([("small", 1), ("medium", 2), ("large", 4)]);
+/// let (size, cpus) = prompt_indexed_map!("Pick a size:", &sizes);
+/// println!("A {} instance has {} CPUs", size, cpus);
+/// ```
+#[macro_export]
+macro_rules! prompt_indexed_map {
+    ($msg:expr, $map:expr $(,)?) => {
+        $crate::Prompter::stdio()
+            .select_map(&$msg, $map)
+            .unwrap_or_else(|e| panic!("failed prompting on stdin/stdout: {}", e))
+    };
+}
+
+/// Prints to the standard output. Then reads a line of input and parses it as
+/// one of the listed choices, re-prompting until parsing succeeds.
+///
+/// The answer is parsed with [`FromStr`](std::str::FromStr), and the valid
+/// choices are listed each time parsing fails. This panics on I/O errors, or
+/// if stdin is closed before a valid answer is given. See
+/// [`Prompter::choice`] for the underlying implementation.
+///
+/// # Examples
+///
+/// ```no_run
+/// use io_prompt_prototype::prompt_choice;
+/// use std::str::FromStr;
+///
+/// #[derive(Debug)]
+/// enum Mode {
+///     Fast,
+///     Slow,
+/// }
+///
+/// impl FromStr for Mode {
+///     type Err = String;
+///
+///     fn from_str(s: &str) -> Result<Self, Self::Err> {
+///         match s {
+///             "fast" => Ok(Mode::Fast),
+///             "slow" => Ok(Mode::Slow),
+///             _ => Err(format!("unknown mode: {}", s)),
+///         }
+///     }
+/// }
+///
+/// let mode: Mode = prompt_choice!("Mode? >", &["fast", "slow"]);
+/// println!("Running in {:?} mode", mode);
+/// ```
+#[macro_export]
+macro_rules! prompt_choice {
+    ($msg:expr, $choices:expr $(,)?) => {
+        $crate::Prompter::stdio()
+            .choice(&$msg, $choices)
+            .unwrap_or_else(|e| panic!("failed prompting on stdin/stdout: {}", e))
+    };
+}
+
+/// Prints to the standard output. Then reads a line of input, re-prompting
+/// until it matches one of a fixed set of values.
+///
+/// The answer is trimmed and matched case-insensitively, unless
+/// `case_sensitive = true` is passed, and the matching value is returned as
+/// spelled in the set. The allowed values are printed each time the answer
+/// doesn't match. This panics on I/O errors, or if stdin is closed before a
+/// valid answer is given. See [`Prompter::matching`] for the underlying
+/// implementation.
+///
+/// # Examples
+///
+/// ```no_run
+/// use io_prompt_prototype::prompt_matching;
+///
+/// let env = prompt_matching!("Env? >", &["dev", "staging", "prod"]);
+/// let tag = prompt_matching!("Tag? >", &["RC", "rc"], case_sensitive = true);
+/// println!("Deploying {} to {}", tag, env);
+/// ```
+#[macro_export]
+macro_rules! prompt_matching {
+    ($msg:expr, $allowed:expr, case_sensitive = $case_sensitive:expr $(,)?) => {
+        $crate::Prompter::stdio()
+            .matching(&$msg, $allowed, $case_sensitive)
+            .unwrap_or_else(|e| panic!("failed prompting on stdin/stdout: {}", e))
+    };
+    ($msg:expr, $allowed:expr $(,)?) => {
+        $crate::prompt_matching!($msg, $allowed, case_sensitive = false)
+    };
+}
+
+/// Prints to the standard output. Then reads a line of input, returning `None`
+/// at the end of the input.
+///
+/// This is a shorthand for calling [`print!`] and [`read_line_opt`], which
+/// makes it possible to stop a prompt loop cleanly when the user presses
+/// Ctrl-D.
+///
+/// # Examples
+///
+/// ```no_run
+/// use io_prompt_prototype::prompt_opt;
+///
+/// while let Some(line) = prompt_opt!("> ") {
+///     println!("Read: {}", line);
+/// }
+/// ```
+#[macro_export]
+macro_rules! prompt_opt {
+    ($($arg:tt)*) => {{
+        use std::io::Write;
+        let mut out = $crate::prompt_stream();
+        $crate::__private::write_prompt(&mut out, format_args!($($arg)*))
+            .unwrap_or_else(|e| panic!("failed writing to {}: {}", out, e));
+        out.flush().unwrap_or_else(|e| panic!("failed writing to {}: {}", out, e));
+        $crate::__private::read_answer($crate::read_line_opt())
+            .unwrap_or_else(|e| panic!("failed reading from stdin: {}", e))
     }};
 }
@@ -0,0 +1,128 @@
+use std::io::{self, BufRead};
+
+use crate::{is_eof_marker, script};
+
+/// What to do when a line is longer than the limit passed to
+/// [`read_line_limited`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Overflow {
+    /// Return an error of kind [`io::ErrorKind::InvalidData`].
+    Error,
+    /// Return the line truncated to the limit.
+    Truncate,
+}
+
+/// Reads a line of input from stdin, accepting at most `max` bytes.
+///
+/// This guards against runaway input, such as an accidental paste of a huge
+/// file, which would otherwise grow the returned string without bound. The
+/// limit applies to the line without its line terminator. If the line is
+/// longer, `overflow` decides whether an error is returned or the line is
+/// truncated; truncation never splits a character, so the result may be a
+/// few bytes shorter than `max`. Either way, the rest of the line is read and
+/// discarded without being stored, so the next read starts on the next line.
+///
+/// The trailing newline is removed as with [`read_line_trimmed`].
+///
+/// [`read_line_trimmed`]: crate::read_line_trimmed
+///
+/// # Examples
+///
+/// ```no_run
+/// # fn main() -> std::io::Result<()> {
+/// use io_prompt_prototype::{read_line_limited, Overflow};
+///
+/// print!("Username: >");
+/// let username = read_line_limited(64, Overflow::Error)?;
+/// # Ok(()) }
+/// ```
+pub fn read_line_limited(max: usize, overflow: Overflow) -> io::Result<String> {
+    read_line_limited_from(&mut script::input(), max, overflow)
+}
+
+/// Reads a line of input from a reader, accepting at most `max` bytes.
+///
+/// This behaves exactly like [`read_line_limited`], but reads from an
+/// arbitrary [`BufRead`] rather than from stdin.
+///
+/// # Examples
+///
+/// ```
+/// # fn main() -> std::io::Result<()> {
+/// use io_prompt_prototype::{read_line_limited_from, Overflow};
+/// use std::io::Cursor;
+///
+/// let mut input = Cursor::new(b"abcdef\nabcdef\nabc\n");
+/// assert!(read_line_limited_from(&mut input, 3, Overflow::Error).is_err());
+/// assert_eq!(read_line_limited_from(&mut input, 3, Overflow::Truncate)?, "abc");
+/// assert_eq!(read_line_limited_from(&mut input, 3, Overflow::Error)?, "abc");
+/// # Ok(()) }
+/// ```
+pub fn read_line_limited_from<R: BufRead>(
+    reader: &mut R,
+    max: usize,
+    overflow: Overflow,
+) -> io::Result<String> {
+    // One byte more than the limit is kept, which is enough to tell whether
+    // the line is too long, and leaves room for the `\r` of a `\r\n`.
+    let mut line = Vec::new();
+    let mut overflowed = false;
+    loop {
+        let available = match reader.fill_buf() {
+            Ok(available) => available,
+            Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+            Err(err) => return Err(err),
+        };
+        if available.is_empty() {
+            break;
+        }
+        let newline = available.iter().position(|&b| b == b'\n');
+        let chunk = &available[..newline.unwrap_or(available.len())];
+        let room = max.saturating_add(1).saturating_sub(line.len());
+        if chunk.len() > room {
+            overflowed = true;
+        }
+        line.extend_from_slice(&chunk[..chunk.len().min(room)]);
+        let used = newline.map_or(available.len(), |i| i + 1);
+        reader.consume(used);
+        if newline.is_some() {
+            break;
+        }
+    }
+    if !overflowed && line.last() == Some(&b'\r') {
+        line.pop();
+    }
+    if is_eof_marker(&line) {
+        return Ok(String::new());
+    }
+    let truncated = overflowed || line.len() > max;
+    if truncated {
+        if overflow == Overflow::Error {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("line is longer than the limit of {} bytes", max),
+            ));
+        }
+        line.truncate(max);
+    }
+    let mut line = match String::from_utf8(line) {
+        Ok(line) => line,
+        // The truncation may have split the last character.
+        Err(err) if truncated && err.utf8_error().error_len().is_none() => {
+            let valid = err.utf8_error().valid_up_to();
+            let mut line = err.into_bytes();
+            line.truncate(valid);
+            String::from_utf8(line).expect("prefix is valid UTF-8")
+        }
+        Err(_) => {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "stream did not contain valid UTF-8",
+            ))
+        }
+    };
+    if !truncated && line.ends_with(['\u{2028}', '\u{2029}']) {
+        line.pop();
+    }
+    Ok(line)
+}
@@ -0,0 +1,235 @@
+use std::sync::{PoisonError, RwLock};
+
+/// The locale used by prompters which weren't given one.
+static LOCALE: RwLock<Locale> = RwLock::new(Locale::ENGLISH);
+
+/// The answers accepted and the messages written by the built-in prompts.
+///
+/// Methods such as [`Prompter::confirm`] and [`Prompter::number`] accept
+/// English answers like `yes`, and re-prompt with English messages like
+/// `Please enter a number`. A `Locale` replaces these, so programs in other
+/// languages can use the same loops. The easiest way to create one is to
+/// change the fields of [`Locale::ENGLISH`] which need translating.
+///
+/// A locale can be registered for the whole process with [`set_locale`], which
+/// the prompt macros pick up, or given to a single [`Prompter`] with
+/// [`Prompter::locale`]. The hints in prompt messages, such as `[y/N]`, are
+/// written by the caller and need translating separately.
+///
+/// Answers are matched against the tokens case-insensitively. In the message
+/// templates, `{}` is replaced by the value described for each field.
+///
+/// [`Prompter`]: crate::Prompter
+/// [`Prompter::confirm`]: crate::Prompter::confirm
+/// [`Prompter::number`]: crate::Prompter::number
+/// [`Prompter::locale`]: crate::Prompter::locale
+///
+/// # Examples
+///
+/// ```no_run
+/// use io_prompt_prototype::{confirm, set_locale, Locale};
+///
+/// set_locale(Locale {
+///     yes: &["o", "oui"],
+///     no: &["n", "non"],
+///     enter_number: "Veuillez saisir un nombre",
+///     ..Locale::ENGLISH
+/// });
+/// if confirm!("Supprimer le fichier ? [o/N] >") {
+///     println!("Suppression du fichier");
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Locale {
+    /// The answers accepted as yes by confirmations.
+    pub yes: &'static [&'static str],
+    /// The answers accepted as no by confirmations.
+    pub no: &'static [&'static str],
+    /// The answers accepted as quit by [`Prompter::yes_no_quit`].
+    ///
+    /// [`Prompter::yes_no_quit`]: crate::Prompter::yes_no_quit
+    pub quit: &'static [&'static str],
+    /// The question asked by [`Prompter::prompt_confirmed`], where `{}` is the
+    /// answer being confirmed.
+    ///
+    /// [`Prompter::prompt_confirmed`]: crate::Prompter::prompt_confirmed
+    pub confirm_answer: &'static str,
+    /// Written when an answer isn't a number.
+    pub enter_number: &'static str,
+    /// Written by [`Prompter::range`] when a value is out of a range with an
+    /// inclusive start and end, such as `1..=10`, where the first `{}` is the
+    /// start and the second the end.
+    ///
+    /// [`Prompter::range`]: crate::Prompter::range
+    pub range_between: &'static str,
+    /// Written by [`Prompter::range`] when a value is out of a range with an
+    /// inclusive start and an exclusive end, such as `1..10`, where the first
+    /// `{}` is the start and the second the end.
+    ///
+    /// [`Prompter::range`]: crate::Prompter::range
+    pub range_at_least_less_than: &'static str,
+    /// Written by [`Prompter::range`] when a value is out of a range with an
+    /// exclusive start and an inclusive end, where the first `{}` is the start
+    /// and the second the end.
+    ///
+    /// [`Prompter::range`]: crate::Prompter::range
+    pub range_greater_than_at_most: &'static str,
+    /// Written by [`Prompter::range`] when a value is out of a range with an
+    /// exclusive start and end, where the first `{}` is the start and the
+    /// second the end.
+    ///
+    /// [`Prompter::range`]: crate::Prompter::range
+    pub range_greater_than_less_than: &'static str,
+    /// Written by [`Prompter::range`] when a value is below a range with an
+    /// inclusive start and no end, such as `1..`, where `{}` is the start.
+    ///
+    /// [`Prompter::range`]: crate::Prompter::range
+    pub range_at_least: &'static str,
+    /// Written by [`Prompter::range`] when a value is below a range with an
+    /// exclusive start and no end, where `{}` is the start.
+    ///
+    /// [`Prompter::range`]: crate::Prompter::range
+    pub range_greater_than: &'static str,
+    /// Written by [`Prompter::range`] when a value is above a range with no
+    /// start and an inclusive end, such as `..=10`, where `{}` is the end.
+    ///
+    /// [`Prompter::range`]: crate::Prompter::range
+    pub range_at_most: &'static str,
+    /// Written by [`Prompter::range`] when a value is above a range with no
+    /// start and an exclusive end, such as `..10`, where `{}` is the end.
+    ///
+    /// [`Prompter::range`]: crate::Prompter::range
+    pub range_less_than: &'static str,
+    /// Written when an answer isn't one of the choices, where `{}` is the
+    /// choices separated by commas.
+    pub choose_one_of: &'static str,
+    /// Written by [`Prompter::select_fuzzy`] when no option matches a query,
+    /// where `{}` is the query.
+    ///
+    /// [`Prompter::select_fuzzy`]: crate::Prompter::select_fuzzy
+    pub no_option_matches: &'static str,
+    /// Written by [`Prompter::select_fuzzy`] before the options a query
+    /// matches, when it matches more than one.
+    ///
+    /// [`Prompter::select_fuzzy`]: crate::Prompter::select_fuzzy
+    pub several_options_match: &'static str,
+    /// Written when an answer isn't an email address.
+    pub enter_email: &'static str,
+    /// Written when an answer isn't a URL.
+    pub enter_url: &'static str,
+    /// Written when an answer isn't an IP address.
+    pub enter_ip: &'static str,
+    /// Written when an answer isn't an IP address followed by a port.
+    pub enter_socket_addr: &'static str,
+    /// Written when an answer isn't a percentage between 0% and 100%.
+    pub enter_percent: &'static str,
+    /// Written when an answer isn't an amount of money.
+    pub enter_amount: &'static str,
+    /// Written when an answer isn't a date.
+    pub enter_date: &'static str,
+    /// Written when an answer isn't a duration.
+    pub enter_duration: &'static str,
+    /// Written when an answer isn't valid JSON of the expected shape, where
+    /// `{}` is the error.
+    pub invalid_json: &'static str,
+    /// Written when no path is entered.
+    pub enter_path: &'static str,
+    /// Written when a path doesn't exist, where `{}` is the path.
+    pub path_not_found: &'static str,
+    /// Written when a path isn't a file, where `{}` is the path.
+    pub not_a_file: &'static str,
+    /// Written when a path isn't a directory, where `{}` is the path.
+    pub not_a_dir: &'static str,
+    /// Written by the password confirmation prompts when the two passwords
+    /// entered differ, before asking again.
+    pub passwords_do_not_match: &'static str,
+}
+
+impl Locale {
+    /// The English locale, which is used by default.
+    pub const ENGLISH: Locale = Locale {
+        yes: &["y", "yes"],
+        no: &["n", "no"],
+        quit: &["q", "quit"],
+        confirm_answer: "You entered: {}. Is that correct? [y/N] ",
+        enter_number: "Please enter a number",
+        range_between: "expected a value between {} and {}",
+        range_at_least_less_than: "expected a value at least {} and less than {}",
+        range_greater_than_at_most: "expected a value greater than {} and at most {}",
+        range_greater_than_less_than: "expected a value greater than {} and less than {}",
+        range_at_least: "expected a value of at least {}",
+        range_greater_than: "expected a value greater than {}",
+        range_at_most: "expected a value of at most {}",
+        range_less_than: "expected a value less than {}",
+        choose_one_of: "Please choose one of: {}",
+        no_option_matches: "No option matches {}",
+        several_options_match: "Several options match:",
+        enter_email: "Please enter an email address",
+        enter_url: "Please enter a URL",
+        enter_ip: "Please enter an IP address",
+        enter_socket_addr: "Please enter an IP address and port",
+        enter_percent: "Please enter a percentage between 0% and 100%, such as 50%",
+        enter_amount: "Please enter an amount, such as $1,234.56",
+        enter_date: "Please enter a date as YYYY-MM-DD, such as 2024-05-17",
+        enter_duration: "Please enter a duration, such as 30s, 5m or 2h",
+        invalid_json: "Please enter valid JSON: {}",
+        enter_path: "Please enter a path",
+        path_not_found: "{} does not exist",
+        not_a_file: "{} is not a file",
+        not_a_dir: "{} is not a directory",
+        passwords_do_not_match: "Passwords do not match, please try again.",
+    };
+}
+
+impl Default for Locale {
+    fn default() -> Self {
+        Locale::ENGLISH
+    }
+}
+
+/// Sets the locale used by prompts for the whole process.
+///
+/// This affects all subsequent prompt macros, and every [`Prompter`] created
+/// afterwards which isn't given a locale with [`Prompter::locale`], from any
+/// thread. It's meant to be called once at startup. See [`Locale`] for an
+/// example.
+///
+/// [`Prompter`]: crate::Prompter
+/// [`Prompter::locale`]: crate::Prompter::locale
+pub fn set_locale(locale: Locale) {
+    *LOCALE.write().unwrap_or_else(PoisonError::into_inner) = locale;
+}
+
+/// Returns the locale used by prompts.
+///
+/// This is [`Locale::ENGLISH`] unless [`set_locale`] was called.
+///
+/// # Examples
+///
+/// ```
+/// use io_prompt_prototype::{locale, Locale};
+///
+/// assert_eq!(locale(), Locale::ENGLISH);
+/// ```
+pub fn locale() -> Locale {
+    *LOCALE.read().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Returns `true` if `answer`, which must be lowercase, matches one of
+/// `tokens`.
+pub(crate) fn matches(tokens: &[&str], answer: &str) -> bool {
+    tokens.iter().any(|token| token.to_lowercase() == answer)
+}
+
+/// Replaces the `{}` in a message template with `value`.
+pub(crate) fn fill(template: &str, value: &str) -> String {
+    template.replacen("{}", value, 1)
+}
+
+/// Replaces the two `{}` in a message template with `first` and `second`.
+pub(crate) fn fill2(template: &str, first: &str, second: &str) -> String {
+    match template.split_once("{}") {
+        Some((head, tail)) => format!("{}{}{}", head, first, fill(tail, second)),
+        None => template.to_string(),
+    }
+}
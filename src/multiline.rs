@@ -0,0 +1,127 @@
+use std::io::{self, BufRead};
+
+use crate::{read_line_opt_from, script};
+
+/// Reads lines of input from stdin until a sentinel line is entered.
+///
+/// Lines are read until one is exactly equal to `sentinel`, after its line
+/// terminator is removed. The lines before it are joined with `\n` and
+/// returned; the sentinel itself is not included, and neither is a trailing
+/// newline. Reaching the end of the input is treated as an implicit sentinel.
+///
+/// # Examples
+///
+/// ```no_run
+/// # fn main() -> std::io::Result<()> {
+/// use io_prompt_prototype::read_multiline;
+///
+/// println!("Enter your message, end with '.' on its own line:");
+/// let message = read_multiline(".")?;
+/// # Ok(()) }
+/// ```
+pub fn read_multiline(sentinel: &str) -> io::Result<String> {
+    read_multiline_from(&mut script::input(), sentinel)
+}
+
+/// Reads lines of input from a reader until a sentinel line is entered.
+///
+/// This behaves exactly like [`read_multiline`], but reads from an arbitrary
+/// [`BufRead`] rather than from stdin.
+///
+/// # Examples
+///
+/// ```
+/// # fn main() -> std::io::Result<()> {
+/// use io_prompt_prototype::read_multiline_from;
+/// use std::io::Cursor;
+///
+/// let mut input = Cursor::new(b"first\n\nthird\n.\nignored\n");
+/// assert_eq!(read_multiline_from(&mut input, ".")?, "first\n\nthird");
+/// # Ok(()) }
+/// ```
+pub fn read_multiline_from<R: BufRead>(reader: &mut R, sentinel: &str) -> io::Result<String> {
+    let mut text = String::new();
+    let mut first = true;
+    while let Some(line) = read_line_opt_from(reader)? {
+        if line == sentinel {
+            break;
+        }
+        if !first {
+            text.push('\n');
+        }
+        text.push_str(&line);
+        first = false;
+    }
+    Ok(text)
+}
+
+/// Reads lines of input from a reader until the text read so far is complete.
+///
+/// After each line is read, the lines so far are joined with `\n`, without a
+/// trailing newline, and passed to `is_complete`. Reading stops as soon as it
+/// returns `true`, and the joined lines are returned. This suits input whose
+/// end depends on its contents as a whole, such as an expression which ends
+/// once its brackets are balanced.
+///
+/// Reaching the end of the input also stops reading, and returns the lines
+/// read so far even though `is_complete` never accepted them. That's an empty
+/// string if the input ended before the first line. Callers which need to
+/// tell the two apart can call `is_complete` on the result again.
+///
+/// # Examples
+///
+/// ```
+/// # fn main() -> std::io::Result<()> {
+/// use io_prompt_prototype::read_until_complete_from;
+/// use std::io::Cursor;
+///
+/// let mut input = Cursor::new(b"first \\\nsecond \\\nthird\nignored\n");
+/// let text = read_until_complete_from(&mut input, |text| !text.ends_with('\\'))?;
+/// assert_eq!(text, "first \\\nsecond \\\nthird");
+/// # Ok(()) }
+/// ```
+pub fn read_until_complete_from<R, F>(reader: &mut R, mut is_complete: F) -> io::Result<String>
+where
+    R: BufRead,
+    F: FnMut(&str) -> bool,
+{
+    let mut text = String::new();
+    let mut first = true;
+    while let Some(line) = read_line_opt_from(reader)? {
+        if !first {
+            text.push('\n');
+        }
+        text.push_str(&line);
+        first = false;
+        if is_complete(&text) {
+            break;
+        }
+    }
+    Ok(text)
+}
+
+/// Prints to the standard output, with a newline. Then reads lines of input
+/// until a sentinel line is entered.
+///
+/// The sentinel defaults to a single `.`, and can be changed by passing
+/// `sentinel = <str>`. See [`read_multiline`] for how the lines are joined.
+///
+/// # Examples
+///
+/// ```no_run
+/// use io_prompt_prototype::prompt_multiline;
+///
+/// let message = prompt_multiline!("Enter text, end with '.' on its own line:");
+/// let notes = prompt_multiline!("Enter notes, end with EOF:", sentinel = "EOF");
+/// ```
+#[macro_export]
+macro_rules! prompt_multiline {
+    ($msg:expr, sentinel = $sentinel:expr $(,)?) => {
+        $crate::Prompter::stdio()
+            .prompt_multiline(&$msg, &$sentinel)
+            .unwrap_or_else(|e| panic!("failed prompting on stdin/stdout: {}", e))
+    };
+    ($msg:expr $(,)?) => {
+        $crate::prompt_multiline!($msg, sentinel = ".")
+    };
+}
@@ -0,0 +1,571 @@
+use std::env;
+use std::fs;
+use std::io::{self, BufRead, Read, Write};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::editor::Editor;
+use crate::input::{interrupted, next_key, Key};
+use crate::{
+    is_scripted, locale, prompt_stream, read_line_trimmed, read_line_trimmed_from, script, term,
+    trace, trim_newline,
+};
+
+/// Whether a newline is written once a hidden read completes.
+static NEWLINE: AtomicBool = AtomicBool::new(true);
+
+/// Sets whether a newline is written after reading a password.
+///
+/// With echo turned off, the Enter key which ends a password isn't shown
+/// either, so the cursor stays at the end of the prompt and whatever is
+/// printed next ends up on the same line. Like `sudo` and `ssh`, the password
+/// reads therefore write a newline themselves once the password has been
+/// entered, which is on by default. [`read_password`] and `read_secret`
+/// write `\n` to stdout when stdin is a terminal, and the masked reads such
+/// as [`read_password_masked_from`] write `\r\n` to their writer, since
+/// they run in raw mode. Nothing is written when the read fails, or when a
+/// non-terminal stdin makes the reads fall back to [`read_line_trimmed`],
+/// since the line was never hidden then. Calling
+/// `set_password_newline(false)` turns this off for the whole process, for
+/// programs which end the line themselves.
+///
+/// # Examples
+///
+/// ```
+/// # fn main() -> std::io::Result<()> {
+/// use io_prompt_prototype::{read_password_masked_from, set_password_newline};
+/// use std::io::Cursor;
+///
+/// let mut output = Vec::new();
+/// read_password_masked_from(&mut Cursor::new("12\r"), &mut output)?;
+/// assert_eq!(output, b"**\r\n");
+///
+/// set_password_newline(false);
+/// let mut output = Vec::new();
+/// read_password_masked_from(&mut Cursor::new("12\r"), &mut output)?;
+/// assert_eq!(output, b"**");
+/// # Ok(()) }
+/// ```
+pub fn set_password_newline(newline: bool) {
+    NEWLINE.store(newline, Ordering::Relaxed);
+}
+
+/// Returns whether a newline is written after reading a password, see
+/// [`set_password_newline`].
+pub(crate) fn password_newline() -> bool {
+    NEWLINE.load(Ordering::Relaxed)
+}
+
+/// Writes the newline which the terminal didn't echo after a hidden read
+/// from stdin, unless that was turned off.
+pub(crate) fn end_hidden_line() -> io::Result<()> {
+    if password_newline() {
+        let mut out = prompt_stream();
+        out.write_all(b"\n")?;
+        out.flush()?;
+    }
+    Ok(())
+}
+
+/// Reads a line of input from stdin without echoing it to the terminal.
+///
+/// Echo is disabled for the duration of the read and restored afterwards. On
+/// Unix this is done by clearing `ECHO` through termios, and on Windows by
+/// clearing `ENABLE_ECHO_INPUT` from the console mode. The trailing newline is
+/// removed from the returned string, and a newline is written to stdout in
+/// its place, unless turned off with [`set_password_newline`].
+///
+/// When stdin is not a terminal, for example when input is piped in, there is
+/// nothing to hide and this behaves exactly like [`read_line_trimmed`].
+///
+/// The returned string is freed like any other. With the `zeroize` feature,
+/// `read_secret` reads the password into a `Secret` instead, which is
+/// overwritten with zeros when dropped.
+///
+/// # Examples
+///
+/// ```no_run
+/// # fn main() -> std::io::Result<()> {
+/// use io_prompt_prototype::read_password;
+///
+/// print!("Password: >");
+/// let password = read_password()?;
+/// # Ok(()) }
+/// ```
+pub fn read_password() -> io::Result<String> {
+    if !term::stdin_is_tty() {
+        return read_line_trimmed();
+    }
+    let password = {
+        let _guard = term::disable_echo()?;
+        read_line_trimmed()?
+    };
+    end_hidden_line()?;
+    Ok(password)
+}
+
+/// Reads a line of input from stdin, echoing a `*` for every character typed.
+///
+/// The terminal is put into raw mode for the duration of the read, so input
+/// is processed one keypress at a time: Backspace and Delete erase the
+/// character before and under the cursor, which the left and right arrows,
+/// Home and End move, Enter or Ctrl-D finish the read, and Ctrl-C aborts it
+/// with an error of kind [`io::ErrorKind::Interrupted`]. Once the read
+/// finishes the cursor is moved to the next line, unless turned off with
+/// [`set_password_newline`]. The original terminal mode is restored
+/// afterwards, including when the read fails.
+///
+/// When stdin is not a terminal this behaves exactly like
+/// [`read_line_trimmed`].
+///
+/// # Examples
+///
+/// ```no_run
+/// # fn main() -> std::io::Result<()> {
+/// use io_prompt_prototype::read_password_masked;
+///
+/// print!("PIN: >");
+/// let pin = read_password_masked()?;
+/// # Ok(()) }
+/// ```
+pub fn read_password_masked() -> io::Result<String> {
+    read_password_masked_with(Masking::Full)
+}
+
+/// How the characters of a masked password are echoed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Masking {
+    /// Every character is echoed as a `*`.
+    Full,
+    /// The character just typed is echoed as is, and replaced by a `*` as soon
+    /// as the next key is pressed. This lets users catch typos on keyboards
+    /// where they're easy to make, at the cost of showing each character to
+    /// anyone looking at the screen. The last character is masked too before
+    /// the read finishes, so the password never stays on the screen.
+    RevealLast,
+}
+
+/// Reads a line of input from stdin, echoing it masked as chosen by
+/// `masking`.
+///
+/// This behaves like [`read_password_masked`], which is a shorthand for
+/// passing [`Masking::Full`]. With [`Masking::RevealLast`], the character just
+/// typed is shown until the next keypress. There is no timer: a character
+/// stays visible while the user pauses.
+///
+/// # Examples
+///
+/// ```no_run
+/// # fn main() -> std::io::Result<()> {
+/// use io_prompt_prototype::{read_password_masked_with, Masking};
+///
+/// print!("Password: >");
+/// let password = read_password_masked_with(Masking::RevealLast)?;
+/// # Ok(()) }
+/// ```
+pub fn read_password_masked_with(masking: Masking) -> io::Result<String> {
+    if !term::stdin_is_tty() {
+        return read_line_trimmed();
+    }
+    let _guard = term::enable_raw()?;
+    read_password_masked_with_from(&mut script::input(), &mut prompt_stream(), masking)
+}
+
+/// Reads a line of raw keypresses from a reader, echoing a `*` to a writer for
+/// every character typed.
+///
+/// This implements the input handling of [`read_password_masked`] without
+/// touching the terminal mode. Each character is echoed as a single `*`,
+/// regardless of how many bytes its UTF-8 encoding takes. Erasing the last
+/// character writes `\x08 \x08` to move back over its `*`. Once the read
+/// finishes, `\r\n` is written, unless turned off with
+/// [`set_password_newline`].
+///
+/// # Examples
+///
+/// ```
+/// # fn main() -> std::io::Result<()> {
+/// use io_prompt_prototype::read_password_masked_from;
+/// use std::io::Cursor;
+///
+/// let mut output = Vec::new();
+/// let pin = read_password_masked_from(&mut Cursor::new("12é\r"), &mut output)?;
+/// assert_eq!(pin, "12é");
+/// assert_eq!(output, b"***\r\n");
+/// # Ok(()) }
+/// ```
+pub fn read_password_masked_from<R, W>(reader: &mut R, writer: &mut W) -> io::Result<String>
+where
+    R: Read,
+    W: Write,
+{
+    read_password_masked_with_from(reader, writer, Masking::Full)
+}
+
+/// Reads a line of raw keypresses from a reader, echoing it masked to a writer
+/// as chosen by `masking`.
+///
+/// This implements the input handling of [`read_password_masked_with`]
+/// without touching the terminal mode. With [`Masking::RevealLast`], each
+/// character is written as is when it's typed. On the next keypress the
+/// cursor is moved back over it with `\x08` and a `*` is written in its
+/// place, before the key is handled.
+///
+/// # Examples
+///
+/// ```
+/// # fn main() -> std::io::Result<()> {
+/// use io_prompt_prototype::{read_password_masked_with_from, Masking};
+/// use std::io::Cursor;
+///
+/// let mut output = Vec::new();
+/// let input = &mut Cursor::new("ab\r");
+/// let password = read_password_masked_with_from(input, &mut output, Masking::RevealLast)?;
+/// assert_eq!(password, "ab");
+/// assert_eq!(output, b"a\x08*b\x08*\r\n");
+/// # Ok(()) }
+/// ```
+pub fn read_password_masked_with_from<R, W>(
+    reader: &mut R,
+    writer: &mut W,
+    masking: Masking,
+) -> io::Result<String>
+where
+    R: Read,
+    W: Write,
+{
+    let mut editor = Editor::masked(writer, "", '*');
+    if masking == Masking::RevealLast {
+        editor = editor.reveal_last();
+    }
+    let result = loop {
+        match next_key(reader)? {
+            None | Some(Key::Enter) | Some(Key::Eof) => break Ok(()),
+            Some(Key::Interrupt) => break Err(interrupted()),
+            Some(key) => editor.edit(key)?,
+        }
+    };
+    editor.conceal()?;
+    result?;
+    if password_newline() {
+        return editor.finish();
+    }
+    Ok(editor.line().to_string())
+}
+
+/// Prints to the standard output and reads a secret without echoing it, or
+/// reads it from an environment variable when running non-interactively.
+///
+/// When stdin is a terminal, `msg` is printed and the secret is read with
+/// [`read_password`]. Otherwise, for example in a CI pipeline, nothing is
+/// printed and the secret is read from the environment variable `var`
+/// instead. If that variable isn't set either, an error of kind
+/// [`io::ErrorKind::NotFound`] is returned.
+///
+/// # Examples
+///
+/// ```no_run
+/// # fn main() -> std::io::Result<()> {
+/// use io_prompt_prototype::prompt_secret_env_fallback;
+///
+/// let token = prompt_secret_env_fallback("Token: >", "MY_TOKEN")?;
+/// # Ok(()) }
+/// ```
+pub fn prompt_secret_env_fallback(msg: &str, var: &str) -> io::Result<String> {
+    if term::stdin_is_tty() || is_scripted() {
+        trace::prompt(msg);
+        let mut out = prompt_stream();
+        out.write_all(msg.as_bytes())?;
+        out.flush()?;
+        return trace::read_secret(read_password());
+    }
+    match env::var(var) {
+        Ok(secret) => Ok(secret),
+        Err(env::VarError::NotPresent) => Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            format!(
+                "stdin is not a terminal and the `{}` environment variable is not set",
+                var
+            ),
+        )),
+        Err(env::VarError::NotUnicode(_)) => Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("the `{}` environment variable is not valid unicode", var),
+        )),
+    }
+}
+
+/// Prints to the standard output and reads a secret without echoing it, or
+/// reads it from a file when running non-interactively.
+///
+/// When stdin is a terminal, `msg` is printed and the secret is read with
+/// [`read_password`]. Otherwise nothing is printed and the secret is read from
+/// the file at `path` instead, with a single trailing newline removed, which
+/// suits secrets mounted as files by container orchestrators such as
+/// Kubernetes. Such paths are conventionally passed in environment variables
+/// with a `_FILE` suffix, such as `DB_PASSWORD_FILE`. If the file doesn't
+/// exist, an error of kind [`io::ErrorKind::NotFound`] is returned which names
+/// the path. Other errors reading the file keep their kind, and name the path
+/// too.
+///
+/// # Examples
+///
+/// ```no_run
+/// # fn main() -> std::io::Result<()> {
+/// use io_prompt_prototype::prompt_secret_file_fallback;
+///
+/// let path = std::env::var("DB_PASSWORD_FILE").unwrap_or_default();
+/// let password = prompt_secret_file_fallback("Database password: >", path)?;
+/// # Ok(()) }
+/// ```
+pub fn prompt_secret_file_fallback(msg: &str, path: impl AsRef<Path>) -> io::Result<String> {
+    if term::stdin_is_tty() || is_scripted() {
+        trace::prompt(msg);
+        let mut out = prompt_stream();
+        out.write_all(msg.as_bytes())?;
+        out.flush()?;
+        return trace::read_secret(read_password());
+    }
+    let path = path.as_ref();
+    let mut secret = fs::read_to_string(path).map_err(|err| {
+        let msg = match err.kind() {
+            io::ErrorKind::NotFound => format!(
+                "stdin is not a terminal and the secret file `{}` does not exist",
+                path.display()
+            ),
+            _ => format!(
+                "failed reading the secret file `{}`: {}",
+                path.display(),
+                err
+            ),
+        };
+        io::Error::new(err.kind(), msg)
+    })?;
+    trim_newline(&mut secret);
+    Ok(secret)
+}
+
+/// Prints to the standard output and reads a password, then does so again to
+/// confirm it.
+///
+/// Both entries are read with [`read_password`], so neither is echoed. If they
+/// don't match, a message saying so is printed and both are asked for again,
+/// up to `attempts` times in total. After that an error of kind
+/// [`io::ErrorKind::InvalidInput`] is returned.
+///
+/// # Examples
+///
+/// ```no_run
+/// # fn main() -> std::io::Result<()> {
+/// use io_prompt_prototype::prompt_password_confirm;
+///
+/// let password = prompt_password_confirm("Password: >", "Confirm: >", 3)?;
+/// # Ok(()) }
+/// ```
+pub fn prompt_password_confirm(msg: &str, confirm: &str, attempts: usize) -> io::Result<String> {
+    let is_tty = term::stdin_is_tty();
+    confirm_loop(&mut prompt_stream(), msg, confirm, attempts, |out| {
+        let password = read_password()?;
+        if is_tty && !password_newline() {
+            // The newline ending the entry wasn't echoed either.
+            out.write_all(b"\n")?;
+        }
+        Ok(password)
+    })
+}
+
+/// Writes a message to a writer and reads a password from a reader, then does
+/// so again to confirm it.
+///
+/// This implements the confirmation logic of [`prompt_password_confirm`],
+/// reading each entry as a line from `reader`.
+///
+/// # Examples
+///
+/// ```
+/// # fn main() -> std::io::Result<()> {
+/// use io_prompt_prototype::prompt_password_confirm_from;
+/// use std::io::Cursor;
+///
+/// let mut input = Cursor::new(b"hunter2\nhunter3\nhunter2\nhunter2\n");
+/// let mut output = Vec::new();
+/// let password =
+///     prompt_password_confirm_from(&mut input, &mut output, "Password: >", "Confirm: >", 3)?;
+/// assert_eq!(password, "hunter2");
+/// # Ok(()) }
+/// ```
+pub fn prompt_password_confirm_from<R, W>(
+    reader: &mut R,
+    writer: &mut W,
+    msg: &str,
+    confirm: &str,
+    attempts: usize,
+) -> io::Result<String>
+where
+    R: BufRead,
+    W: Write,
+{
+    confirm_loop(writer, msg, confirm, attempts, |_| {
+        read_line_trimmed_from(reader)
+    })
+}
+
+/// Asks for a password twice using `read`, until both entries match.
+fn confirm_loop<W, F>(
+    writer: &mut W,
+    msg: &str,
+    confirm: &str,
+    attempts: usize,
+    mut read: F,
+) -> io::Result<String>
+where
+    W: Write,
+    F: FnMut(&mut W) -> io::Result<String>,
+{
+    for _ in 0..attempts {
+        trace::prompt(msg);
+        writer.write_all(msg.as_bytes())?;
+        writer.flush()?;
+        let password = trace::read_secret(read(writer))?;
+        trace::prompt(confirm);
+        writer.write_all(confirm.as_bytes())?;
+        writer.flush()?;
+        if trace::read_secret(read(writer))? == password {
+            return Ok(password);
+        }
+        writeln!(writer, "{}", locale().passwords_do_not_match)?;
+    }
+    Err(io::Error::new(
+        io::ErrorKind::InvalidInput,
+        format!("passwords did not match after {} attempts", attempts),
+    ))
+}
+
+/// Prints to the standard output. Then reads a line of input without echoing
+/// it to the terminal.
+///
+/// This is a shorthand for calling [`print!`] and [`read_password`].
+///
+/// # Examples
+///
+/// ```no_run
+/// use io_prompt_prototype::prompt_password;
+///
+/// let password = prompt_password!("Password: >");
+/// ```
+#[macro_export]
+macro_rules! prompt_password {
+    ($($arg:tt)*) => {{
+        use std::io::Write;
+        let mut out = $crate::prompt_stream();
+        $crate::__private::write_prompt(&mut out, format_args!($($arg)*))
+            .unwrap_or_else(|e| panic!("failed writing to {}: {}", out, e));
+        out.flush().unwrap_or_else(|e| panic!("failed writing to {}: {}", out, e));
+        $crate::__private::read_secret($crate::read_password())
+            .unwrap_or_else(|e| panic!("failed reading from stdin: {}", e))
+    }};
+}
+
+/// Prints to the standard output. Then reads a line of input, echoing a `*` for
+/// every character typed.
+///
+/// This is a shorthand for calling [`print!`] and [`read_password_masked`].
+///
+/// # Examples
+///
+/// ```no_run
+/// use io_prompt_prototype::prompt_password_masked;
+///
+/// let pin = prompt_password_masked!("PIN: >");
+/// ```
+#[macro_export]
+macro_rules! prompt_password_masked {
+    ($($arg:tt)*) => {{
+        use std::io::Write;
+        let mut out = $crate::prompt_stream();
+        $crate::__private::write_prompt(&mut out, format_args!($($arg)*))
+            .unwrap_or_else(|e| panic!("failed writing to {}: {}", out, e));
+        out.flush().unwrap_or_else(|e| panic!("failed writing to {}: {}", out, e));
+        $crate::__private::read_secret($crate::read_password_masked())
+            .unwrap_or_else(|e| panic!("failed reading from stdin: {}", e))
+    }};
+}
+
+/// Prints to the standard output and reads a password, then does so again to
+/// confirm it.
+///
+/// This is a shorthand for calling [`prompt_password_confirm`], which
+/// describes the details, and allows three attempts unless `attempts = <n>`
+/// is passed. Unlike the other prompt macros this returns an `io::Result`,
+/// since giving up after too many mismatches is expected to be handled.
+///
+/// # Examples
+///
+/// ```no_run
+/// # fn main() -> std::io::Result<()> {
+/// use io_prompt_prototype::prompt_password_confirm;
+///
+/// let password = prompt_password_confirm!("Password: >", "Confirm: >")?;
+/// let pin = prompt_password_confirm!("PIN: >", "Confirm PIN: >", attempts = 5)?;
+/// # Ok(()) }
+/// ```
+#[macro_export]
+macro_rules! prompt_password_confirm {
+    ($msg:expr, $confirm:expr, attempts = $attempts:expr $(,)?) => {
+        $crate::prompt_password_confirm(&$msg, &$confirm, $attempts)
+    };
+    ($msg:expr, $confirm:expr $(,)?) => {
+        $crate::prompt_password_confirm!($msg, $confirm, attempts = 3)
+    };
+}
+
+/// Prints to the standard output and reads a secret without echoing it, or
+/// reads it from an environment variable when running non-interactively.
+///
+/// This is a shorthand for calling [`prompt_secret_env_fallback`], which
+/// describes the details. Like [`prompt_password_confirm!`] this returns an
+/// `io::Result`, since a missing environment variable is expected to be
+/// handled.
+///
+/// [`prompt_password_confirm!`]: crate::prompt_password_confirm!
+///
+/// # Examples
+///
+/// ```no_run
+/// # fn main() -> std::io::Result<()> {
+/// use io_prompt_prototype::prompt_secret_env_fallback;
+///
+/// let token = prompt_secret_env_fallback!("Token: >", "MY_TOKEN")?;
+/// # Ok(()) }
+/// ```
+#[macro_export]
+macro_rules! prompt_secret_env_fallback {
+    ($msg:expr, $var:expr $(,)?) => {
+        $crate::prompt_secret_env_fallback(&$msg, &$var)
+    };
+}
+
+/// Prints to the standard output and reads a secret without echoing it, or
+/// reads it from a file when running non-interactively.
+///
+/// This is a shorthand for calling [`prompt_secret_file_fallback`], which
+/// describes the details. Like [`prompt_secret_env_fallback!`] this returns an
+/// `io::Result`, since a missing file is expected to be handled.
+///
+/// [`prompt_secret_env_fallback!`]: crate::prompt_secret_env_fallback!
+///
+/// # Examples
+///
+/// ```no_run
+/// # fn main() -> std::io::Result<()> {
+/// use io_prompt_prototype::prompt_secret_file_fallback;
+///
+/// let token = prompt_secret_file_fallback!("Token: >", "/run/secrets/token")?;
+/// # Ok(()) }
+/// ```
+#[macro_export]
+macro_rules! prompt_secret_file_fallback {
+    ($msg:expr, $path:expr $(,)?) => {
+        $crate::prompt_secret_file_fallback(&$msg, $path)
+    };
+}
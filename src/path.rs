@@ -0,0 +1,175 @@
+use std::env;
+use std::ffi::OsString;
+use std::io::{self, BufRead, Write};
+use std::path::PathBuf;
+
+use crate::locale::fill;
+use crate::Prompter;
+
+/// The kind of path a prompt accepts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PathKind {
+    Any,
+    File,
+    Dir,
+}
+
+impl<R: BufRead, W: Write> Prompter<R, W> {
+    /// Writes `msg` to the output. Then reads a path, re-prompting until it
+    /// names an existing file or directory.
+    ///
+    /// A leading `~` is expanded to the home directory, as given by the `HOME`
+    /// environment variable, or `USERPROFILE` on Windows. Relative paths are
+    /// checked against the current directory, and are returned as entered
+    /// rather than made absolute. Each rejected answer is followed by a
+    /// message saying why. If the input ends before a valid answer is given,
+    /// an error of kind [`io::ErrorKind::UnexpectedEof`] is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # fn main() -> std::io::Result<()> {
+    /// use io_prompt_prototype::Prompter;
+    /// use std::io::Cursor;
+    ///
+    /// let mut prompter = Prompter::new(Cursor::new(b"/does/not/exist\nsrc\n"), Vec::new());
+    /// assert_eq!(prompter.path("Directory? >")?, std::path::Path::new("src"));
+    /// # Ok(()) }
+    /// ```
+    pub fn path(&mut self, msg: &str) -> io::Result<PathBuf> {
+        self.prompt_path(msg, PathKind::Any)
+    }
+
+    /// Writes `msg` to the output. Then reads a path, re-prompting until it
+    /// names an existing file.
+    ///
+    /// This behaves like [`Prompter::path`], but directories are rejected.
+    pub fn file_path(&mut self, msg: &str) -> io::Result<PathBuf> {
+        self.prompt_path(msg, PathKind::File)
+    }
+
+    /// Writes `msg` to the output. Then reads a path, re-prompting until it
+    /// names an existing directory.
+    ///
+    /// This behaves like [`Prompter::path`], but files are rejected.
+    pub fn dir_path(&mut self, msg: &str) -> io::Result<PathBuf> {
+        self.prompt_path(msg, PathKind::Dir)
+    }
+
+    fn prompt_path(&mut self, msg: &str, kind: PathKind) -> io::Result<PathBuf> {
+        let locale = self.locale;
+        self.prompt_with(msg, |answer| {
+            if answer.is_empty() {
+                return Err(String::from(locale.enter_path));
+            }
+            let path = expand_home(answer);
+            let valid = match kind {
+                PathKind::Any => path.exists(),
+                PathKind::File => path.is_file(),
+                PathKind::Dir => path.is_dir(),
+            };
+            if valid {
+                return Ok(path);
+            }
+            let template = match (kind, path.exists()) {
+                (_, false) => locale.path_not_found,
+                (PathKind::Dir, true) => locale.not_a_dir,
+                _ => locale.not_a_file,
+            };
+            Err(fill(template, answer))
+        })
+    }
+}
+
+/// Expands a leading `~` in `path` to the home directory.
+fn expand_home(path: &str) -> PathBuf {
+    let rest = match path.strip_prefix('~') {
+        Some(rest) => rest,
+        None => return PathBuf::from(path),
+    };
+    let home = match home_dir() {
+        Some(home) => PathBuf::from(home),
+        None => return PathBuf::from(path),
+    };
+    match rest.strip_prefix(std::path::is_separator) {
+        Some(rest) => home.join(rest),
+        None if rest.is_empty() => home,
+        // A path like `~user` is left alone.
+        None => PathBuf::from(path),
+    }
+}
+
+fn home_dir() -> Option<OsString> {
+    let var = if cfg!(windows) { "USERPROFILE" } else { "HOME" };
+    env::var_os(var).filter(|home| !home.is_empty())
+}
+
+/// Prints to the standard output. Then reads a path, re-prompting until it
+/// names an existing file or directory.
+///
+/// Returns a [`PathBuf`]. See [`Prompter::path`] for the validation
+/// performed, including the expansion of a leading `~`. This panics on I/O
+/// errors, or if stdin is closed before a valid path is given.
+///
+/// [`PathBuf`]: std::path::PathBuf
+///
+/// # Examples
+///
+/// ```no_run
+/// use io_prompt_prototype::prompt_path;
+///
+/// let path = prompt_path!("File or directory? >");
+/// println!("Found {}", path.display());
+/// ```
+#[macro_export]
+macro_rules! prompt_path {
+    ($($arg:tt)*) => {
+        $crate::Prompter::stdio()
+            .path(&format!($($arg)*))
+            .unwrap_or_else(|e| panic!("failed prompting on stdin/stdout: {}", e))
+    };
+}
+
+/// Prints to the standard output. Then reads a path, re-prompting until it
+/// names an existing file.
+///
+/// See [`prompt_path!`] and [`Prompter::file_path`] for the details.
+///
+/// # Examples
+///
+/// ```no_run
+/// use io_prompt_prototype::prompt_path_file;
+///
+/// let config = prompt_path_file!("Config file? >");
+/// let contents = std::fs::read_to_string(config).unwrap();
+/// ```
+#[macro_export]
+macro_rules! prompt_path_file {
+    ($($arg:tt)*) => {
+        $crate::Prompter::stdio()
+            .file_path(&format!($($arg)*))
+            .unwrap_or_else(|e| panic!("failed prompting on stdin/stdout: {}", e))
+    };
+}
+
+/// Prints to the standard output. Then reads a path, re-prompting until it
+/// names an existing directory.
+///
+/// See [`prompt_path!`] and [`Prompter::dir_path`] for the details.
+///
+/// # Examples
+///
+/// ```no_run
+/// use io_prompt_prototype::prompt_path_dir;
+///
+/// let dir = prompt_path_dir!("Output directory? >");
+/// std::fs::write(dir.join("out.txt"), "hello").unwrap();
+/// ```
+#[macro_export]
+macro_rules! prompt_path_dir {
+    ($($arg:tt)*) => {
+        $crate::Prompter::stdio()
+            .dir_path(&format!($($arg)*))
+            .unwrap_or_else(|e| panic!("failed prompting on stdin/stdout: {}", e))
+    };
+}
@@ -0,0 +1,86 @@
+use std::io::{self, Read, Write};
+
+use crate::editor::Editor;
+use crate::input::{interrupted, next_key, Key};
+use crate::{prompt_stream, read_line_trimmed, script, term, trace};
+
+/// Prints to the standard output. Then reads a line of input, starting with
+/// `initial` already typed.
+///
+/// The user can edit `initial` before pressing Enter, rather than only being
+/// able to accept it whole as with [`prompt_default!`]. The cursor starts at
+/// the end of `initial`, and can be moved as in [`prompt_complete`]. The
+/// terminal is put into raw mode for the duration of the read.
+///
+/// When stdin is not a terminal there is nothing to edit. A line is read as
+/// with [`read_line_trimmed`] and appended to `initial`, just as if it had
+/// been typed after it, so an empty line returns `initial` unchanged.
+///
+/// [`prompt_default!`]: crate::prompt_default
+/// [`prompt_complete`]: crate::prompt_complete
+///
+/// # Examples
+///
+/// ```no_run
+/// # fn main() -> std::io::Result<()> {
+/// use io_prompt_prototype::prompt_prefill;
+///
+/// let branch = prompt_prefill("Branch name? >", "feature/")?;
+/// # Ok(()) }
+/// ```
+pub fn prompt_prefill(msg: &str, initial: &str) -> io::Result<String> {
+    let mut out = prompt_stream();
+    if !term::stdin_is_tty() {
+        trace::prompt(msg);
+        out.write_all(msg.as_bytes())?;
+        out.flush()?;
+        let line = format!("{}{}", initial, read_line_trimmed()?);
+        trace::answer(&line);
+        return Ok(line);
+    }
+    let _guard = term::enable_raw()?;
+    prompt_prefill_from(&mut script::input(), &mut out, msg, initial)
+}
+
+/// Writes `msg` and `initial` to a writer. Then reads raw keypresses from a
+/// reader, editing `initial` until Enter is pressed.
+///
+/// This implements the input handling of [`prompt_prefill`] without touching
+/// the terminal mode.
+///
+/// # Examples
+///
+/// ```
+/// # fn main() -> std::io::Result<()> {
+/// use io_prompt_prototype::prompt_prefill_from;
+/// use std::io::Cursor;
+///
+/// // Erase the last character, then type two more.
+/// let mut output = Vec::new();
+/// let line = prompt_prefill_from(&mut Cursor::new(b"\x7fry\r"), &mut output, "> ", "rusx")?;
+/// assert_eq!(line, "rusry");
+/// # Ok(()) }
+/// ```
+pub fn prompt_prefill_from<R, W>(
+    reader: &mut R,
+    writer: &mut W,
+    msg: &str,
+    initial: &str,
+) -> io::Result<String>
+where
+    R: Read,
+    W: Write,
+{
+    trace::prompt(msg);
+    let mut editor = Editor::new(writer, msg);
+    editor.set_line(initial.to_string())?;
+    loop {
+        match next_key(reader)? {
+            None | Some(Key::Enter) => break,
+            Some(Key::Eof) if editor.line().is_empty() => break,
+            Some(Key::Interrupt) => return Err(interrupted()),
+            Some(key) => editor.edit(key)?,
+        }
+    }
+    trace::read_answer(editor.finish())
+}
@@ -0,0 +1,1254 @@
+use std::fmt::{Debug, Display};
+use std::io::{self, BufRead, Write};
+use std::ops::{Bound, RangeBounds};
+use std::str::FromStr;
+
+use crate::locale::{self, fill, fill2, matches};
+use crate::script::{self, Input};
+use crate::trace;
+use crate::{
+    append_line, prompt_stream, read_line_opt_from, read_multiline_from, read_until_complete_from,
+    trim_newline, Answer, ControlCharError, ControlChars, Locale, PromptParseError,
+    RetriesExhausted, Stream, YesNoQuit,
+};
+
+/// The answers accepted as `true` by [`Prompter::prompt_bool`].
+const TRUE_TOKENS: &[&str] = &["y", "yes", "t", "true", "1", "on", "enable", "enabled"];
+
+/// The answers accepted as `false` by [`Prompter::prompt_bool`].
+const FALSE_TOKENS: &[&str] = &["n", "no", "f", "false", "0", "off", "disable", "disabled"];
+
+/// A prompt which reads from and writes to user-provided streams.
+///
+/// The `prompt!` family of macros always talk to stdin and stdout. A
+/// `Prompter` instead takes any [`BufRead`] as its input and any [`Write`] as
+/// its output, which makes it possible to prompt over a pipe, a
+/// pseudo-terminal, or an in-memory buffer.
+///
+/// # Examples
+///
+/// ```
+/// # fn main() -> std::io::Result<()> {
+/// use io_prompt_prototype::Prompter;
+/// use std::io::Cursor;
+///
+/// let mut prompter = Prompter::new(Cursor::new(b"Ferris\n"), Vec::new());
+/// let name = prompter.prompt("What's your name? >")?;
+/// assert_eq!(name, "Ferris");
+///
+/// let (_, output) = prompter.into_parts();
+/// assert_eq!(output, b"What's your name? >");
+/// # Ok(()) }
+/// ```
+#[derive(Debug)]
+pub struct Prompter<R, W> {
+    pub(crate) reader: R,
+    pub(crate) writer: W,
+    max_retries: Option<usize>,
+    pub(crate) auto_flush: bool,
+    pub(crate) locale: Locale,
+    pub(crate) terminal: bool,
+    pub(crate) answer_log: Option<fn(&mut W, &str) -> io::Result<()>>,
+    line: String,
+}
+
+impl Prompter<Input, Stream> {
+    /// Creates a new `Prompter` which reads from stdin and writes to stdout,
+    /// or to stderr if that was chosen with [`set_prompt_stream`].
+    ///
+    /// While a scripted session is active, it reads the scripted answers and
+    /// its output is captured instead. See [`push_scripted_answer`].
+    ///
+    /// [`push_scripted_answer`]: crate::push_scripted_answer
+    /// [`set_prompt_stream`]: crate::set_prompt_stream
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # fn main() -> std::io::Result<()> {
+    /// use io_prompt_prototype::Prompter;
+    ///
+    /// let mut prompter = Prompter::stdio();
+    /// let name = prompter.prompt("What's your name? >")?;
+    /// println!("Hello, {}!", name);
+    /// # Ok(()) }
+    /// ```
+    pub fn stdio() -> Self {
+        let out = prompt_stream();
+        let terminal = !script::is_scripted() && out.is_terminal();
+        Self::new(script::input(), out).terminal(terminal)
+    }
+}
+
+impl<R: BufRead, W: Write> Prompter<R, W> {
+    /// Creates a new `Prompter` from a reader and a writer.
+    pub fn new(reader: R, writer: W) -> Self {
+        Self {
+            reader,
+            writer,
+            max_retries: None,
+            auto_flush: true,
+            locale: locale::locale(),
+            terminal: false,
+            answer_log: None,
+            line: String::new(),
+        }
+    }
+
+    /// Creates a new `Prompter` from a reader and a writer, with room for
+    /// answers of `capacity` bytes.
+    ///
+    /// [`Prompter::prompt`], [`Prompter::promptln`] and
+    /// [`Prompter::prompt_ref`] read each answer into a line buffer owned by
+    /// the `Prompter`, which is cleared before each read but keeps its
+    /// capacity. Reserving it up front means that answers which fit are read
+    /// without the buffer having to grow. [`Prompter::new`] starts with an
+    /// empty buffer, which grows to the longest answer read.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # fn main() -> std::io::Result<()> {
+    /// use io_prompt_prototype::Prompter;
+    /// use std::io::Cursor;
+    ///
+    /// let mut prompter = Prompter::with_capacity(Cursor::new(b"Ferris\n"), Vec::new(), 64);
+    /// assert_eq!(prompter.prompt_ref("What's your name? >")?, "Ferris");
+    /// assert!(prompter.capacity() >= 64);
+    /// # Ok(()) }
+    /// ```
+    pub fn with_capacity(reader: R, writer: W, capacity: usize) -> Self {
+        Self {
+            line: String::with_capacity(capacity),
+            ..Self::new(reader, writer)
+        }
+    }
+
+    /// Sets how many times an invalid answer is asked for again before giving
+    /// up.
+    ///
+    /// This applies to all of the re-prompting methods, such as
+    /// [`Prompter::prompt_until`] and [`Prompter::parse_loop`]. Once the
+    /// retries are exhausted they return a [`RetriesExhausted`] error, wrapped
+    /// in an [`io::Error`] of kind [`io::ErrorKind::InvalidInput`]. The methods
+    /// which parse answers, [`Prompter::parse_loop`] and [`Prompter::number`],
+    /// return a [`PromptParseError`] instead, which also holds the error the
+    /// last answer failed to parse with. By default there is no limit.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use io_prompt_prototype::Prompter;
+    /// use std::io::{Cursor, ErrorKind};
+    ///
+    /// let input = Cursor::new(b"nope\nstill no\n42\n");
+    /// let mut prompter = Prompter::new(input, Vec::new()).max_retries(1);
+    /// let err = prompter.parse_loop::<u8>("Number? >").unwrap_err();
+    /// assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    /// ```
+    pub fn max_retries(mut self, max_retries: usize) -> Self {
+        self.max_retries = Some(max_retries);
+        self
+    }
+
+    /// Sets whether the output is flushed before each read.
+    ///
+    /// By default it is, so that a prompt is always visible before its answer
+    /// is waited for. When writing many prompts to a buffered writer, for
+    /// example when answers are piped in by another program, flushing every
+    /// time is wasteful. With `auto_flush(false)` the prompts stay in the
+    /// writer's buffer until it's flushed by the caller, or fills up. Until
+    /// then nobody sees them, so an interactive user would be left waiting
+    /// for a prompt which never appears.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # fn main() -> std::io::Result<()> {
+    /// use io_prompt_prototype::Prompter;
+    /// use std::io::{BufWriter, Cursor, Write};
+    ///
+    /// let output = BufWriter::new(Vec::new());
+    /// let mut prompter = Prompter::new(Cursor::new(b"1\n2\n"), output).auto_flush(false);
+    /// prompter.prompt("a? >")?;
+    /// prompter.prompt("b? >")?;
+    ///
+    /// let (_, mut output) = prompter.into_parts();
+    /// assert!(output.get_ref().is_empty());
+    /// output.flush()?;
+    /// assert_eq!(output.get_ref(), b"a? >b? >");
+    /// # Ok(()) }
+    /// ```
+    pub fn auto_flush(mut self, auto_flush: bool) -> Self {
+        self.auto_flush = auto_flush;
+        self
+    }
+
+    /// Sets the locale which determines the answers accepted and the messages
+    /// written by the built-in prompts.
+    ///
+    /// By default the locale registered with [`set_locale`] at the time the
+    /// `Prompter` is created is used, which is English unless changed.
+    ///
+    /// [`set_locale`]: crate::set_locale
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # fn main() -> std::io::Result<()> {
+    /// use io_prompt_prototype::{Locale, Prompter};
+    /// use std::io::Cursor;
+    ///
+    /// let german = Locale {
+    ///     yes: &["j", "ja"],
+    ///     no: &["n", "nein"],
+    ///     ..Locale::ENGLISH
+    /// };
+    /// let mut prompter = Prompter::new(Cursor::new(b"yes\nJa\n"), Vec::new()).locale(german);
+    /// assert!(prompter.confirm("Weiter? [j/N] >", None)?);
+    /// # Ok(()) }
+    /// ```
+    pub fn locale(mut self, locale: Locale) -> Self {
+        self.locale = locale;
+        self
+    }
+
+    /// Sets whether the output is a terminal.
+    ///
+    /// This decides whether output only meant for a user, such as the bell
+    /// enabled by [`PromptBuilder::bell_on_error`], is written. A `Prompter`
+    /// created with [`Prompter::stdio`] checks whether stdout is a terminal.
+    /// Any other `Prompter` assumes its output isn't one, since it can't
+    /// tell, unless this is set.
+    ///
+    /// [`PromptBuilder::bell_on_error`]: crate::PromptBuilder::bell_on_error
+    pub fn terminal(mut self, terminal: bool) -> Self {
+        self.terminal = terminal;
+        self
+    }
+
+    /// Writes `msg` to the output. Then reads a line of input.
+    ///
+    /// Any trailing newline is removed from the returned string. The line is
+    /// read into the `Prompter`'s reused line buffer, see
+    /// [`Prompter::with_capacity`], and returned as an owned copy of the
+    /// answer, which is allocated at its exact length. Use
+    /// [`Prompter::prompt_ref`] to borrow the answer instead.
+    pub fn prompt(&mut self, msg: &str) -> io::Result<String> {
+        self.prompt_ref(msg).map(str::to_string)
+    }
+
+    /// Writes `msg` to the output, with a newline. Then reads a line of input.
+    ///
+    /// Any trailing newline is removed from the returned string. Like
+    /// [`Prompter::prompt`], the line is read into the reused line buffer and
+    /// an owned copy of it is returned.
+    pub fn promptln(&mut self, msg: &str) -> io::Result<String> {
+        trace::prompt(msg);
+        self.writer.write_all(msg.as_bytes())?;
+        self.writer.write_all(b"\n")?;
+        self.read()?;
+        Ok(self.line.clone())
+    }
+
+    /// Writes `msg` to the output. Then reads a line of input, returning a
+    /// borrow of it which lasts until the `Prompter` is used again.
+    ///
+    /// This behaves like [`Prompter::prompt`], but borrows the answer from
+    /// the `Prompter`'s line buffer rather than copying it, which makes
+    /// answering repeated prompts free of allocations once the buffer is
+    /// large enough. The next read overwrites the buffer, so the answer has to
+    /// be copied to be kept around, for example with `to_string`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # fn main() -> std::io::Result<()> {
+    /// use io_prompt_prototype::Prompter;
+    /// use std::io::Cursor;
+    ///
+    /// let mut prompter = Prompter::new(Cursor::new(b"3\n4\n"), Vec::new());
+    /// let mut total = 0;
+    /// for _ in 0..2 {
+    ///     total += prompter.prompt_ref("Number? >")?.parse::<u32>().unwrap();
+    /// }
+    /// assert_eq!(total, 7);
+    /// # Ok(()) }
+    /// ```
+    pub fn prompt_ref(&mut self, msg: &str) -> io::Result<&str> {
+        trace::prompt(msg);
+        self.writer.write_all(msg.as_bytes())?;
+        self.read()?;
+        Ok(&self.line)
+    }
+
+    /// Returns the capacity of the line buffer answers are read into, in
+    /// bytes.
+    ///
+    /// The capacity is kept across reads, and only grows when an answer
+    /// doesn't fit, see [`Prompter::with_capacity`].
+    pub fn capacity(&self) -> usize {
+        self.line.capacity()
+    }
+
+    /// Writes `msg` to the output. Then reads a line of input, returning `None`
+    /// at the end of the input.
+    ///
+    /// Any trailing newline is removed from the returned string.
+    pub fn prompt_opt(&mut self, msg: &str) -> io::Result<Option<String>> {
+        trace::prompt(msg);
+        self.writer.write_all(msg.as_bytes())?;
+        self.flush()?;
+        let answer = trace::read_answer(read_line_opt_from(&mut self.reader))?;
+        if let Some(answer) = &answer {
+            self.log_answer(answer)?;
+        }
+        Ok(answer)
+    }
+
+    /// Writes `msg` to the output, with a newline. Then reads lines of input
+    /// until a line equal to `sentinel` is entered.
+    ///
+    /// See [`read_multiline`](crate::read_multiline) for how the lines are
+    /// joined.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # fn main() -> std::io::Result<()> {
+    /// use io_prompt_prototype::Prompter;
+    /// use std::io::Cursor;
+    ///
+    /// let mut prompter = Prompter::new(Cursor::new(b"Hello\nWorld\n.\n"), Vec::new());
+    /// let text = prompter.prompt_multiline("Message:", ".")?;
+    /// assert_eq!(text, "Hello\nWorld");
+    /// # Ok(()) }
+    /// ```
+    pub fn prompt_multiline(&mut self, msg: &str, sentinel: &str) -> io::Result<String> {
+        trace::prompt(msg);
+        writeln!(self.writer, "{}", msg)?;
+        self.flush()?;
+        let answer = trace::read_answer(read_multiline_from(&mut self.reader, sentinel))?;
+        self.log_answer(&answer)?;
+        Ok(answer)
+    }
+
+    /// Writes `msg` to the output, with a newline. Then reads lines of input
+    /// until `is_complete` accepts the text read so far.
+    ///
+    /// See [`read_until_complete_from`](crate::read_until_complete_from) for
+    /// how the lines are joined, and what's returned if the input ends first.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # fn main() -> std::io::Result<()> {
+    /// use io_prompt_prototype::Prompter;
+    /// use std::io::Cursor;
+    ///
+    /// let input = Cursor::new(b"(+ 1\n   (* 2 3))\n");
+    /// let mut prompter = Prompter::new(input, Vec::new());
+    /// let expr = prompter.prompt_until_complete("Expression:", |text| {
+    ///     text.matches('(').count() == text.matches(')').count()
+    /// })?;
+    /// assert_eq!(expr, "(+ 1\n   (* 2 3))");
+    /// # Ok(()) }
+    /// ```
+    pub fn prompt_until_complete<F>(&mut self, msg: &str, is_complete: F) -> io::Result<String>
+    where
+        F: FnMut(&str) -> bool,
+    {
+        trace::prompt(msg);
+        writeln!(self.writer, "{}", msg)?;
+        self.flush()?;
+        let answer = read_until_complete_from(&mut self.reader, is_complete);
+        let answer = trace::read_answer(answer)?;
+        self.log_answer(&answer)?;
+        Ok(answer)
+    }
+
+    /// Asks a yes/no question, re-prompting until a recognized answer is given.
+    ///
+    /// The answer is matched case-insensitively, ignoring surrounding
+    /// whitespace. `y` and `yes` return `true`, `n` and `no` return `false`. An
+    /// empty answer, or reaching the end of the input, returns `default` if one
+    /// is given and `false` otherwise. Any other answer causes `msg` to be
+    /// written again. The accepted answers can be changed with
+    /// [`Prompter::locale`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # fn main() -> std::io::Result<()> {
+    /// use io_prompt_prototype::Prompter;
+    /// use std::io::Cursor;
+    ///
+    /// let mut prompter = Prompter::new(Cursor::new(b"maybe\nYES\n\n"), Vec::new());
+    /// assert!(prompter.confirm("Continue? [y/N] >", None)?);
+    /// assert!(prompter.confirm("Continue? [Y/n] >", Some(true))?);
+    /// # Ok(()) }
+    /// ```
+    pub fn confirm(&mut self, msg: &str, default: Option<bool>) -> io::Result<bool> {
+        let mut retries = 0;
+        loop {
+            let answer = self.prompt(msg)?;
+            let token = answer.trim().to_lowercase();
+            if token.is_empty() {
+                return Ok(default.unwrap_or(false));
+            }
+            if matches(self.locale.yes, &token) {
+                return Ok(true);
+            }
+            if matches(self.locale.no, &token) {
+                return Ok(false);
+            }
+            self.retry(&mut retries, &answer)?;
+        }
+    }
+
+    /// Asks a yes/no/quit question, re-prompting until a recognized answer is
+    /// given.
+    ///
+    /// The answer is matched case-insensitively, ignoring surrounding
+    /// whitespace. `y` and `yes` return [`YesNoQuit::Yes`], `n` and `no`
+    /// return [`YesNoQuit::No`], and `q` and `quit` return [`YesNoQuit::Quit`].
+    /// Reaching the end of the input, for example because the user pressed
+    /// Ctrl-D, also returns [`YesNoQuit::Quit`]. Any other answer, including
+    /// an empty one, causes `msg` to be written again. The accepted answers can
+    /// be changed with [`Prompter::locale`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # fn main() -> std::io::Result<()> {
+    /// use io_prompt_prototype::{Prompter, YesNoQuit};
+    ///
+    /// let mut prompter = Prompter::new(std::io::Cursor::new(b"\nN\n"), Vec::new());
+    /// assert_eq!(prompter.yes_no_quit("Proceed? [y/n/q] >")?, YesNoQuit::No);
+    /// assert_eq!(prompter.yes_no_quit("Proceed? [y/n/q] >")?, YesNoQuit::Quit);
+    /// # Ok(()) }
+    /// ```
+    pub fn yes_no_quit(&mut self, msg: &str) -> io::Result<YesNoQuit> {
+        let mut retries = 0;
+        loop {
+            let answer = match self.prompt_opt(msg)? {
+                Some(answer) => answer,
+                None => return Ok(YesNoQuit::Quit),
+            };
+            let token = answer.trim().to_lowercase();
+            if matches(self.locale.yes, &token) {
+                return Ok(YesNoQuit::Yes);
+            }
+            if matches(self.locale.no, &token) {
+                return Ok(YesNoQuit::No);
+            }
+            if matches(self.locale.quit, &token) {
+                return Ok(YesNoQuit::Quit);
+            }
+            self.retry(&mut retries, &answer)?;
+        }
+    }
+
+    /// Writes `msg` to the output. Then reads a line of input and returns it
+    /// as a `bool`, re-prompting until a recognized answer is given.
+    ///
+    /// The answer is matched case-insensitively, ignoring surrounding
+    /// whitespace, against the following tokens:
+    ///
+    /// | `true`    | `false`    |
+    /// |-----------|------------|
+    /// | `y`       | `n`        |
+    /// | `yes`     | `no`       |
+    /// | `t`       | `f`        |
+    /// | `true`    | `false`    |
+    /// | `1`       | `0`        |
+    /// | `on`      | `off`      |
+    /// | `enable`  | `disable`  |
+    /// | `enabled` | `disabled` |
+    ///
+    /// Unlike [`Prompter::confirm`], an empty answer isn't accepted. If the
+    /// input ends before a recognized answer is given, an error of kind
+    /// [`io::ErrorKind::UnexpectedEof`] is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # fn main() -> std::io::Result<()> {
+    /// use io_prompt_prototype::Prompter;
+    /// use std::io::Cursor;
+    ///
+    /// let mut prompter = Prompter::new(Cursor::new(b"maybe\nOn\n0\n"), Vec::new());
+    /// assert!(prompter.prompt_bool("Verbose? >")?);
+    /// assert!(!prompter.prompt_bool("Colors? >")?);
+    /// # Ok(()) }
+    /// ```
+    pub fn prompt_bool(&mut self, msg: &str) -> io::Result<bool> {
+        self.prompt_bool_with(msg, TRUE_TOKENS, FALSE_TOKENS)
+    }
+
+    /// Writes `msg` to the output. Then reads a line of input and returns it
+    /// as a `bool`, using custom sets of accepted tokens.
+    ///
+    /// This behaves like [`Prompter::prompt_bool`], but an answer is `true` if
+    /// it matches one of `truthy`, and `false` if it matches one of `falsy`.
+    /// Matching is still case-insensitive, which makes it possible to accept
+    /// answers in other languages.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # fn main() -> std::io::Result<()> {
+    /// use io_prompt_prototype::Prompter;
+    /// use std::io::Cursor;
+    ///
+    /// let mut prompter = Prompter::new(Cursor::new(b"yes\nJa\n"), Vec::new());
+    /// assert!(prompter.prompt_bool_with("Weiter? >", &["j", "ja"], &["n", "nein"])?);
+    /// # Ok(()) }
+    /// ```
+    pub fn prompt_bool_with(
+        &mut self,
+        msg: &str,
+        truthy: &[&str],
+        falsy: &[&str],
+    ) -> io::Result<bool> {
+        let mut retries = 0;
+        loop {
+            let answer = self.ask(msg)?;
+            let token = answer.trim().to_lowercase();
+            let matches = |tokens: &[&str]| tokens.iter().any(|t| t.to_lowercase() == token);
+            if matches(truthy) {
+                return Ok(true);
+            }
+            if matches(falsy) {
+                return Ok(false);
+            }
+            self.retry(&mut retries, &answer)?;
+        }
+    }
+
+    /// Writes `msg` to the output and reads an answer. Then echoes the answer
+    /// back and asks for confirmation, re-prompting until it's confirmed.
+    ///
+    /// The confirmation is asked as `You entered: <answer>. Is that correct?
+    /// [y/N] ` and answered as with [`Prompter::confirm`], so an empty answer
+    /// rejects. If the input ends before an answer is confirmed, an error of
+    /// kind [`io::ErrorKind::UnexpectedEof`] is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # fn main() -> std::io::Result<()> {
+    /// use io_prompt_prototype::Prompter;
+    /// use std::io::Cursor;
+    ///
+    /// let input = Cursor::new(b"ferris@example.con\nn\nferris@example.com\ny\n");
+    /// let mut prompter = Prompter::new(input, Vec::new());
+    /// assert_eq!(prompter.prompt_confirmed("Email? >")?, "ferris@example.com");
+    /// # Ok(()) }
+    /// ```
+    pub fn prompt_confirmed(&mut self, msg: &str) -> io::Result<String> {
+        loop {
+            let answer = self.ask(msg)?;
+            let question = fill(self.locale.confirm_answer, &answer);
+            if self.confirm(&question, None)? {
+                return Ok(answer);
+            }
+        }
+    }
+
+    /// Writes `msg` to the output, followed by a hint showing `default`. Then
+    /// reads a line of input, returning `default` if the answer is empty.
+    ///
+    /// The hint is rendered as ` [default] ` after `msg`, with any trailing
+    /// whitespace in `msg` removed. See [`Prompter::prompt_or`] for a variant
+    /// which doesn't render a hint.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # fn main() -> std::io::Result<()> {
+    /// use io_prompt_prototype::Prompter;
+    /// use std::io::Cursor;
+    ///
+    /// let mut prompter = Prompter::new(Cursor::new(b"\n"), Vec::new());
+    /// assert_eq!(prompter.prompt_default("Name? >", "anonymous")?, "anonymous");
+    ///
+    /// let (_, output) = prompter.into_parts();
+    /// assert_eq!(output, b"Name? > [anonymous] ");
+    /// # Ok(()) }
+    /// ```
+    pub fn prompt_default(&mut self, msg: &str, default: impl Into<String>) -> io::Result<String> {
+        let default = default.into();
+        let msg = format!("{} [{}] ", msg.trim_end(), default);
+        self.prompt_or(&msg, default)
+    }
+
+    /// Writes `msg` to the output. Then reads a line of input, returning
+    /// `default` if the answer is empty.
+    ///
+    /// The answer is trimmed of surrounding whitespace, so an answer consisting
+    /// only of whitespace is treated as empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # fn main() -> std::io::Result<()> {
+    /// use io_prompt_prototype::Prompter;
+    /// use std::io::Cursor;
+    ///
+    /// let mut prompter = Prompter::new(Cursor::new(b"  \nFerris\n"), Vec::new());
+    /// assert_eq!(prompter.prompt_or("Name? >", "anonymous")?, "anonymous");
+    /// assert_eq!(prompter.prompt_or("Name? >", "anonymous")?, "Ferris");
+    /// # Ok(()) }
+    /// ```
+    pub fn prompt_or(&mut self, msg: &str, default: impl Into<String>) -> io::Result<String> {
+        let answer = self.prompt(msg)?;
+        match answer.trim() {
+            "" => Ok(default.into()),
+            answer => Ok(answer.to_string()),
+        }
+    }
+
+    /// Writes `msg` to the output. Then reads a line of input and parses it.
+    ///
+    /// The trailing newline is removed before parsing. Parse errors are
+    /// returned in the inner `Result`, as a [`PromptParseError`] which holds
+    /// the answer along with the parser's error. I/O errors are returned in
+    /// the outer one.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+    /// use io_prompt_prototype::Prompter;
+    /// use std::io::Cursor;
+    ///
+    /// let mut prompter = Prompter::new(Cursor::new(b"42\nnope\n"), Vec::new());
+    /// let num: u16 = prompter.parse("What's your favorite number? >")??;
+    /// assert_eq!(num, 42);
+    /// assert!(prompter.parse::<u16>("What's your favorite number? >")?.is_err());
+    /// # Ok(()) }
+    /// ```
+    pub fn parse<T: FromStr>(
+        &mut self,
+        msg: &str,
+    ) -> io::Result<Result<T, PromptParseError<T::Err>>> {
+        let answer = self.prompt(msg)?;
+        Ok(answer
+            .parse()
+            .map_err(|err| PromptParseError::new(answer, 1, err)))
+    }
+
+    /// Writes `msg` to the output. Then reads a line of input and parses it,
+    /// keeping the original text.
+    ///
+    /// This behaves like [`Prompter::parse`], but the parsed value is returned
+    /// as an [`Answer`], which also holds the text it was parsed from.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+    /// use io_prompt_prototype::Prompter;
+    /// use std::io::Cursor;
+    ///
+    /// let mut prompter = Prompter::new(Cursor::new(b"+7\n"), Vec::new());
+    /// let num = prompter.answer::<i8>("Number? >")??;
+    /// assert_eq!((*num, num.as_str()), (7, "+7"));
+    /// # Ok(()) }
+    /// ```
+    pub fn answer<T: FromStr>(&mut self, msg: &str) -> io::Result<Result<Answer<T>, T::Err>> {
+        let text = self.prompt(msg)?;
+        Ok(text.parse().map(|value| Answer::new(value, text)))
+    }
+
+    /// Writes `msg` to the output. Then reads a line of input, splits it on
+    /// `delimiter` and parses each element.
+    ///
+    /// Each element is trimmed before parsing, and empty elements, such as
+    /// those left by a trailing delimiter, are skipped. The first parse error
+    /// is returned in the inner `Result`, I/O errors in the outer one.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+    /// use io_prompt_prototype::Prompter;
+    /// use std::io::Cursor;
+    ///
+    /// let mut prompter = Prompter::new(Cursor::new(b"1, 2,3,\nrust cli  io\n"), Vec::new());
+    /// let nums: Vec<u32> = prompter.list("Numbers? >", ',')??;
+    /// assert_eq!(nums, [1, 2, 3]);
+    /// let tags: Vec<String> = prompter.list("Tags? >", ' ')??;
+    /// assert_eq!(tags, ["rust", "cli", "io"]);
+    /// # Ok(()) }
+    /// ```
+    pub fn list<T: FromStr>(
+        &mut self,
+        msg: &str,
+        delimiter: char,
+    ) -> io::Result<Result<Vec<T>, T::Err>> {
+        Ok(self
+            .prompt(msg)?
+            .split(delimiter)
+            .map(str::trim)
+            .filter(|element| !element.is_empty())
+            .map(str::parse)
+            .collect())
+    }
+
+    /// Writes `msg` to the output and reads a line of input, repeatedly,
+    /// collecting the answers until a blank one is given.
+    ///
+    /// `msg` is written again before each answer. Answers are trimmed of
+    /// surrounding whitespace, and the collecting stops at the first answer
+    /// which is empty after trimming, or when the input ends. The blank answer
+    /// isn't included, so if the first answer is blank an empty `Vec` is
+    /// returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # fn main() -> std::io::Result<()> {
+    /// use io_prompt_prototype::Prompter;
+    /// use std::io::Cursor;
+    ///
+    /// let input = Cursor::new(b"alpha.example.com\n beta.example.com \n\n");
+    /// let mut prompter = Prompter::new(input, Vec::new());
+    /// let hosts = prompter.prompt_repeat("Add host (blank to finish) >")?;
+    /// assert_eq!(hosts, ["alpha.example.com", "beta.example.com"]);
+    /// # Ok(()) }
+    /// ```
+    pub fn prompt_repeat(&mut self, msg: &str) -> io::Result<Vec<String>> {
+        let mut answers = Vec::new();
+        while let Some(answer) = self.prompt_opt(msg)? {
+            let answer = answer.trim();
+            if answer.is_empty() {
+                break;
+            }
+            answers.push(answer.to_string());
+        }
+        Ok(answers)
+    }
+
+    /// Writes `msg` to the output. Then reads a line of input, applying a
+    /// policy for control characters to it.
+    ///
+    /// Rejected answers are returned in the inner `Result`, I/O errors in the
+    /// outer one. See [`ControlChars`] for which characters are affected.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # fn main() -> std::io::Result<()> {
+    /// use io_prompt_prototype::{ControlChars, Prompter};
+    /// use std::io::Cursor;
+    ///
+    /// let mut prompter = Prompter::new(Cursor::new(b"a\x1b[2Jb\na\0b\n"), Vec::new());
+    /// let name = prompter.sanitized("Name? >", ControlChars::Strip)?;
+    /// assert_eq!(name.unwrap(), "a[2Jb");
+    /// assert!(prompter.sanitized("Name? >", ControlChars::Reject)?.is_err());
+    /// # Ok(()) }
+    /// ```
+    pub fn sanitized(
+        &mut self,
+        msg: &str,
+        policy: ControlChars,
+    ) -> io::Result<Result<String, ControlCharError>> {
+        Ok(policy.apply(&self.prompt(msg)?))
+    }
+
+    /// Writes `msg` to the output. Then reads a line of input and parses it,
+    /// re-prompting until parsing succeeds.
+    ///
+    /// Each parse error is written to the output before re-prompting. If the
+    /// input ends before a valid answer is given, an error of kind
+    /// [`io::ErrorKind::UnexpectedEof`] is returned.
+    ///
+    /// Once the retries set with [`Prompter::max_retries`] run out, a
+    /// [`PromptParseError`] is returned, wrapped in an [`io::Error`] of kind
+    /// [`io::ErrorKind::InvalidInput`]. It holds the last answer, how many
+    /// answers were read, and the error parsing the last one returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # fn main() -> std::io::Result<()> {
+    /// use io_prompt_prototype::Prompter;
+    /// use std::io::Cursor;
+    ///
+    /// let mut prompter = Prompter::new(Cursor::new(b"nope\n42\n"), Vec::new());
+    /// let num: u16 = prompter.parse_loop("What's your favorite number? >")?;
+    /// assert_eq!(num, 42);
+    /// # Ok(()) }
+    /// ```
+    pub fn parse_loop<T>(&mut self, msg: &str) -> io::Result<T>
+    where
+        T: FromStr,
+        T::Err: Debug + Display + Send + Sync + 'static,
+    {
+        self.parse_until_valid(msg, str::parse, ToString::to_string)
+    }
+
+    /// Writes `msg` to the output. Then reads a line of input and parses it as
+    /// a number, re-prompting until parsing succeeds.
+    ///
+    /// Surrounding whitespace is trimmed before parsing, and `Please enter a
+    /// number` is written each time parsing fails. Any of the standard integer
+    /// and float types can be read. If the input ends before a valid answer is
+    /// given, an error of kind [`io::ErrorKind::UnexpectedEof`] is returned.
+    /// Running out of retries returns a [`PromptParseError`], as with
+    /// [`Prompter::parse_loop`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # fn main() -> std::io::Result<()> {
+    /// use io_prompt_prototype::Prompter;
+    /// use std::io::Cursor;
+    ///
+    /// let mut prompter = Prompter::new(Cursor::new(b"old\n 42 \n2.5\n"), Vec::new());
+    /// let age: u8 = prompter.number("Age? >")?;
+    /// let height: f64 = prompter.number("Height? >")?;
+    /// assert_eq!((age, height), (42, 2.5));
+    /// # Ok(()) }
+    /// ```
+    pub fn number<T>(&mut self, msg: &str) -> io::Result<T>
+    where
+        T: FromStr,
+        T::Err: Debug + Display + Send + Sync + 'static,
+    {
+        let invalid = self.locale.enter_number;
+        self.parse_until_valid(msg, |answer| answer.trim().parse(), |_| invalid.to_string())
+    }
+
+    /// Writes `msg` to the output. Then reads a line of input and parses it,
+    /// re-prompting until it parses to a value within `range`.
+    ///
+    /// The answer is trimmed of surrounding whitespace before it's parsed, as
+    /// with [`Prompter::number`]. Parse errors are written to the output
+    /// before re-prompting, as are values outside of `range`, along with the
+    /// bounds they have to be in. If the input ends before a valid answer is
+    /// given, an error of kind [`io::ErrorKind::UnexpectedEof`] is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # fn main() -> std::io::Result<()> {
+    /// use io_prompt_prototype::Prompter;
+    /// use std::io::Cursor;
+    ///
+    /// let mut prompter = Prompter::new(Cursor::new(b"0\n8080\n"), Vec::new());
+    /// let port: u16 = prompter.range("Port? >", 1..=65535)?;
+    /// assert_eq!(port, 8080);
+    /// # Ok(()) }
+    /// ```
+    pub fn range<T, B>(&mut self, msg: &str, range: B) -> io::Result<T>
+    where
+        T: FromStr + PartialOrd + Display,
+        T::Err: Display,
+        B: RangeBounds<T>,
+    {
+        let locale = self.locale;
+        self.prompt_with(msg, |answer| {
+            let value: T = answer
+                .trim()
+                .parse()
+                .map_err(|err: T::Err| err.to_string())?;
+            if range.contains(&value) {
+                Ok(value)
+            } else {
+                Err(describe_range(&locale, &range))
+            }
+        })
+    }
+
+    /// Writes `msg` to the output. Then reads a line of input and parses it
+    /// with `parse`, re-prompting until parsing succeeds.
+    ///
+    /// The trailing newline is removed before calling `parse`. Each error
+    /// returned by `parse` is written to the output before re-prompting, so any
+    /// error implementing [`Display`] can be used, including
+    /// `Box<dyn std::error::Error>`. If the input ends before a valid answer is
+    /// given, an error of kind [`io::ErrorKind::UnexpectedEof`] is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # fn main() -> std::io::Result<()> {
+    /// use io_prompt_prototype::Prompter;
+    /// use std::error::Error;
+    /// use std::io::Cursor;
+    ///
+    /// fn parse_pair(s: &str) -> Result<(i32, i32), Box<dyn Error>> {
+    ///     let (x, y) = s.split_once(',').ok_or("expected two numbers separated by a comma")?;
+    ///     Ok((x.trim().parse()?, y.trim().parse()?))
+    /// }
+    ///
+    /// let mut prompter = Prompter::new(Cursor::new(b"1\n1, 2\n"), Vec::new());
+    /// assert_eq!(prompter.prompt_with("Point? >", parse_pair)?, (1, 2));
+    /// # Ok(()) }
+    /// ```
+    pub fn prompt_with<T, E, F>(&mut self, msg: &str, mut parse: F) -> io::Result<T>
+    where
+        F: FnMut(&str) -> Result<T, E>,
+        E: Display,
+    {
+        let mut retries = 0;
+        loop {
+            let answer = self.ask(msg)?;
+            match parse(&answer) {
+                Ok(value) => return Ok(value),
+                Err(err) => writeln!(self.writer, "{}", err)?,
+            }
+            self.retry(&mut retries, &answer)?;
+        }
+    }
+
+    /// Writes `msg` to the output. Then reads a line of input, re-prompting
+    /// until `is_valid` accepts it.
+    ///
+    /// If `invalid` is given it is written, with a newline, each time an answer
+    /// is rejected. If the input ends before an answer is accepted, an error of
+    /// kind [`io::ErrorKind::UnexpectedEof`] is returned rather than looping
+    /// forever.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # fn main() -> std::io::Result<()> {
+    /// use io_prompt_prototype::Prompter;
+    /// use std::io::Cursor;
+    ///
+    /// let mut prompter = Prompter::new(Cursor::new(b"old\n42\n"), Vec::new());
+    /// let is_age = |s: &str| s.parse::<u8>().is_ok();
+    /// let age = prompter.prompt_until("Age? >", is_age, Some("Please enter a number"))?;
+    /// assert_eq!(age, "42");
+    /// # Ok(()) }
+    /// ```
+    pub fn prompt_until<F>(
+        &mut self,
+        msg: &str,
+        mut is_valid: F,
+        invalid: Option<&str>,
+    ) -> io::Result<String>
+    where
+        F: FnMut(&str) -> bool,
+    {
+        let mut retries = 0;
+        loop {
+            let answer = self.ask(msg)?;
+            if is_valid(&answer) {
+                return Ok(answer);
+            }
+            if let Some(invalid) = invalid {
+                writeln!(self.writer, "{}", invalid)?;
+            }
+            self.retry(&mut retries, &answer)?;
+        }
+    }
+
+    /// Writes `msg` and a numbered list of `options` to the output. Then reads
+    /// the number of an option, re-prompting until a valid one is given.
+    ///
+    /// Options are numbered starting from `1`, and input is read after a `> `
+    /// prompt. The returned value is the zero-based index of the chosen option,
+    /// so it can be used to index into `options` directly. If the input ends
+    /// before a valid option is chosen, or `options` is empty, an error is
+    /// returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # fn main() -> std::io::Result<()> {
+    /// use io_prompt_prototype::Prompter;
+    /// use std::io::Cursor;
+    ///
+    /// let options = ["red", "green", "blue"];
+    /// let mut prompter = Prompter::new(Cursor::new(b"2\n"), Vec::new());
+    /// let index = prompter.select("Pick a color:", &options)?;
+    /// assert_eq!(options[index], "green");
+    ///
+    /// let (_, output) = prompter.into_parts();
+    /// assert_eq!(output, b"Pick a color:\n1) red\n2) green\n3) blue\n> ");
+    /// # Ok(()) }
+    /// ```
+    pub fn select<T: Display>(&mut self, msg: &str, options: &[T]) -> io::Result<usize> {
+        if options.is_empty() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "no options to select from",
+            ));
+        }
+        writeln!(self.writer, "{}", msg)?;
+        for (i, option) in options.iter().enumerate() {
+            writeln!(self.writer, "{}) {}", i + 1, option)?;
+        }
+        let mut retries = 0;
+        loop {
+            let answer = self.ask("> ")?;
+            match answer.trim().parse::<usize>() {
+                Ok(n) if (1..=options.len()).contains(&n) => return Ok(n - 1),
+                _ => self.retry(&mut retries, &answer)?,
+            }
+        }
+    }
+
+    /// Writes `msg` and a numbered list of the entries of a map to the output.
+    /// Then reads the number of an entry, re-prompting until a valid one is
+    /// given, and returns its key and value.
+    ///
+    /// The map can be anything which iterates over references to keys and
+    /// values, such as a `&HashMap` or a `&BTreeMap`. Entries are listed
+    /// sorted by key, whatever order the map iterates in, so the numbering of
+    /// a given map is the same on every run even though a `HashMap`'s
+    /// iteration order isn't. Each entry is written as `<n>) <key>: <value>`,
+    /// and otherwise behaves like [`Prompter::select`], including returning
+    /// an error if the map is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # fn main() -> std::io::Result<()> {
+    /// use io_prompt_prototype::Prompter;
+    /// use std::collections::HashMap;
+    /// use std::io::Cursor;
+    ///
+    /// let regions = HashMap::from([("us-east", "Virginia"), ("eu-west", "Ireland")]);
+    /// let mut prompter = Prompter::new(Cursor::new(b"1\n"), Vec::new());
+    /// let (key, _) = prompter.select_map("Region:", &regions)?;
+    /// assert_eq!(*key, "eu-west");
+    ///
+    /// let (_, output) = prompter.into_parts();
+    /// assert_eq!(output, b"Region:\n1) eu-west: Ireland\n2) us-east: Virginia\n> ");
+    /// # Ok(()) }
+    /// ```
+    pub fn select_map<'m, K, V, M>(&mut self, msg: &str, map: M) -> io::Result<(&'m K, &'m V)>
+    where
+        K: Ord + Display + 'm,
+        V: Display + 'm,
+        M: IntoIterator<Item = (&'m K, &'m V)>,
+    {
+        let mut entries: Vec<_> = map.into_iter().collect();
+        entries.sort_by(|a, b| a.0.cmp(b.0));
+        let labels: Vec<_> = entries
+            .iter()
+            .map(|(key, value)| format!("{}: {}", key, value))
+            .collect();
+        let index = self.select(msg, &labels)?;
+        Ok(entries[index])
+    }
+
+    /// Writes `msg` to the output. Then reads a line of input and parses it as
+    /// one of `choices`, re-prompting until parsing succeeds.
+    ///
+    /// Surrounding whitespace is trimmed before parsing. Since the variants of
+    /// an enum can't be enumerated, the valid answers are passed as `choices`,
+    /// and `Please choose one of: <choices>` is written each time parsing
+    /// fails. The answer is parsed with [`FromStr`], so `choices` are only
+    /// shown, and don't restrict what is accepted. If the input ends before a
+    /// valid answer is given, an error of kind [`io::ErrorKind::UnexpectedEof`]
+    /// is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # fn main() -> std::io::Result<()> {
+    /// use io_prompt_prototype::Prompter;
+    /// use std::io::Cursor;
+    /// # use std::str::FromStr;
+    /// # #[derive(Debug, PartialEq)]
+    /// # enum Mode { Fast, Slow }
+    /// # impl FromStr for Mode {
+    /// #     type Err = ();
+    /// #     fn from_str(s: &str) -> Result<Self, ()> {
+    /// #         match s { "fast" => Ok(Mode::Fast), "slow" => Ok(Mode::Slow), _ => Err(()) }
+    /// #     }
+    /// # }
+    ///
+    /// let mut prompter = Prompter::new(Cursor::new(b"medium\nslow\n"), Vec::new());
+    /// let mode: Mode = prompter.choice("Mode? >", &["fast", "slow"])?;
+    /// assert_eq!(mode, Mode::Slow);
+    ///
+    /// let (_, output) = prompter.into_parts();
+    /// assert_eq!(output, b"Mode? >Please choose one of: fast, slow\nMode? >");
+    /// # Ok(()) }
+    /// ```
+    pub fn choice<T: FromStr>(&mut self, msg: &str, choices: &[&str]) -> io::Result<T> {
+        let invalid = fill(self.locale.choose_one_of, &choices.join(", "));
+        self.prompt_with(msg, |answer| answer.trim().parse().map_err(|_| &invalid))
+    }
+
+    /// Writes `msg` to the output. Then reads a line of input, re-prompting
+    /// until it matches one of `allowed`, and returns the matching value.
+    ///
+    /// Surrounding whitespace is trimmed before matching. Unless
+    /// `case_sensitive` is set, the answer is matched case-insensitively, and
+    /// the value is returned as spelled in `allowed`, so `PROD` returns
+    /// `"prod"`. `Please choose one of: <allowed>` is written each time the
+    /// answer doesn't match. Unlike [`choice`](Prompter::choice), nothing is
+    /// parsed: this suits a small set of plain strings, such as environment
+    /// names. If the input ends before a valid answer is given, an error of
+    /// kind [`io::ErrorKind::UnexpectedEof`] is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # fn main() -> std::io::Result<()> {
+    /// use io_prompt_prototype::Prompter;
+    /// use std::io::Cursor;
+    ///
+    /// let mut prompter = Prompter::new(Cursor::new(b"test\n Staging\n"), Vec::new());
+    /// let env = prompter.matching("Env? >", &["dev", "staging", "prod"], false)?;
+    /// assert_eq!(env, "staging");
+    ///
+    /// let (_, output) = prompter.into_parts();
+    /// assert_eq!(output, b"Env? >Please choose one of: dev, staging, prod\nEnv? >");
+    /// # Ok(()) }
+    /// ```
+    pub fn matching<'a>(
+        &mut self,
+        msg: &str,
+        allowed: &[&'a str],
+        case_sensitive: bool,
+    ) -> io::Result<&'a str> {
+        let invalid = fill(self.locale.choose_one_of, &allowed.join(", "));
+        self.prompt_with(msg, |answer| {
+            let answer = answer.trim();
+            allowed
+                .iter()
+                .copied()
+                .find(|value| {
+                    if case_sensitive {
+                        *value == answer
+                    } else {
+                        value.to_lowercase() == answer.to_lowercase()
+                    }
+                })
+                .ok_or(&invalid)
+        })
+    }
+
+    /// Consumes the `Prompter`, returning the underlying reader and writer.
+    pub fn into_parts(self) -> (R, W) {
+        (self.reader, self.writer)
+    }
+
+    /// Counts a retry after `answer` was rejected, returning an error if no
+    /// retries are left.
+    pub(crate) fn retry(&self, retries: &mut usize, answer: &str) -> io::Result<()> {
+        if self.max_retries == Some(*retries) {
+            return Err(RetriesExhausted::new(*retries + 1, answer).into());
+        }
+        *retries += 1;
+        Ok(())
+    }
+
+    /// Writes `msg` and reads answers until `parse` accepts one, writing the
+    /// message `describe` returns for each error.
+    ///
+    /// Unlike `prompt_with`, running out of retries returns the last parse
+    /// error as a `PromptParseError`, rather than a `RetriesExhausted`.
+    fn parse_until_valid<T, E, P, D>(
+        &mut self,
+        msg: &str,
+        mut parse: P,
+        describe: D,
+    ) -> io::Result<T>
+    where
+        P: FnMut(&str) -> Result<T, E>,
+        D: Fn(&E) -> String,
+        E: Debug + Display + Send + Sync + 'static,
+    {
+        let mut attempts = 0;
+        loop {
+            let answer = self.ask(msg)?;
+            attempts += 1;
+            let err = match parse(&answer) {
+                Ok(value) => return Ok(value),
+                Err(err) => err,
+            };
+            writeln!(self.writer, "{}", describe(&err))?;
+            if self.max_retries == Some(attempts - 1) {
+                let err = PromptParseError::new(answer, attempts, err);
+                return Err(io::Error::new(io::ErrorKind::InvalidInput, err));
+            }
+        }
+    }
+
+    /// Writes `msg` and reads an answer for a re-prompting loop.
+    ///
+    /// Unlike `prompt`, reaching the end of the input is an error here, so
+    /// loops can't spin forever on a closed stdin.
+    pub(crate) fn ask(&mut self, msg: &str) -> io::Result<String> {
+        self.prompt_opt(msg)?.ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "input ended before a valid answer was given",
+            )
+        })
+    }
+
+    /// Flushes the output, then reads a line into the line buffer, with its
+    /// newline removed.
+    fn read(&mut self) -> io::Result<()> {
+        self.flush()?;
+        self.line.clear();
+        append_line(&mut self.reader, &mut self.line)?;
+        trim_newline(&mut self.line);
+        trace::answer(&self.line);
+        if let Some(log) = self.answer_log {
+            log(&mut self.writer, &self.line)?;
+        }
+        Ok(())
+    }
+
+    /// Records an answer which was read, if answers are logged.
+    pub(crate) fn log_answer(&mut self, answer: &str) -> io::Result<()> {
+        match self.answer_log {
+            Some(log) => log(&mut self.writer, answer),
+            None => Ok(()),
+        }
+    }
+
+    /// Flushes the output, unless flushing was turned off.
+    fn flush(&mut self) -> io::Result<()> {
+        if self.auto_flush {
+            self.writer.flush()?;
+        }
+        Ok(())
+    }
+}
+
+/// Describes the values within `range`, for example `expected a value between
+/// 1 and 10`.
+fn describe_range<T: Display, B: RangeBounds<T>>(locale: &Locale, range: &B) -> String {
+    use Bound::*;
+    match (range.start_bound(), range.end_bound()) {
+        (Included(start), Included(end)) => {
+            fill2(locale.range_between, &start.to_string(), &end.to_string())
+        }
+        (Included(start), Excluded(end)) => fill2(
+            locale.range_at_least_less_than,
+            &start.to_string(),
+            &end.to_string(),
+        ),
+        (Excluded(start), Included(end)) => fill2(
+            locale.range_greater_than_at_most,
+            &start.to_string(),
+            &end.to_string(),
+        ),
+        (Excluded(start), Excluded(end)) => fill2(
+            locale.range_greater_than_less_than,
+            &start.to_string(),
+            &end.to_string(),
+        ),
+        (Included(start), Unbounded) => fill(locale.range_at_least, &start.to_string()),
+        (Excluded(start), Unbounded) => fill(locale.range_greater_than, &start.to_string()),
+        (Unbounded, Included(end)) => fill(locale.range_at_most, &end.to_string()),
+        (Unbounded, Excluded(end)) => fill(locale.range_less_than, &end.to_string()),
+        // Every value is within an unbounded range, so this is only reached by
+        // a `RangeBounds` implementation which overrides `contains`.
+        (Unbounded, Unbounded) => locale.enter_number.to_string(),
+    }
+}
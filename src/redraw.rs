@@ -0,0 +1,114 @@
+use std::io::{self, BufRead, Write};
+
+use crate::{prompt_stream, read_line_trimmed_from, script, trace};
+
+/// A point at which [`prompt_redraw`] hands the terminal to the caller.
+///
+/// The callback given to [`prompt_redraw`] is called twice for each prompt,
+/// first with `Clear` and then with `Restore`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Redraw {
+    /// The prompt is about to be written. Any spinner or progress bar should
+    /// erase its line, leaving the cursor at the start of an empty line, and
+    /// stop drawing.
+    Clear,
+    /// The answer has been read, or reading it failed. The cursor is on the
+    /// line below the answer, where drawing can resume.
+    Restore,
+}
+
+/// Prints to the standard output, sharing the terminal with a spinner or
+/// progress bar. Then reads a line of input, removing the trailing newline.
+///
+/// Programs which animate a line of the terminal from another thread would
+/// otherwise draw over the prompt, or have the prompt written into the middle
+/// of their line. Instead, `redraw` is called around the prompt, in this
+/// order:
+///
+/// 1. `redraw(Redraw::Clear)`, before anything is written.
+/// 2. `msg` is written to the stream set with [`set_prompt_stream`] and
+///    flushed.
+/// 3. The answer is read from stdin, as with [`read_line_trimmed`].
+/// 4. `redraw(Redraw::Restore)`, whether or not the answer could be read.
+///
+/// The callback is expected to block any background output between the two
+/// calls, such as by holding a lock its drawing thread also takes. This is an
+/// integration point for progress libraries rather than a terminal UI: the
+/// prompt itself is written and read like any other.
+///
+/// [`set_prompt_stream`]: crate::set_prompt_stream
+/// [`read_line_trimmed`]: crate::read_line_trimmed
+///
+/// # Examples
+///
+/// ```no_run
+/// # fn main() -> std::io::Result<()> {
+/// use io_prompt_prototype::{prompt_redraw, Redraw};
+/// use std::io::Write;
+/// use std::sync::atomic::{AtomicBool, Ordering};
+///
+/// // Checked by the thread drawing the progress bar.
+/// static PAUSED: AtomicBool = AtomicBool::new(false);
+///
+/// let answer = prompt_redraw("Overwrite out.txt? [y/N] >", |event| match event {
+///     Redraw::Clear => {
+///         PAUSED.store(true, Ordering::SeqCst);
+///         print!("\r\x1b[K");
+///     }
+///     Redraw::Restore => PAUSED.store(false, Ordering::SeqCst),
+/// })?;
+/// # Ok(()) }
+/// ```
+pub fn prompt_redraw<F>(msg: &str, redraw: F) -> io::Result<String>
+where
+    F: FnMut(Redraw),
+{
+    prompt_redraw_from(&mut script::input(), &mut prompt_stream(), msg, redraw)
+}
+
+/// Writes `msg` to a writer, calling `redraw` around it. Then reads a line of
+/// input from a reader, removing the trailing newline.
+///
+/// This behaves exactly like [`prompt_redraw`], but reads from an arbitrary
+/// [`BufRead`] and writes to an arbitrary [`Write`].
+///
+/// # Examples
+///
+/// ```
+/// # fn main() -> std::io::Result<()> {
+/// use io_prompt_prototype::{prompt_redraw_from, Redraw};
+/// use std::io::Cursor;
+///
+/// let mut events = Vec::new();
+/// let mut output = Vec::new();
+/// let answer = prompt_redraw_from(&mut Cursor::new(b"y\n"), &mut output, "> ", |event| {
+///     events.push(event)
+/// })?;
+/// assert_eq!(answer, "y");
+/// assert_eq!(events, [Redraw::Clear, Redraw::Restore]);
+/// # Ok(()) }
+/// ```
+pub fn prompt_redraw_from<R, W, F>(
+    reader: &mut R,
+    writer: &mut W,
+    msg: &str,
+    mut redraw: F,
+) -> io::Result<String>
+where
+    R: BufRead,
+    W: Write,
+    F: FnMut(Redraw),
+{
+    redraw(Redraw::Clear);
+    let answer = ask(reader, writer, msg);
+    redraw(Redraw::Restore);
+    answer
+}
+
+/// Writes and flushes `msg`, then reads the answer.
+fn ask<R: BufRead, W: Write>(reader: &mut R, writer: &mut W, msg: &str) -> io::Result<String> {
+    trace::prompt(msg);
+    writer.write_all(msg.as_bytes())?;
+    writer.flush()?;
+    trace::read_answer(read_line_trimmed_from(reader))
+}
@@ -0,0 +1,101 @@
+use std::error::Error;
+use std::fmt;
+
+/// How control characters in an answer are handled.
+///
+/// Characters are considered control characters if [`char::is_control`]
+/// returns `true` for them: `U+0000` to `U+001F`, which includes tabs, escape
+/// and NUL, and `U+007F` to `U+009F`. The trailing line terminator is removed
+/// before the policy is applied, so it's never affected.
+///
+/// # Examples
+///
+/// ```
+/// use io_prompt_prototype::ControlChars;
+///
+/// assert_eq!(ControlChars::Strip.apply("a\x1b[2Jb").unwrap(), "a[2Jb");
+/// assert!(ControlChars::Reject.apply("a\x1b[2Jb").is_err());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ControlChars {
+    /// Remove control characters from the answer.
+    Strip,
+    /// Return an error if the answer contains a control character.
+    Reject,
+}
+
+impl ControlChars {
+    /// Applies this policy to `answer`.
+    pub fn apply(self, answer: &str) -> Result<String, ControlCharError> {
+        match self {
+            ControlChars::Strip => Ok(answer.chars().filter(|c| !c.is_control()).collect()),
+            ControlChars::Reject => match answer.char_indices().find(|(_, c)| c.is_control()) {
+                Some((index, ch)) => Err(ControlCharError { ch, index }),
+                None => Ok(answer.to_string()),
+            },
+        }
+    }
+}
+
+/// The error returned when an answer containing a control character is
+/// rejected.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ControlCharError {
+    ch: char,
+    index: usize,
+}
+
+impl ControlCharError {
+    /// Returns the first control character in the answer.
+    pub fn control_char(&self) -> char {
+        self.ch
+    }
+
+    /// Returns the byte index of the first control character in the answer.
+    pub fn index(&self) -> usize {
+        self.index
+    }
+}
+
+impl fmt::Display for ControlCharError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "answer contains control character U+{:04X} at byte {}",
+            self.ch as u32, self.index
+        )
+    }
+}
+
+impl Error for ControlCharError {}
+
+/// Prints to the standard output. Then reads a line of input, stripping or
+/// rejecting any control characters in it.
+///
+/// Control characters are stripped by default, and can be rejected instead by
+/// passing `policy = ControlChars::Reject`. See [`ControlChars`] for which
+/// characters are affected. Answers which feed into shells, SQL or later
+/// terminal output are natural candidates. This panics on I/O errors, while
+/// rejected answers are returned as an error.
+///
+/// # Examples
+///
+/// ```no_run
+/// # fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+/// use io_prompt_prototype::{prompt_sanitized, ControlChars};
+///
+/// let name = prompt_sanitized!("Name? >")?;
+/// let query = prompt_sanitized!("Query? >", policy = ControlChars::Reject)?;
+/// # Ok(()) }
+/// ```
+#[macro_export]
+macro_rules! prompt_sanitized {
+    ($msg:expr, policy = $policy:expr $(,)?) => {
+        $crate::Prompter::stdio()
+            .sanitized(&$msg, $policy)
+            .unwrap_or_else(|e| panic!("failed prompting on stdin/stdout: {}", e))
+    };
+    ($msg:expr $(,)?) => {
+        $crate::prompt_sanitized!($msg, policy = $crate::ControlChars::Strip)
+    };
+}
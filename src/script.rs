@@ -0,0 +1,261 @@
+//! Scripted answers which replace stdin and stdout, for testing prompt flows.
+
+use std::collections::VecDeque;
+use std::io::{self, stdin, BufRead, Read, StdinLock};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+/// The scripted session, if one is active.
+static SCRIPT: Mutex<Option<Script>> = Mutex::new(None);
+
+/// Whether a byte order mark should be stripped from the next input read from
+/// stdin.
+static STRIP_BOM: AtomicBool = AtomicBool::new(false);
+
+/// The UTF-8 encoding of the byte order mark, `U+FEFF`.
+const BOM: &[u8] = b"\xef\xbb\xbf";
+
+/// The input read from stdin which hasn't been consumed yet.
+///
+/// Everything stdin has buffered is moved here as soon as it's read, so the
+/// line readers and the timed reads, which wait on the file descriptor, share
+/// one buffer, and no input is left in stdin's own buffer where polling the
+/// file descriptor can't see it.
+static PENDING: Mutex<Vec<u8>> = Mutex::new(Vec::new());
+
+/// The answers left to give, and the prompts written so far.
+#[derive(Debug, Default)]
+struct Script {
+    answers: VecDeque<String>,
+    output: Vec<u8>,
+}
+
+fn script() -> MutexGuard<'static, Option<Script>> {
+    SCRIPT.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Queues an answer to be given to the next prompt, starting a scripted
+/// session if there isn't one.
+///
+/// While a scripted session is active, all prompt macros and functions which
+/// would read from stdin read the queued answers instead, one line per
+/// answer, and everything they would write to stdout or stderr is captured
+/// rather than printed. It can be retrieved with [`take_scripted_output`].
+/// Prompts behave as if stdin were not a terminal, so for example passwords
+/// are read as plain lines. Once the queued answers run out the input ends,
+/// which makes most prompts fail.
+///
+/// This is intended for end-to-end tests of prompt flows, without spawning a
+/// child process. The session is global to the process, so tests using it
+/// shouldn't run concurrently with each other or with anything else using
+/// stdin or stdout. Call [`end_script`] to end the session. The async
+/// functions aren't affected by it.
+///
+/// # Examples
+///
+/// ```
+/// use io_prompt_prototype::{end_script, prompt, push_scripted_answer, take_scripted_output};
+///
+/// push_scripted_answer("Ferris");
+/// push_scripted_answer("crab");
+/// let name = prompt!("What's your name? >");
+/// let kind = prompt!("What are you, {}? >", name);
+/// assert_eq!(kind, "crab");
+/// assert_eq!(
+///     take_scripted_output(),
+///     "What's your name? >What are you, Ferris? >"
+/// );
+/// end_script();
+/// ```
+pub fn push_scripted_answer(answer: &str) {
+    script()
+        .get_or_insert_with(Script::default)
+        .answers
+        .push_back(answer.to_string());
+}
+
+/// Returns everything written by prompts during the scripted session so far,
+/// and clears it.
+///
+/// Returns an empty string if no scripted session is active. Invalid UTF-8 is
+/// replaced with `U+FFFD`. See [`push_scripted_answer`] for how a session
+/// works.
+pub fn take_scripted_output() -> String {
+    match script().as_mut() {
+        Some(script) => {
+            let output = std::mem::take(&mut script.output);
+            String::from_utf8_lossy(&output).into_owned()
+        }
+        None => String::new(),
+    }
+}
+
+/// Ends the scripted session, discarding any answers and output left.
+///
+/// Prompts read from stdin and write to stdout again afterwards. Does nothing
+/// if no scripted session is active.
+pub fn end_script() {
+    *script() = None;
+}
+
+/// Returns `true` if a scripted session is active.
+///
+/// See [`push_scripted_answer`] for how a session works.
+///
+/// # Examples
+///
+/// ```
+/// use io_prompt_prototype::is_scripted;
+///
+/// assert!(!is_scripted());
+/// ```
+pub fn is_scripted() -> bool {
+    script().is_some()
+}
+
+/// Strips a UTF-8 byte order mark from the start of stdin.
+///
+/// Text files saved by some Windows editors begin with a byte order mark,
+/// `U+FEFF`, which ends up at the start of the first answer when such a file
+/// is piped in, and makes it fail to parse. After calling `set_strip_bom(true)`
+/// a byte order mark at the start of the next input read from stdin is
+/// removed, by any of the read functions and prompt macros. Later lines are
+/// left alone, since a `U+FEFF` there is part of the text. It's meant to be
+/// called once at startup, before anything is read. By default nothing is
+/// stripped, so the answer is exactly what was sent.
+///
+/// # Examples
+///
+/// ```no_run
+/// use io_prompt_prototype::{prompt, set_strip_bom};
+///
+/// set_strip_bom(true);
+/// // Parses even when run as `my-cli < answers.txt`, with a BOM in the file.
+/// let num: u32 = prompt!("Number? >").parse().unwrap();
+/// ```
+pub fn set_strip_bom(strip: bool) {
+    STRIP_BOM.store(strip, Ordering::Relaxed);
+}
+
+/// Returns the input read from stdin which hasn't been consumed yet.
+///
+/// It has to be locked before stdin is, so that the two are always locked in
+/// the same order.
+pub(crate) fn pending() -> MutexGuard<'static, Vec<u8>> {
+    PENDING.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Moves all the input stdin has buffered to `pending`, reading more if it
+/// has none, and stripping a byte order mark if [`set_strip_bom`] asked for
+/// it.
+///
+/// Returns `false` once the input has ended.
+pub(crate) fn fill_pending(stdin: &mut StdinLock<'_>, pending: &mut Vec<u8>) -> io::Result<bool> {
+    let buf = stdin.fill_buf()?;
+    let len = buf.len();
+    let mut input = buf;
+    // Only the first input read is checked for a byte order mark.
+    if !buf.is_empty()
+        && STRIP_BOM.load(Ordering::Relaxed)
+        && STRIP_BOM.swap(false, Ordering::Relaxed)
+        && buf.starts_with(BOM)
+    {
+        input = &buf[BOM.len()..];
+    }
+    pending.extend_from_slice(input);
+    stdin.consume(len);
+    Ok(len > 0)
+}
+
+/// Captures `buf` if a scripted session is active, returning whether it was.
+pub(crate) fn capture(buf: &[u8]) -> bool {
+    match script().as_mut() {
+        Some(script) => {
+            script.output.extend_from_slice(buf);
+            true
+        }
+        None => false,
+    }
+}
+
+/// Returns the input prompts read from: the scripted answers while a scripted
+/// session is active, and stdin otherwise.
+pub(crate) fn input() -> Input {
+    let inner = if is_scripted() {
+        Inner::Script {
+            line: Vec::new(),
+            pos: 0,
+        }
+    } else {
+        let pending = pending();
+        Inner::Stdin {
+            stdin: stdin().lock(),
+            pending,
+        }
+    };
+    Input { inner }
+}
+
+/// The input read by [`Prompter::stdio`].
+///
+/// This reads from stdin, or from the scripted answers while a scripted
+/// session is active. See [`push_scripted_answer`] for how a session works.
+///
+/// [`Prompter::stdio`]: crate::Prompter::stdio
+#[derive(Debug)]
+pub struct Input {
+    inner: Inner,
+}
+
+#[derive(Debug)]
+enum Inner {
+    /// Stdin, and the input read from it which hasn't been consumed yet.
+    Stdin {
+        stdin: StdinLock<'static>,
+        pending: MutexGuard<'static, Vec<u8>>,
+    },
+    /// The answer currently being read, and how much of it has been read.
+    Script { line: Vec<u8>, pos: usize },
+}
+
+impl Read for Input {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let available = self.fill_buf()?;
+        let len = available.len().min(buf.len());
+        buf[..len].copy_from_slice(&available[..len]);
+        self.consume(len);
+        Ok(len)
+    }
+}
+
+impl BufRead for Input {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        match &mut self.inner {
+            Inner::Stdin { stdin, pending } => {
+                while pending.is_empty() && fill_pending(stdin, pending)? {}
+                Ok(&pending[..])
+            }
+            Inner::Script { line, pos } => {
+                if *pos == line.len() {
+                    line.clear();
+                    *pos = 0;
+                    if let Some(answer) = script().as_mut().and_then(|s| s.answers.pop_front()) {
+                        line.extend_from_slice(answer.as_bytes());
+                        line.push(b'\n');
+                    }
+                }
+                Ok(&line[*pos..])
+            }
+        }
+    }
+
+    fn consume(&mut self, amt: usize) {
+        match &mut self.inner {
+            Inner::Stdin { pending, .. } => {
+                let amt = amt.min(pending.len());
+                pending.drain(..amt);
+            }
+            Inner::Script { line, pos } => *pos = (*pos + amt).min(line.len()),
+        }
+    }
+}
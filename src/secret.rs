@@ -0,0 +1,212 @@
+use std::fmt;
+use std::io::{self, BufRead};
+use std::mem;
+
+use zeroize::{Zeroize, ZeroizeOnDrop, Zeroizing};
+
+use crate::password::end_hidden_line;
+use crate::{is_eof_marker, script, term, trim_newline};
+
+/// How many bytes are reserved for a secret before reading it.
+///
+/// Secrets which fit are read without reallocating, so no partial copies of
+/// them are freed along the way.
+const INITIAL_CAPACITY: usize = 128;
+
+/// A secret, such as a password, which is overwritten with zeros when dropped.
+///
+/// Returned by [`read_secret`]. The secret can be borrowed with
+/// [`expose`](Secret::expose), or taken out with
+/// [`into_inner`](Secret::into_inner) when it has to be handed to an API which
+/// requires a `String`, at which point it's no longer cleared. Its `Debug`
+/// output doesn't include the secret.
+///
+/// Zeroing is best effort. Rust moves values freely, and copies made before
+/// the secret reached this type are out of its control: notably whatever the
+/// terminal, the operating system and std's buffer for stdin kept of it, and
+/// any copy made from the exposed `&str`. What it does guarantee is that the
+/// buffers this crate allocates while reading the secret are cleared before
+/// they're freed, so the secret doesn't linger in freed heap memory.
+///
+/// This requires the `zeroize` feature.
+///
+/// # Examples
+///
+/// ```
+/// # fn main() -> std::io::Result<()> {
+/// use io_prompt_prototype::read_secret_from;
+/// use std::io::Cursor;
+///
+/// let password = read_secret_from(&mut Cursor::new(b"hunter2\n"))?;
+/// assert_eq!(password.expose(), "hunter2");
+/// assert_eq!(format!("{:?}", password), "Secret(<redacted>)");
+/// # Ok(()) }
+/// ```
+pub struct Secret(String);
+
+impl Secret {
+    /// Wraps a string, so that it's cleared when dropped.
+    pub fn new(secret: String) -> Self {
+        Self(secret)
+    }
+
+    /// Returns the secret.
+    pub fn expose(&self) -> &str {
+        &self.0
+    }
+
+    /// Returns the secret as a `String`, which won't be cleared when dropped.
+    pub fn into_inner(mut self) -> String {
+        mem::take(&mut self.0)
+    }
+}
+
+impl Drop for Secret {
+    fn drop(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+impl ZeroizeOnDrop for Secret {}
+
+impl fmt::Debug for Secret {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Secret(<redacted>)")
+    }
+}
+
+/// Reads a secret from stdin without echoing it to the terminal, clearing it
+/// from memory once it's dropped.
+///
+/// This behaves like [`read_password`], including writing a newline after a
+/// hidden read, but returns a [`Secret`], and reads
+/// into buffers which are overwritten with zeros before they're freed. See
+/// [`Secret`] for what that does and doesn't cover.
+///
+/// This requires the `zeroize` feature.
+///
+/// [`read_password`]: crate::read_password
+///
+/// # Examples
+///
+/// ```no_run
+/// # fn main() -> std::io::Result<()> {
+/// use io_prompt_prototype::read_secret;
+///
+/// print!("Password: >");
+/// let password = read_secret()?;
+/// # Ok(()) }
+/// ```
+pub fn read_secret() -> io::Result<Secret> {
+    if !term::stdin_is_tty() {
+        return read_secret_from(&mut script::input());
+    }
+    let secret = {
+        let _guard = term::disable_echo()?;
+        read_secret_from(&mut script::input())?
+    };
+    end_hidden_line()?;
+    Ok(secret)
+}
+
+/// Reads a secret from a reader, clearing it from memory once it's dropped.
+///
+/// This reads a line and removes the trailing newline, exactly like
+/// [`read_line_trimmed_from`]. Any buffer grown while reading, and the bytes of
+/// a line which isn't valid UTF-8, are cleared as well.
+///
+/// [`read_line_trimmed_from`]: crate::read_line_trimmed_from
+///
+/// # Examples
+///
+/// ```
+/// # fn main() -> std::io::Result<()> {
+/// use io_prompt_prototype::read_secret_from;
+/// use std::io::Cursor;
+///
+/// let pin = read_secret_from(&mut Cursor::new(b"1234\r\n"))?;
+/// assert_eq!(pin.expose(), "1234");
+/// # Ok(()) }
+/// ```
+pub fn read_secret_from<R: BufRead>(reader: &mut R) -> io::Result<Secret> {
+    let mut bytes = Zeroizing::new(Vec::with_capacity(INITIAL_CAPACITY));
+    loop {
+        let buf = match reader.fill_buf() {
+            Ok(buf) => buf,
+            Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+            Err(err) => return Err(err),
+        };
+        if buf.is_empty() {
+            break;
+        }
+        let (chunk, terminated) = match buf.iter().position(|&b| b == b'\n') {
+            Some(i) => (&buf[..=i], true),
+            None => (buf, false),
+        };
+        reserve(&mut bytes, chunk.len());
+        bytes.extend_from_slice(chunk);
+        let len = chunk.len();
+        reader.consume(len);
+        if terminated {
+            break;
+        }
+    }
+    if is_eof_marker(&bytes) {
+        bytes.clear();
+    }
+    let mut secret = match String::from_utf8(mem::take(&mut *bytes)) {
+        Ok(secret) => Secret(secret),
+        Err(err) => {
+            err.into_bytes().zeroize();
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "stream did not contain valid UTF-8",
+            ));
+        }
+    };
+    trim_newline(&mut secret.0);
+    Ok(secret)
+}
+
+/// Makes room for `additional` more bytes, moving `bytes` to a larger buffer
+/// and clearing the old one rather than letting `Vec` reallocate it.
+fn reserve(bytes: &mut Zeroizing<Vec<u8>>, additional: usize) {
+    if bytes.capacity() - bytes.len() >= additional {
+        return;
+    }
+    let capacity = (bytes.len() + additional).max(bytes.capacity() * 2);
+    let mut grown = Zeroizing::new(Vec::with_capacity(capacity));
+    grown.extend_from_slice(bytes);
+    mem::swap(bytes, &mut grown);
+}
+
+/// Prints to the standard output. Then reads a secret without echoing it to
+/// the terminal, clearing it from memory once it's dropped.
+///
+/// This is a shorthand for calling [`print!`] and [`read_secret`]. Like
+/// [`prompt_password!`], this panics on I/O errors.
+///
+/// This requires the `zeroize` feature.
+///
+/// [`read_secret`]: crate::read_secret
+/// [`prompt_password!`]: crate::prompt_password!
+///
+/// # Examples
+///
+/// ```no_run
+/// use io_prompt_prototype::prompt_secret;
+///
+/// let password = prompt_secret!("Password for {}: >", "ferris");
+/// ```
+#[macro_export]
+macro_rules! prompt_secret {
+    ($($arg:tt)*) => {{
+        use std::io::Write;
+        let mut out = $crate::prompt_stream();
+        $crate::__private::write_prompt(&mut out, format_args!($($arg)*))
+            .unwrap_or_else(|e| panic!("failed writing to {}: {}", out, e));
+        out.flush().unwrap_or_else(|e| panic!("failed writing to {}: {}", out, e));
+        $crate::__private::read_secret($crate::read_secret())
+            .unwrap_or_else(|e| panic!("failed reading from stdin: {}", e))
+    }};
+}
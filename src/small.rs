@@ -0,0 +1,172 @@
+use std::fmt;
+use std::io::{self, BufRead};
+use std::ops::Deref;
+use std::str;
+
+use smallvec::SmallVec;
+
+use crate::{is_eof_marker, script};
+
+/// How many bytes of an answer a [`SmallLine`] stores inline.
+pub const SMALL_LINE_CAPACITY: usize = 32;
+
+/// A line of input which is stored inline when it's short.
+///
+/// Returned by [`read_line_small`]. Answers of up to [`SMALL_LINE_CAPACITY`]
+/// bytes of UTF-8 are kept on the stack, and only longer ones are moved to the
+/// heap. The trailing `\n` doesn't count towards the capacity, but a `\r`
+/// before it does, since it's only known to be part of the terminator once the
+/// `\n` is read. It dereferences to a `str`, without its line terminator.
+///
+/// This requires the `smallvec` feature.
+///
+/// # Examples
+///
+/// ```
+/// # fn main() -> std::io::Result<()> {
+/// use io_prompt_prototype::read_line_small_from;
+/// use std::io::Cursor;
+///
+/// let line = read_line_small_from(&mut Cursor::new(b"yes\n"))?;
+/// assert_eq!(&*line, "yes");
+/// assert!(!line.spilled());
+/// # Ok(()) }
+/// ```
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct SmallLine {
+    bytes: SmallVec<[u8; SMALL_LINE_CAPACITY]>,
+}
+
+impl SmallLine {
+    /// Returns the line, without its line terminator.
+    pub fn as_str(&self) -> &str {
+        // The bytes were checked to be UTF-8 when the line was read.
+        str::from_utf8(&self.bytes).expect("a small line is valid UTF-8")
+    }
+
+    /// Returns `true` if the line was too long to be stored inline, and was
+    /// moved to the heap.
+    pub fn spilled(&self) -> bool {
+        self.bytes.spilled()
+    }
+}
+
+impl Deref for SmallLine {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl AsRef<str> for SmallLine {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl fmt::Debug for SmallLine {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.as_str(), f)
+    }
+}
+
+impl fmt::Display for SmallLine {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Reads a line of input from stdin into a [`SmallLine`], removing the
+/// trailing newline.
+///
+/// This behaves like [`read_line_trimmed`], but doesn't allocate for answers
+/// which fit in [`SMALL_LINE_CAPACITY`] bytes. Only the answer itself is kept
+/// inline: stdin's own buffer is allocated once per process regardless. If the
+/// line isn't valid UTF-8, an error of kind [`io::ErrorKind::InvalidData`] is
+/// returned.
+///
+/// This requires the `smallvec` feature.
+///
+/// [`read_line_trimmed`]: crate::read_line_trimmed
+///
+/// # Examples
+///
+/// ```no_run
+/// # fn main() -> std::io::Result<()> {
+/// use io_prompt_prototype::read_line_small;
+///
+/// print!("Continue? [y/N] >");
+/// let answer = read_line_small()?;
+/// if answer.eq_ignore_ascii_case("y") {
+///     println!("Continuing");
+/// }
+/// # Ok(()) }
+/// ```
+pub fn read_line_small() -> io::Result<SmallLine> {
+    read_line_small_from(&mut script::input())
+}
+
+/// Reads a line of input from a reader into a [`SmallLine`], removing the
+/// trailing newline.
+///
+/// This behaves exactly like [`read_line_small`], but reads from an arbitrary
+/// [`BufRead`] rather than from stdin.
+///
+/// # Examples
+///
+/// ```
+/// # fn main() -> std::io::Result<()> {
+/// use io_prompt_prototype::read_line_small_from;
+/// use std::io::Cursor;
+///
+/// let mut input = Cursor::new(format!("ok\r\n{}\n", "x".repeat(100)));
+/// assert!(!read_line_small_from(&mut input)?.spilled());
+/// assert!(read_line_small_from(&mut input)?.spilled());
+/// # Ok(()) }
+/// ```
+pub fn read_line_small_from<R: BufRead>(reader: &mut R) -> io::Result<SmallLine> {
+    let mut bytes = SmallVec::new();
+    let mut terminated = false;
+    loop {
+        let buf = match reader.fill_buf() {
+            Ok(buf) => buf,
+            Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+            Err(err) => return Err(err),
+        };
+        if buf.is_empty() {
+            break;
+        }
+        match buf.iter().position(|&b| b == b'\n') {
+            Some(i) => {
+                bytes.extend_from_slice(&buf[..i]);
+                reader.consume(i + 1);
+                terminated = true;
+                break;
+            }
+            None => {
+                let len = buf.len();
+                bytes.extend_from_slice(buf);
+                reader.consume(len);
+            }
+        }
+    }
+    if is_eof_marker(&bytes) {
+        bytes.clear();
+    }
+    let line = str::from_utf8(&bytes).map_err(|_| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            "stream did not contain valid UTF-8",
+        )
+    })?;
+    let len = if terminated {
+        line.strip_suffix('\r').unwrap_or(line).len()
+    } else {
+        line.strip_suffix(['\r', '\u{2028}', '\u{2029}'])
+            .unwrap_or(line)
+            .len()
+    };
+    bytes.truncate(len);
+    Ok(SmallLine { bytes })
+}
@@ -0,0 +1,157 @@
+use std::fmt;
+use std::io::{self, stderr, stdout, IsTerminal, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::{is_scripted, script};
+
+/// Whether prompts are written to stderr rather than stdout.
+static PROMPT_ON_STDERR: AtomicBool = AtomicBool::new(false);
+
+/// A standard output stream which prompts can be written to.
+///
+/// Writing to a `Stream` writes to the standard stream it names. While a
+/// scripted session is active, the output is captured instead. See
+/// [`push_scripted_answer`].
+///
+/// If the stream is a pipe whose reading end was closed, such as when the
+/// output is piped into `head` which then exits, writing fails with an error
+/// of kind [`io::ErrorKind::BrokenPipe`]. Its message says that the pipe was
+/// closed, so the panics of the prompt macros explain what happened. The
+/// `try_` macros return the error instead, which lets a program exit quietly.
+///
+/// [`push_scripted_answer`]: crate::push_scripted_answer
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stream {
+    /// The standard output.
+    Stdout,
+    /// The standard error.
+    Stderr,
+}
+
+impl fmt::Display for Stream {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Stream::Stdout => f.write_str("stdout"),
+            Stream::Stderr => f.write_str("stderr"),
+        }
+    }
+}
+
+impl Write for Stream {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if script::capture(buf) {
+            return Ok(buf.len());
+        }
+        match self {
+            Stream::Stdout => stdout().write(buf),
+            Stream::Stderr => stderr().write(buf),
+        }
+        .map_err(|err| self.closed(err))
+    }
+
+    fn write_all(&mut self, buf: &[u8]) -> io::Result<()> {
+        if script::capture(buf) {
+            return Ok(());
+        }
+        match self {
+            Stream::Stdout => stdout().write_all(buf),
+            Stream::Stderr => stderr().write_all(buf),
+        }
+        .map_err(|err| self.closed(err))
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        if is_scripted() {
+            return Ok(());
+        }
+        match self {
+            Stream::Stdout => stdout().flush(),
+            Stream::Stderr => stderr().flush(),
+        }
+        .map_err(|err| self.closed(err))
+    }
+}
+
+impl Stream {
+    /// Returns `true` if the standard stream this names is a terminal.
+    ///
+    /// This doesn't take a scripted session into account, during which the
+    /// output is captured rather than written to the stream.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use io_prompt_prototype::prompt_stream;
+    ///
+    /// if prompt_stream().is_terminal() {
+    ///     println!("Prompts are shown on a terminal");
+    /// }
+    /// ```
+    pub fn is_terminal(&self) -> bool {
+        match self {
+            Stream::Stdout => stdout().is_terminal(),
+            Stream::Stderr => stderr().is_terminal(),
+        }
+    }
+
+    /// Replaces a broken pipe error with one explaining that the pipe was
+    /// closed. Other errors are returned unchanged.
+    fn closed(&self, err: io::Error) -> io::Error {
+        if err.kind() == io::ErrorKind::BrokenPipe {
+            let msg = format!(
+                "{} is a closed pipe, the program reading it has exited",
+                self
+            );
+            io::Error::new(io::ErrorKind::BrokenPipe, msg)
+        } else {
+            err
+        }
+    }
+}
+
+/// Sets the stream which prompts are written to for the whole process.
+///
+/// This affects all subsequent prompts written by this crate, from any
+/// thread: those of the macros such as [`prompt!`] and [`confirm!`], of
+/// [`Prompter::stdio`], and of the functions which prompt on the terminal,
+/// such as [`prompt_timeout`] and `prompt_async`. It's meant to be called
+/// once at startup, for example to keep stdout a clean data channel. Prompts
+/// are written to stdout by default. The `e`-prefixed macros always write to
+/// stderr.
+///
+/// [`prompt!`]: crate::prompt
+/// [`confirm!`]: crate::confirm
+/// [`Prompter::stdio`]: crate::Prompter::stdio
+/// [`prompt_timeout`]: crate::prompt_timeout()
+///
+/// # Examples
+///
+/// ```no_run
+/// use io_prompt_prototype::{prompt, set_prompt_stream, Stream};
+///
+/// set_prompt_stream(Stream::Stderr);
+/// let name = prompt!("What's your name? >");
+/// println!("{}", name);
+/// ```
+pub fn set_prompt_stream(stream: Stream) {
+    PROMPT_ON_STDERR.store(stream == Stream::Stderr, Ordering::Relaxed);
+}
+
+/// Returns the stream which prompts are written to.
+///
+/// See [`set_prompt_stream`] for which prompts this affects.
+///
+/// # Examples
+///
+/// ```
+/// use io_prompt_prototype::{prompt_stream, Stream};
+///
+/// assert_eq!(prompt_stream(), Stream::Stdout);
+/// ```
+pub fn prompt_stream() -> Stream {
+    if PROMPT_ON_STDERR.load(Ordering::Relaxed) {
+        Stream::Stderr
+    } else {
+        Stream::Stdout
+    }
+}
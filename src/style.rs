@@ -0,0 +1,108 @@
+/// A style which can be applied to prompt text.
+///
+/// Styles are rendered using ANSI escape codes, which requires the `color`
+/// feature to be enabled. Without it, applying a style leaves the text
+/// unchanged.
+///
+/// # Examples
+///
+/// ```no_run
+/// use io_prompt_prototype::{prompt_styled, Style};
+///
+/// let name = prompt_styled!(Style::Green, "What's your name? >");
+/// println!("Hello, {}!", name);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Style {
+    /// Bold text.
+    Bold,
+    /// Red text.
+    Red,
+    /// Green text.
+    Green,
+    /// Yellow text.
+    Yellow,
+    /// Blue text.
+    Blue,
+    /// Magenta text.
+    Magenta,
+    /// Cyan text.
+    Cyan,
+}
+
+impl Style {
+    /// Wraps `text` in the escape codes for this style.
+    ///
+    /// The style is reset at the end of `text`, so anything written afterwards,
+    /// such as the user's answer, is unaffected. When the `color` feature is
+    /// disabled `text` is returned unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use io_prompt_prototype::Style;
+    ///
+    /// let text = Style::Red.apply("Name? >");
+    /// if cfg!(feature = "color") {
+    ///     assert_eq!(text, "\x1b[31mName? >\x1b[0m");
+    /// } else {
+    ///     assert_eq!(text, "Name? >");
+    /// }
+    /// ```
+    pub fn apply(self, text: &str) -> String {
+        if cfg!(feature = "color") {
+            format!("\x1b[{}m{}\x1b[0m", self.code(), text)
+        } else {
+            text.to_string()
+        }
+    }
+
+    /// Returns the SGR parameter for this style.
+    fn code(self) -> u8 {
+        match self {
+            Style::Bold => 1,
+            Style::Red => 31,
+            Style::Green => 32,
+            Style::Yellow => 33,
+            Style::Blue => 34,
+            Style::Magenta => 35,
+            Style::Cyan => 36,
+        }
+    }
+}
+
+/// Prints styled text to the standard output. Then reads a line of input.
+///
+/// The style is only applied when the `color` feature is enabled and the
+/// stream chosen with [`set_prompt_stream`] is a terminal, so output which is
+/// piped or redirected never contains escape codes. Only the prompt text is
+/// styled, not the user's answer.
+///
+/// [`set_prompt_stream`]: crate::set_prompt_stream
+///
+/// # Examples
+///
+/// ```no_run
+/// use io_prompt_prototype::{prompt_styled, Style};
+///
+/// let name = prompt_styled!(Style::Bold, "What's your {}? >", "name");
+/// println!("Hello, {}!", name);
+/// ```
+#[macro_export]
+macro_rules! prompt_styled {
+    ($style:expr, $($arg:tt)*) => {{
+        use std::io::Write;
+        let msg = format!($($arg)*);
+        let mut out = $crate::prompt_stream();
+        $crate::__private::trace_prompt(&msg);
+        if out.is_terminal() && !$crate::is_scripted() {
+            out.write_all($crate::Style::apply($style, &msg).as_bytes())
+        } else {
+            out.write_all(msg.as_bytes())
+        }
+        .unwrap_or_else(|e| panic!("failed writing to {}: {}", out, e));
+        out.flush().unwrap_or_else(|e| panic!("failed writing to {}: {}", out, e));
+        $crate::__private::read_answer($crate::read_line_trimmed())
+            .unwrap_or_else(|e| panic!("failed reading from stdin: {}", e))
+    }};
+}
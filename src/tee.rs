@@ -0,0 +1,146 @@
+use std::io::{self, BufRead, Write};
+
+use crate::Prompter;
+
+/// A writer which duplicates everything written to it to a log.
+///
+/// Meant as the output of a [`Prompter`], so that an interactive session can
+/// be recorded while it's shown on the terminal, see [`Prompter::tee`].
+/// Output is written to the primary writer first. Whatever it accepted is
+/// then written to the log in full.
+///
+/// Flushing flushes the primary writer, then the log. Both are flushed even
+/// if the first fails, and the first error is returned. Since a `Prompter`
+/// flushes its output before each read, a buffered log such as a
+/// [`BufWriter`](std::io::BufWriter) around a file is up to date whenever an
+/// answer is waited for. With [`Prompter::auto_flush`] turned off, neither
+/// writer is flushed until the caller does so.
+///
+/// # Examples
+///
+/// ```
+/// use io_prompt_prototype::Tee;
+/// use std::io::Write;
+///
+/// let mut tee = Tee::new(Vec::new(), Vec::new());
+/// write!(tee, "Name? >").unwrap();
+/// let (terminal, log) = tee.into_parts();
+/// assert_eq!(terminal, log);
+/// ```
+#[derive(Debug)]
+pub struct Tee<W, L> {
+    primary: W,
+    log: L,
+}
+
+impl<W: Write, L: Write> Tee<W, L> {
+    /// Creates a writer which writes to `primary`, and duplicates its output to
+    /// `log`.
+    pub fn new(primary: W, log: L) -> Self {
+        Self { primary, log }
+    }
+
+    /// Returns a mutable reference to the primary writer.
+    pub fn primary_mut(&mut self) -> &mut W {
+        &mut self.primary
+    }
+
+    /// Returns a mutable reference to the log.
+    pub fn log_mut(&mut self) -> &mut L {
+        &mut self.log
+    }
+
+    /// Consumes the `Tee`, returning the primary writer and the log.
+    pub fn into_parts(self) -> (W, L) {
+        (self.primary, self.log)
+    }
+
+    /// Writes an answer to the log only, on its own line.
+    ///
+    /// The terminal already echoed the answer as it was typed, so it isn't
+    /// written to the primary writer.
+    fn log_answer(&mut self, answer: &str) -> io::Result<()> {
+        writeln!(self.log, "{}", answer)
+    }
+}
+
+impl<W: Write, L: Write> Write for Tee<W, L> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = self.primary.write(buf)?;
+        self.log.write_all(&buf[..written])?;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        let primary = self.primary.flush();
+        let log = self.log.flush();
+        primary.and(log)
+    }
+}
+
+impl<R: BufRead, W: Write, L: Write> Prompter<R, Tee<W, L>> {
+    /// Creates a new `Prompter` which writes its prompts both to `primary`,
+    /// usually the terminal, and to `log`.
+    ///
+    /// Everything the `Prompter` writes, including re-prompts and the messages
+    /// explaining why an answer was rejected, goes to both. Answers only
+    /// reach the log if [`Prompter::log_answers`] is turned on. See [`Tee`]
+    /// for how the two writers are flushed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # fn main() -> std::io::Result<()> {
+    /// use io_prompt_prototype::Prompter;
+    /// use std::io::Cursor;
+    ///
+    /// let mut prompter = Prompter::tee(Cursor::new(b"Ferris\n"), Vec::new(), Vec::new());
+    /// prompter.prompt("What's your name? >")?;
+    ///
+    /// let (_, output) = prompter.into_parts();
+    /// let (terminal, log) = output.into_parts();
+    /// assert_eq!(terminal, b"What's your name? >");
+    /// assert_eq!(log, b"What's your name? >");
+    /// # Ok(()) }
+    /// ```
+    pub fn tee(reader: R, primary: W, log: L) -> Self {
+        Self::new(reader, Tee::new(primary, log))
+    }
+
+    /// Sets whether answers are written to the log, each on its own line after
+    /// the prompt it answers.
+    ///
+    /// By default they aren't, so the log only records what was asked. Only
+    /// the lines read by the `Prompter` are logged: passwords and secrets are
+    /// read by functions such as [`read_password`] instead, so they never end
+    /// up in the log. Their masked echo is written by those functions to the
+    /// writer they're given, which leaves out the password itself.
+    ///
+    /// [`read_password`]: crate::read_password
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # fn main() -> std::io::Result<()> {
+    /// use io_prompt_prototype::Prompter;
+    /// use std::io::Cursor;
+    ///
+    /// let mut prompter =
+    ///     Prompter::tee(Cursor::new(b"Ferris\n"), Vec::new(), Vec::new()).log_answers(true);
+    /// prompter.prompt("What's your name? >")?;
+    ///
+    /// let (_, output) = prompter.into_parts();
+    /// let (terminal, log) = output.into_parts();
+    /// assert_eq!(terminal, b"What's your name? >");
+    /// assert_eq!(log, b"What's your name? >Ferris\n");
+    /// # Ok(()) }
+    /// ```
+    pub fn log_answers(mut self, log_answers: bool) -> Self {
+        self.answer_log = if log_answers {
+            Some(Tee::log_answer)
+        } else {
+            None
+        };
+        self
+    }
+}
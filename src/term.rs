@@ -0,0 +1,221 @@
+//! Terminal detection and mode handling for stdin.
+
+use std::fmt;
+use std::io::{self, stdin, stdout, IsTerminal};
+
+use crate::is_scripted;
+
+/// Returns `true` if both stdin and stdout are connected to a terminal.
+///
+/// This is a good indication that there is a user to answer prompts, as
+/// opposed to running in a CI pipeline or as part of a shell pipeline. Both
+/// streams are checked, since a prompt is only useful if the user can see it
+/// and answer it. To check a stream individually, use
+/// [`IsTerminal::is_terminal`] from the standard library.
+///
+/// While a scripted session is active this always returns `true`, since the
+/// scripted answers stand in for the user. See [`push_scripted_answer`].
+///
+/// [`push_scripted_answer`]: crate::push_scripted_answer
+///
+/// # Examples
+///
+/// ```no_run
+/// use io_prompt_prototype::{is_interactive, prompt};
+///
+/// let name = if is_interactive() {
+///     prompt!("What's your name? >")
+/// } else {
+///     String::from("anonymous")
+/// };
+/// ```
+pub fn is_interactive() -> bool {
+    is_scripted() || stdin().is_terminal() && stdout().is_terminal()
+}
+
+/// Returns `true` if stdin is connected to a terminal, and no scripted session
+/// is active.
+pub(crate) fn stdin_is_tty() -> bool {
+    !is_scripted() && stdin().is_terminal()
+}
+
+/// Disables echoing of stdin until the returned guard is dropped.
+pub(crate) fn disable_echo() -> io::Result<RawModeGuard> {
+    RawModeGuard::without_echo()
+}
+
+/// Puts stdin into raw mode until the returned guard is dropped.
+pub(crate) fn enable_raw() -> io::Result<RawModeGuard> {
+    RawModeGuard::raw()
+}
+
+/// A guard which restores the terminal mode when dropped.
+///
+/// The prompts which change the terminal mode, such as [`read_password`] and
+/// [`read_char`], do so through this guard. Because the mode is restored in
+/// `Drop`, it's restored when the read returns early with an error or panics
+/// too, rather than leaving the shell with echo disabled. It can be used
+/// directly to keep the terminal in a mode for longer than a single read.
+///
+/// [`read_password`]: crate::read_password
+/// [`read_char`]: crate::read_char
+///
+/// # Examples
+///
+/// ```no_run
+/// # fn main() -> std::io::Result<()> {
+/// use io_prompt_prototype::{read_line_trimmed, RawModeGuard};
+///
+/// let guard = RawModeGuard::without_echo()?;
+/// print!("Password: >");
+/// let password = read_line_trimmed()?;
+/// print!("Confirm: >");
+/// let confirmation = read_line_trimmed()?;
+/// guard.restore()?;
+/// # Ok(()) }
+/// ```
+pub struct RawModeGuard {
+    restore: Option<Box<dyn FnOnce() -> io::Result<()> + Send>>,
+}
+
+impl RawModeGuard {
+    /// Puts the terminal attached to stdin into raw mode.
+    ///
+    /// In raw mode input is neither echoed nor line buffered, and keypresses
+    /// such as Ctrl-C are delivered as bytes rather than as signals.
+    pub fn raw() -> io::Result<Self> {
+        Self::set(sys::raw)
+    }
+
+    /// Disables echoing of input on the terminal attached to stdin.
+    ///
+    /// Input is still line buffered, so it can be read a line at a time.
+    pub fn without_echo() -> io::Result<Self> {
+        Self::set(sys::without_echo)
+    }
+
+    /// Creates a guard which calls `restore` when dropped.
+    ///
+    /// This makes it possible to manage the mode of other terminals, such as
+    /// a pseudo-terminal or a mock in tests, the same way.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use io_prompt_prototype::RawModeGuard;
+    /// use std::sync::atomic::{AtomicBool, Ordering};
+    /// use std::sync::Arc;
+    ///
+    /// let restored = Arc::new(AtomicBool::new(false));
+    /// let flag = restored.clone();
+    /// drop(RawModeGuard::new(move || {
+    ///     flag.store(true, Ordering::SeqCst);
+    ///     Ok(())
+    /// }));
+    /// assert!(restored.load(Ordering::SeqCst));
+    /// ```
+    pub fn new<F>(restore: F) -> Self
+    where
+        F: FnOnce() -> io::Result<()> + Send + 'static,
+    {
+        Self {
+            restore: Some(Box::new(restore)),
+        }
+    }
+
+    /// Restores the original mode now, returning any error doing so.
+    ///
+    /// Errors are ignored when the guard is dropped instead.
+    pub fn restore(mut self) -> io::Result<()> {
+        match self.restore.take() {
+            Some(restore) => restore(),
+            None => Ok(()),
+        }
+    }
+
+    /// Switches stdin to the mode returned by `change`, restoring the current
+    /// mode when dropped.
+    fn set(change: fn(&sys::Mode) -> sys::Mode) -> io::Result<Self> {
+        let original = sys::get_mode()?;
+        sys::set_mode(&change(&original))?;
+        Ok(Self::new(move || sys::set_mode(&original)))
+    }
+}
+
+impl fmt::Debug for RawModeGuard {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RawModeGuard")
+            .field("restored", &self.restore.is_none())
+            .finish()
+    }
+}
+
+impl Drop for RawModeGuard {
+    fn drop(&mut self) {
+        if let Some(restore) = self.restore.take() {
+            let _ = restore();
+        }
+    }
+}
+
+#[cfg(unix)]
+mod sys {
+    use rustix::termios::{self, LocalModes, OptionalActions, Termios};
+    use std::io::{self, stdin};
+
+    pub(crate) type Mode = Termios;
+
+    pub(crate) fn get_mode() -> io::Result<Mode> {
+        Ok(termios::tcgetattr(stdin())?)
+    }
+
+    pub(crate) fn set_mode(mode: &Mode) -> io::Result<()> {
+        Ok(termios::tcsetattr(stdin(), OptionalActions::Now, mode)?)
+    }
+
+    pub(crate) fn without_echo(mode: &Mode) -> Mode {
+        let mut mode = mode.clone();
+        mode.local_modes.remove(LocalModes::ECHO);
+        mode
+    }
+
+    pub(crate) fn raw(mode: &Mode) -> Mode {
+        let mut mode = mode.clone();
+        mode.make_raw();
+        mode
+    }
+}
+
+#[cfg(windows)]
+mod sys {
+    use std::io::{self, stdin};
+    use winapi_util::console;
+
+    /// `ENABLE_PROCESSED_INPUT` from the Windows console API.
+    const ENABLE_PROCESSED_INPUT: u32 = 0x0001;
+    /// `ENABLE_LINE_INPUT` from the Windows console API.
+    const ENABLE_LINE_INPUT: u32 = 0x0002;
+    /// `ENABLE_ECHO_INPUT` from the Windows console API.
+    const ENABLE_ECHO_INPUT: u32 = 0x0004;
+    /// `ENABLE_VIRTUAL_TERMINAL_INPUT` from the Windows console API.
+    const ENABLE_VIRTUAL_TERMINAL_INPUT: u32 = 0x0200;
+
+    pub(crate) type Mode = u32;
+
+    pub(crate) fn get_mode() -> io::Result<Mode> {
+        console::mode(stdin())
+    }
+
+    pub(crate) fn set_mode(mode: &Mode) -> io::Result<()> {
+        console::set_mode(stdin(), *mode)
+    }
+
+    pub(crate) fn without_echo(mode: &Mode) -> Mode {
+        mode & !ENABLE_ECHO_INPUT
+    }
+
+    pub(crate) fn raw(mode: &Mode) -> Mode {
+        let cooked = ENABLE_PROCESSED_INPUT | ENABLE_LINE_INPUT | ENABLE_ECHO_INPUT;
+        (mode & !cooked) | ENABLE_VIRTUAL_TERMINAL_INPUT
+    }
+}
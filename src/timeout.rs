@@ -0,0 +1,456 @@
+use std::io::{self, stdin, Write};
+use std::mem;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+use crate::{
+    is_scripted, prompt_stream, read_line_opt, read_line_trimmed, script, term, trace, trim_newline,
+};
+
+/// How often `read_line_cancelable` checks whether it's been canceled.
+const CANCEL_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// What `next_pending_line` found.
+enum Pending {
+    /// A line, with its trailing newline if it has one.
+    Line(Vec<u8>),
+    /// The input has ended, and no input was left.
+    Ended,
+    /// No complete line arrived before the wait gave up.
+    NotReady,
+}
+
+/// Prints to the standard output. Then reads a line of input, giving up after
+/// a timeout.
+///
+/// This is a shorthand for writing `msg` to stdout and calling
+/// [`read_line_timeout`]. See its documentation for the details on how the
+/// timeout is applied.
+///
+/// # Examples
+///
+/// ```no_run
+/// # fn main() -> std::io::Result<()> {
+/// use io_prompt_prototype::prompt_timeout;
+/// use std::time::Duration;
+///
+/// match prompt_timeout("Still there? >", Duration::from_secs(10))? {
+///     Some(answer) => println!("You said: {}", answer),
+///     None => println!("Nobody answered"),
+/// }
+/// # Ok(()) }
+/// ```
+pub fn prompt_timeout(msg: &str, dur: Duration) -> io::Result<Option<String>> {
+    trace::prompt(msg);
+    let mut out = prompt_stream();
+    out.write_all(msg.as_bytes())?;
+    out.flush()?;
+    trace::read_answer(read_line_timeout(dur))
+}
+
+/// Prints to the standard output. Then reads a line of input, giving up once
+/// a deadline has passed.
+///
+/// This is [`prompt_timeout`] with an absolute deadline instead of a relative
+/// timeout, which suits schedulers working towards a fixed point in time. The
+/// time left until `deadline` is used as the timeout of
+/// [`read_line_timeout`], which documents the details. A deadline given as a
+/// [`SystemTime`](std::time::SystemTime) can be converted with
+/// `Instant::now() + deadline.duration_since(SystemTime::now())`, as long as
+/// it's in the future.
+///
+/// If `deadline` has already passed, `msg` is still printed, but `Ok(None)` is
+/// returned right away, without reading anything. That's the case even if a
+/// line or a scripted answer was already available.
+///
+/// # Examples
+///
+/// ```no_run
+/// # fn main() -> std::io::Result<()> {
+/// use io_prompt_prototype::prompt_deadline;
+/// use std::time::{Duration, Instant};
+///
+/// let deadline = Instant::now() + Duration::from_secs(60);
+/// while let Some(task) = prompt_deadline("Next task? >", deadline)? {
+///     println!("Queued {}", task);
+/// }
+/// println!("Time's up");
+/// # Ok(()) }
+/// ```
+pub fn prompt_deadline(msg: &str, deadline: Instant) -> io::Result<Option<String>> {
+    trace::prompt(msg);
+    let mut out = prompt_stream();
+    out.write_all(msg.as_bytes())?;
+    out.flush()?;
+    let now = Instant::now();
+    if deadline <= now {
+        return Ok(None);
+    }
+    trace::read_answer(read_line_timeout(deadline - now))
+}
+
+/// Prints to the standard output, with a hint showing the default and the
+/// timeout. Then reads a line of input, returning the default if it's empty or
+/// doesn't arrive in time.
+///
+/// The hint is rendered as ` [default in 10s] ` after `msg`, with any trailing
+/// whitespace in `msg` removed. The answer is trimmed of surrounding
+/// whitespace. If no complete line arrives within `dur`, a newline is printed
+/// to end the prompt line and `default` is returned, which suits unattended
+/// flows like kiosks and installers. See [`read_line_timeout`] for how the
+/// timeout is applied, and which platforms are supported.
+///
+/// When stdin isn't a terminal, its input comes from a program rather than a
+/// person, so there's no timeout: a line is read as with [`read_line_opt`],
+/// however long it takes to arrive, and the default is used if it's empty or
+/// the input has ended.
+///
+/// # Examples
+///
+/// ```no_run
+/// # fn main() -> std::io::Result<()> {
+/// use io_prompt_prototype::prompt_timeout_default;
+/// use std::time::Duration;
+///
+/// // Prints "Install updates? [yes in 30s] "
+/// let answer = prompt_timeout_default("Install updates?", Duration::from_secs(30), "yes")?;
+/// # Ok(()) }
+/// ```
+pub fn prompt_timeout_default(
+    msg: &str,
+    dur: Duration,
+    default: impl Into<String>,
+) -> io::Result<String> {
+    let default = default.into();
+    let msg = format!(
+        "{} [{} in {}] ",
+        msg.trim_end(),
+        default,
+        format_duration(dur)
+    );
+    let answer = if term::stdin_is_tty() {
+        prompt_timeout(&msg, dur)?
+    } else {
+        trace::prompt(&msg);
+        let mut out = prompt_stream();
+        out.write_all(msg.as_bytes())?;
+        out.flush()?;
+        trace::read_answer(read_line_opt())?
+    };
+    match answer {
+        Some(answer) if !answer.trim().is_empty() => Ok(answer.trim().to_string()),
+        Some(_) => Ok(default),
+        None => {
+            let mut out = prompt_stream();
+            out.write_all(b"\n")?;
+            out.flush()?;
+            Ok(default)
+        }
+    }
+}
+
+/// Formats `dur` in whole seconds, or in milliseconds if it isn't a whole
+/// number of seconds.
+fn format_duration(dur: Duration) -> String {
+    if dur.subsec_millis() == 0 {
+        format!("{}s", dur.as_secs())
+    } else {
+        format!("{}ms", dur.as_millis())
+    }
+}
+
+/// Reads a line of input from stdin, giving up after a timeout.
+///
+/// Returns `Ok(None)` if no complete line arrived within `dur`, and
+/// `Ok(Some(line))` with the trailing newline removed otherwise. Reaching the
+/// end of the input counts as completing the line.
+///
+/// On timeout, any partial line received so far is discarded. Whatever input
+/// is available is read at once, and the lines after the first are kept in a
+/// buffer inside this crate, which all of its reads from stdin share. So they
+/// aren't lost, and are returned by the next read, whether it's timed or not,
+/// without waiting.
+///
+/// # Platform support
+///
+/// On Unix this waits on the stdin file descriptor using `poll`, and returns
+/// an error if stdin isn't a valid file descriptor. Other platforms aren't
+/// supported yet and always return an error of kind
+/// [`io::ErrorKind::Unsupported`].
+///
+/// # Examples
+///
+/// ```no_run
+/// # fn main() -> std::io::Result<()> {
+/// use io_prompt_prototype::read_line_timeout;
+/// use std::time::Duration;
+///
+/// print!("Still there? >");
+/// if read_line_timeout(Duration::from_secs(10))?.is_none() {
+///     println!("Nobody answered");
+/// }
+/// # Ok(()) }
+/// ```
+pub fn read_line_timeout(dur: Duration) -> io::Result<Option<String>> {
+    if is_scripted() {
+        // Scripted answers are always available right away.
+        return read_line_trimmed().map(Some);
+    }
+    let deadline = Instant::now() + dur;
+    let line = next_pending_line(true, || {
+        sys::wait_readable(deadline.saturating_duration_since(Instant::now()))
+    })?;
+    match line {
+        Pending::Line(line) => into_line(line).map(Some),
+        Pending::Ended => Ok(Some(String::new())),
+        Pending::NotReady => Ok(None),
+    }
+}
+
+/// Reads a line of input from stdin, giving up once `canceled` is set.
+///
+/// Returns `Ok(None)` if `canceled` is set to `true` before a complete line
+/// arrives, and `Ok(Some(line))` with the trailing newline removed otherwise.
+/// Reaching the end of the input counts as completing the line. This lets
+/// another thread cancel a blocking prompt, for example when a Cancel button
+/// is clicked in a GUI. The flag isn't reset, so it has to be set back to
+/// `false` before the next read.
+///
+/// Rather than being woken up by the flag, this waits for input in slices of
+/// 50 milliseconds, and checks the flag between them, so it may take up to
+/// that long for a cancellation to take effect. The flag is checked before
+/// waiting for the first time too, so a read which is canceled up front
+/// returns right away. On cancel, any partial line received so far is
+/// discarded: it has been read from stdin, and won't be returned by a later
+/// read either. As with [`read_line_timeout`], a line which was already read
+/// from stdin along with an earlier one is returned without waiting.
+///
+/// While a scripted session is active, the next scripted answer is returned
+/// right away, unless the flag is already set.
+///
+/// # Platform support
+///
+/// On Unix this waits on the stdin file descriptor using `poll`, and returns
+/// an error if stdin isn't a valid file descriptor. Other platforms aren't
+/// supported yet and always return an error of kind
+/// [`io::ErrorKind::Unsupported`].
+///
+/// # Examples
+///
+/// ```no_run
+/// # fn main() -> std::io::Result<()> {
+/// use io_prompt_prototype::read_line_cancelable;
+/// use std::sync::atomic::{AtomicBool, Ordering};
+/// use std::sync::Arc;
+/// use std::thread;
+/// use std::time::Duration;
+///
+/// let canceled = Arc::new(AtomicBool::new(false));
+/// let cancel = Arc::clone(&canceled);
+/// thread::spawn(move || {
+///     thread::sleep(Duration::from_secs(30));
+///     cancel.store(true, Ordering::SeqCst);
+/// });
+///
+/// print!("Name? >");
+/// match read_line_cancelable(&canceled)? {
+///     Some(name) => println!("Hello, {}!", name),
+///     None => println!("Canceled"),
+/// }
+/// # Ok(()) }
+/// ```
+pub fn read_line_cancelable(canceled: &AtomicBool) -> io::Result<Option<String>> {
+    if canceled.load(Ordering::SeqCst) {
+        return Ok(None);
+    }
+    if is_scripted() {
+        return read_line_trimmed().map(Some);
+    }
+    let line = next_pending_line(true, || loop {
+        if canceled.load(Ordering::SeqCst) {
+            return Ok(false);
+        }
+        if sys::wait_readable(CANCEL_POLL_INTERVAL)? {
+            return Ok(true);
+        }
+    })?;
+    match line {
+        Pending::Line(line) => into_line(line).map(Some),
+        Pending::Ended => Ok(Some(String::new())),
+        Pending::NotReady => Ok(None),
+    }
+}
+
+/// Returns a complete line from stdin if one is available, without blocking.
+///
+/// Returns `Ok(Some(line))`, with the trailing newline removed, if a full
+/// line has been entered, and `Ok(None)` right away otherwise. This lets an
+/// event loop check for an answer on each iteration, without a thread
+/// dedicated to reading stdin. Once the input has ended, a last line without
+/// a trailing newline is returned, after which an error of kind
+/// [`io::ErrorKind::UnexpectedEof`] is returned.
+///
+/// Whatever input is available is read on each call, and a partial line is
+/// kept in a buffer inside this crate until the rest of it arrives. That
+/// buffer is shared with the other functions reading stdin, such as
+/// [`read_line`](crate::read_line()), so `poll_line` can be mixed with them
+/// without skipping input. Reads from stdin which bypass this crate don't see
+/// it though. While a scripted session is active, the next scripted answer is
+/// always returned.
+///
+/// # Platform support
+///
+/// On Unix, stdin is checked for input with a zero timeout using `poll`
+/// before it's read, so reads never block, and stdin itself isn't switched to
+/// non-blocking mode, which would affect other processes sharing it. Other
+/// platforms aren't supported yet and always return an error of kind
+/// [`io::ErrorKind::Unsupported`].
+///
+/// # Examples
+///
+/// ```no_run
+/// # fn main() -> std::io::Result<()> {
+/// use io_prompt_prototype::poll_line;
+/// use std::thread;
+/// use std::time::Duration;
+///
+/// println!("Type `quit` to stop");
+/// loop {
+///     if let Some(line) = poll_line()? {
+///         if line == "quit" {
+///             break;
+///         }
+///     }
+///     // Do other work, such as redrawing.
+///     thread::sleep(Duration::from_millis(16));
+/// }
+/// # Ok(()) }
+/// ```
+pub fn poll_line() -> io::Result<Option<String>> {
+    if is_scripted() {
+        return read_line_trimmed().map(Some);
+    }
+    match next_pending_line(false, || sys::wait_readable(Duration::ZERO))? {
+        Pending::Line(line) => into_line(line).map(Some),
+        Pending::Ended => Err(io::Error::new(
+            io::ErrorKind::UnexpectedEof,
+            "stdin has ended",
+        )),
+        Pending::NotReady => Ok(None),
+    }
+}
+
+/// Returns the next line from the input read from stdin, reading more each
+/// time `wait` reports that stdin is readable, until `wait` returns `false`.
+///
+/// A complete line which was already read is returned without calling
+/// `wait`. The input is shared with [`Input`](crate::Input), which moves
+/// everything stdin has buffered into it, so a line read along with an
+/// earlier one is never left where polling the file descriptor can't see it.
+/// If `discard` is set, a partial line is dropped once `wait` gives up.
+fn next_pending_line<F>(discard: bool, mut wait: F) -> io::Result<Pending>
+where
+    F: FnMut() -> io::Result<bool>,
+{
+    let mut pending = script::pending();
+    let mut stdin = stdin().lock();
+    loop {
+        if let Some(i) = pending.iter().position(|&b| b == b'\n') {
+            return Ok(Pending::Line(pending.drain(..=i).collect()));
+        }
+        if !wait()? {
+            if discard {
+                pending.clear();
+            }
+            return Ok(Pending::NotReady);
+        }
+        match script::fill_pending(&mut stdin, &mut pending) {
+            Ok(true) => {}
+            Ok(false) if pending.is_empty() => return Ok(Pending::Ended),
+            Ok(false) => return Ok(Pending::Line(mem::take(&mut *pending))),
+            Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+            Err(err) => return Err(err),
+        }
+    }
+}
+
+/// Converts the bytes of a line to a string, removing the trailing newline.
+fn into_line(line: Vec<u8>) -> io::Result<String> {
+    let mut line = String::from_utf8(line).map_err(|_| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            "stream did not contain valid UTF-8",
+        )
+    })?;
+    trim_newline(&mut line);
+    Ok(line)
+}
+
+#[cfg(unix)]
+mod sys {
+    use rustix::event::{poll, PollFd, PollFlags, Timespec};
+    use std::convert::TryFrom;
+    use std::io::{self, stdin};
+    use std::time::Duration;
+
+    /// Waits until stdin is readable, returning `false` on timeout.
+    pub(crate) fn wait_readable(timeout: Duration) -> io::Result<bool> {
+        let stdin = stdin();
+        let timeout = Timespec::try_from(timeout)
+            .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "timeout too large"))?;
+        let mut fds = [PollFd::new(&stdin, PollFlags::IN)];
+        loop {
+            match poll(&mut fds, Some(&timeout)) {
+                Ok(0) => return Ok(false),
+                Ok(_) if fds[0].revents().contains(PollFlags::NVAL) => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidInput,
+                        "stdin is not a valid file descriptor",
+                    ))
+                }
+                Ok(_) => return Ok(true),
+                Err(rustix::io::Errno::INTR) => continue,
+                Err(err) => return Err(err.into()),
+            }
+        }
+    }
+}
+
+#[cfg(not(unix))]
+mod sys {
+    use std::io;
+    use std::time::Duration;
+
+    /// Waits until stdin is readable, returning `false` on timeout.
+    pub(crate) fn wait_readable(_timeout: Duration) -> io::Result<bool> {
+        Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "reading with a timeout is only supported on Unix",
+        ))
+    }
+}
+
+/// Prints to the standard output, with a hint showing the default and the
+/// timeout. Then reads a line of input, returning the default if it's empty or
+/// doesn't arrive in time.
+///
+/// This is a shorthand for calling [`prompt_timeout_default`], which
+/// describes the details. This panics on I/O errors, including on platforms
+/// where timeouts aren't supported.
+///
+/// # Examples
+///
+/// ```no_run
+/// use io_prompt_prototype::prompt_with_timeout_default;
+/// use std::time::Duration;
+///
+/// // Prints "Language? > [en in 10s] "
+/// let language = prompt_with_timeout_default!("Language? >", Duration::from_secs(10), "en");
+/// ```
+#[macro_export]
+macro_rules! prompt_with_timeout_default {
+    ($msg:expr, $dur:expr, $default:expr $(,)?) => {
+        $crate::prompt_timeout_default(&$msg, $dur, $default)
+            .unwrap_or_else(|e| panic!("failed prompting on stdin/stdout: {}", e))
+    };
+}
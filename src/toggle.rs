@@ -0,0 +1,176 @@
+use std::fmt::Display;
+use std::io::{self, Read, Write};
+
+use crate::editor::Editor;
+use crate::input::{interrupted, next_key, Key};
+use crate::{prompt_stream, read_line_trimmed, script, term, trace};
+
+/// Prints to the standard output, followed by one of `options`. Then reads
+/// keypresses, cycling through the options until one is confirmed.
+///
+/// The option at index `default` is shown first. The terminal is put into raw
+/// mode for the duration of the read, and the keys are:
+///
+/// - Enter shows the next option in place of the current one, going back to
+///   the first after the last,
+/// - Space, `y` or `Y` confirms the option shown, and returns its zero-based
+///   index,
+/// - Ctrl-C aborts with an error of kind [`io::ErrorKind::Interrupted`],
+/// - Ctrl-D, or the end of the input, aborts with an error of kind
+///   [`io::ErrorKind::UnexpectedEof`].
+///
+/// Other keys are ignored. If `options` is empty, or `default` isn't one of
+/// its indices, an error of kind [`io::ErrorKind::InvalidInput`] is returned.
+///
+/// When stdin is not a terminal there are no keypresses to cycle with. A line
+/// is read as with [`read_line_trimmed`] instead: an empty line selects the
+/// default, and any other line the option it equals, ignoring case and
+/// surrounding whitespace. A line which equals none of them is an error of
+/// kind [`io::ErrorKind::InvalidData`].
+///
+/// # Examples
+///
+/// ```no_run
+/// # fn main() -> std::io::Result<()> {
+/// use io_prompt_prototype::prompt_toggle;
+///
+/// let levels = ["low", "medium", "high"];
+/// let level = prompt_toggle("Level (Enter to change, Space to confirm): ", &levels, 1)?;
+/// println!("Using {} verbosity", levels[level]);
+/// # Ok(()) }
+/// ```
+pub fn prompt_toggle<T: Display>(msg: &str, options: &[T], default: usize) -> io::Result<usize> {
+    check_options(options, default)?;
+    let mut out = prompt_stream();
+    if !term::stdin_is_tty() {
+        trace::prompt(msg);
+        out.write_all(msg.as_bytes())?;
+        out.flush()?;
+        let line = read_line_trimmed()?;
+        trace::answer(&line);
+        let answer = line.trim();
+        if answer.is_empty() {
+            return Ok(default);
+        }
+        let lowercase = answer.to_lowercase();
+        return options
+            .iter()
+            .position(|option| option.to_string().to_lowercase() == lowercase)
+            .ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("{:?} is not one of the options", answer),
+                )
+            });
+    }
+    let _guard = term::enable_raw()?;
+    prompt_toggle_from(&mut script::input(), &mut out, msg, options, default)
+}
+
+/// Writes `msg` and one of `options` to a writer. Then reads raw keypresses
+/// from a reader, cycling through the options until one is confirmed.
+///
+/// This implements the input handling of [`prompt_toggle`] without touching
+/// the terminal mode. Each time the option changes, the line is redrawn by
+/// writing `\r`, `msg` and the option, followed by `\x1b[K` to clear what's
+/// left of the previous one.
+///
+/// # Examples
+///
+/// ```
+/// # fn main() -> std::io::Result<()> {
+/// use io_prompt_prototype::prompt_toggle_from;
+/// use std::io::Cursor;
+///
+/// // Press Enter twice, then confirm with Space.
+/// let levels = ["low", "medium", "high"];
+/// let mut output = Vec::new();
+/// let level = prompt_toggle_from(&mut Cursor::new(b"\r\r "), &mut output, "Level: ", &levels, 1)?;
+/// assert_eq!(levels[level], "low");
+/// # Ok(()) }
+/// ```
+pub fn prompt_toggle_from<R, W, T>(
+    reader: &mut R,
+    writer: &mut W,
+    msg: &str,
+    options: &[T],
+    default: usize,
+) -> io::Result<usize>
+where
+    R: Read,
+    W: Write,
+    T: Display,
+{
+    check_options(options, default)?;
+    trace::prompt(msg);
+    let mut editor = Editor::new(writer, msg);
+    let mut current = default;
+    editor.set_line(options[current].to_string())?;
+    loop {
+        match next_key(reader)? {
+            Some(Key::Enter) => {
+                current = (current + 1) % options.len();
+                editor.set_line(options[current].to_string())?;
+            }
+            Some(Key::Char(' ')) | Some(Key::Char('y')) | Some(Key::Char('Y')) => break,
+            Some(Key::Interrupt) => return Err(interrupted()),
+            None | Some(Key::Eof) => {
+                return Err(io::Error::new(
+                    io::ErrorKind::UnexpectedEof,
+                    "input ended before an option was confirmed",
+                ))
+            }
+            Some(_) => {}
+        }
+    }
+    trace::read_answer(editor.finish())?;
+    Ok(current)
+}
+
+/// Returns an error if there are no options, or `default` isn't one of them.
+fn check_options<T>(options: &[T], default: usize) -> io::Result<()> {
+    if options.is_empty() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "no options to toggle between",
+        ));
+    }
+    if default >= options.len() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "the default is not one of the options",
+        ));
+    }
+    Ok(())
+}
+
+/// Prints to the standard output, followed by one of the options. Then reads
+/// keypresses, cycling through the options until one is confirmed.
+///
+/// Returns the zero-based index of the confirmed option. Enter shows the next
+/// option, and Space or `y` confirms the one shown. The first option is shown
+/// first, unless another index is passed as `default = <usize>`. See
+/// [`prompt_toggle`] for the details, and for how piped input is handled. This
+/// panics on I/O errors, including when the read is aborted with Ctrl-C.
+///
+/// [`prompt_toggle`]: fn@crate::prompt_toggle
+///
+/// # Examples
+///
+/// ```no_run
+/// use io_prompt_prototype::prompt_toggle;
+///
+/// let levels = ["low", "med", "high"];
+/// let level = prompt_toggle!("Level: ", &levels);
+/// let speed = prompt_toggle!("Speed: ", &["slow", "fast"], default = 1);
+/// ```
+#[macro_export]
+macro_rules! prompt_toggle {
+    ($msg:expr, $options:expr, default = $default:expr $(,)?) => {
+        $crate::prompt_toggle(&$msg, $options, $default)
+            .unwrap_or_else(|e| panic!("failed prompting on stdin/stdout: {}", e))
+    };
+    ($msg:expr, $options:expr $(,)?) => {
+        $crate::prompt_toggle!($msg, $options, default = 0)
+    };
+}
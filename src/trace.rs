@@ -0,0 +1,96 @@
+//! Tracing of prompts and answers through the `log` facade.
+//!
+//! With the `log` feature enabled, each prompt message and each answer is
+//! logged at the debug level, under the `io_prompt_prototype` target. Answers
+//! to password and secret prompts are replaced with `<redacted>`. Without the
+//! feature these functions do nothing, and compile to nothing.
+
+use std::fmt;
+use std::io;
+
+/// Logs a prompt message.
+#[inline]
+pub fn prompt(msg: &str) {
+    prompt_args(format_args!("{}", msg));
+}
+
+/// Logs a prompt message given as format arguments, which are only formatted
+/// if the record is enabled.
+#[inline]
+pub(crate) fn prompt_args(msg: fmt::Arguments<'_>) {
+    #[cfg(feature = "log")]
+    log::debug!(target: "io_prompt_prototype", "prompt: {:?}", msg.to_string());
+    #[cfg(not(feature = "log"))]
+    let _ = msg;
+}
+
+/// Logs an answer.
+#[inline]
+pub(crate) fn answer(answer: &str) {
+    #[cfg(feature = "log")]
+    log::debug!(target: "io_prompt_prototype", "answer: {:?}", answer);
+    #[cfg(not(feature = "log"))]
+    let _ = answer;
+}
+
+/// Logs that a secret was answered, without logging the secret itself.
+#[inline]
+pub(crate) fn secret() {
+    #[cfg(feature = "log")]
+    log::debug!(target: "io_prompt_prototype", "answer: <redacted>");
+}
+
+/// Writes a prompt message to `out`, logging it.
+pub fn write_prompt<W: io::Write>(out: &mut W, msg: fmt::Arguments<'_>) -> io::Result<()> {
+    prompt_args(msg);
+    out.write_fmt(msg)
+}
+
+/// Logs the answer read by `result`, if any, and passes it on.
+pub fn read_answer<T: Answered>(result: io::Result<T>) -> io::Result<T> {
+    if let Ok(answer) = &result {
+        answer.trace();
+    }
+    result
+}
+
+/// Logs the secret read by `result`, if any, and passes it on.
+pub fn read_secret<T>(result: io::Result<T>) -> io::Result<T> {
+    if result.is_ok() {
+        secret();
+    }
+    result
+}
+
+/// An answer which can be logged.
+pub trait Answered {
+    /// Logs the answer.
+    fn trace(&self);
+}
+
+impl Answered for String {
+    fn trace(&self) {
+        answer(self);
+    }
+}
+
+impl Answered for Option<String> {
+    fn trace(&self) {
+        if let Some(line) = self {
+            answer(line);
+        }
+    }
+}
+
+impl Answered for char {
+    fn trace(&self) {
+        answer(self.encode_utf8(&mut [0; 4]));
+    }
+}
+
+impl Answered for Vec<u8> {
+    fn trace(&self) {
+        #[cfg(feature = "log")]
+        answer(&String::from_utf8_lossy(self));
+    }
+}
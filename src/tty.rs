@@ -0,0 +1,141 @@
+use std::fs::File;
+use std::io::{self, BufReader, Write};
+
+use crate::{is_scripted, prompt_stream, read_line, read_line_from, trace, trim_newline};
+
+/// Reads a line of input from the controlling terminal, rather than stdin.
+///
+/// This opens `/dev/tty` on Unix and `CONIN$` on Windows, which refer to the
+/// terminal the program was started from even when stdin and stdout are
+/// redirected. This is how tools like `ssh` and `sudo` ask for a password
+/// while their input and output are part of a pipeline. Like [`read_line`],
+/// the trailing newline is kept.
+///
+/// If the terminal can't be opened, for example because the program runs
+/// without one in a CI pipeline or as a service, this falls back to reading
+/// from stdin exactly like [`read_line`]. Use [`is_interactive`] first if a
+/// prompt should only be shown to a user. While a scripted session is active,
+/// the scripted answers are read as with [`read_line`].
+///
+/// On Windows the line is read in the console's code page, so non-ASCII
+/// characters are only read correctly if it's set to UTF-8.
+///
+/// [`is_interactive`]: crate::is_interactive
+///
+/// # Examples
+///
+/// ```no_run
+/// # fn main() -> std::io::Result<()> {
+/// use io_prompt_prototype::read_line_tty;
+///
+/// // Works even when run as `cat data.txt | my-cli > out.txt`.
+/// let answer = read_line_tty()?;
+/// # Ok(()) }
+/// ```
+pub fn read_line_tty() -> io::Result<String> {
+    match open() {
+        Some((input, _)) => read_line_from(&mut BufReader::new(input)),
+        None => read_line(),
+    }
+}
+
+/// Writes `msg` to the controlling terminal. Then reads a line of input from
+/// it, with the trailing newline removed.
+///
+/// If the terminal can't be opened, `msg` is written to the stream set with
+/// [`set_prompt_stream`] and the answer is read from stdin instead. See
+/// [`read_line_tty`] for when that happens.
+///
+/// [`set_prompt_stream`]: crate::set_prompt_stream
+///
+/// # Examples
+///
+/// ```no_run
+/// # fn main() -> std::io::Result<()> {
+/// use io_prompt_prototype::prompt_tty;
+///
+/// let answer = prompt_tty("Overwrite out.txt? [y/N] >")?;
+/// # Ok(()) }
+/// ```
+pub fn prompt_tty(msg: &str) -> io::Result<String> {
+    trace::prompt(msg);
+    let mut line = match open() {
+        Some((input, mut output)) => {
+            output.write_all(msg.as_bytes())?;
+            output.flush()?;
+            read_line_from(&mut BufReader::new(input))?
+        }
+        None => {
+            let mut out = prompt_stream();
+            out.write_all(msg.as_bytes())?;
+            out.flush()?;
+            read_line()?
+        }
+    };
+    trim_newline(&mut line);
+    trace::answer(&line);
+    Ok(line)
+}
+
+/// Opens the controlling terminal for reading and writing, unless a scripted
+/// session is active.
+fn open() -> Option<(File, File)> {
+    if is_scripted() {
+        return None;
+    }
+    sys::open().ok()
+}
+
+#[cfg(unix)]
+mod sys {
+    use std::fs::{File, OpenOptions};
+    use std::io;
+
+    pub(super) fn open() -> io::Result<(File, File)> {
+        let tty = OpenOptions::new().read(true).write(true).open("/dev/tty")?;
+        Ok((tty.try_clone()?, tty))
+    }
+}
+
+#[cfg(windows)]
+mod sys {
+    use std::fs::{File, OpenOptions};
+    use std::io;
+
+    pub(super) fn open() -> io::Result<(File, File)> {
+        let input = OpenOptions::new().read(true).write(true).open("CONIN$")?;
+        let output = OpenOptions::new().write(true).open("CONOUT$")?;
+        Ok((input, output))
+    }
+}
+
+#[cfg(not(any(unix, windows)))]
+mod sys {
+    use std::fs::File;
+    use std::io;
+
+    pub(super) fn open() -> io::Result<(File, File)> {
+        Err(io::ErrorKind::Unsupported.into())
+    }
+}
+
+/// Prints to the controlling terminal. Then reads a line of input from it.
+///
+/// This is a shorthand for calling [`prompt_tty`] with a formatted message,
+/// which falls back to stdin and stdout when there is no terminal. This panics
+/// on I/O errors.
+///
+/// # Examples
+///
+/// ```no_run
+/// use io_prompt_prototype::prompt_tty;
+///
+/// let answer = prompt_tty!("Overwrite {}? [y/N] >", "out.txt");
+/// ```
+#[macro_export]
+macro_rules! prompt_tty {
+    ($($arg:tt)*) => {
+        $crate::prompt_tty(&format!($($arg)*))
+            .unwrap_or_else(|e| panic!("failed prompting on the terminal: {}", e))
+    };
+}
@@ -0,0 +1,489 @@
+use std::io::{self, BufRead, Write};
+use std::net::{IpAddr, SocketAddr};
+
+use crate::Prompter;
+
+impl<R: BufRead, W: Write> Prompter<R, W> {
+    /// Writes `msg` to the output. Then reads an email address, re-prompting
+    /// until it looks valid.
+    ///
+    /// Surrounding whitespace is trimmed. The check is a heuristic which
+    /// catches typos, not an implementation of RFC 5322: the address must
+    /// contain exactly one `@`, with a non-empty part before it, and a domain
+    /// after it which contains a `.` but doesn't start or end with one. It
+    /// can't contain whitespace. Whether the address exists can only be found
+    /// out by sending it an email. `Please enter an email address` is written
+    /// each time an answer is rejected. If the input ends before a valid
+    /// answer is given, an error of kind [`io::ErrorKind::UnexpectedEof`] is
+    /// returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # fn main() -> std::io::Result<()> {
+    /// use io_prompt_prototype::Prompter;
+    /// use std::io::Cursor;
+    ///
+    /// let input = Cursor::new(b"ferris.example.com\nferris@example.com\n");
+    /// let mut prompter = Prompter::new(input, Vec::new());
+    /// assert_eq!(prompter.email("Email? >")?, "ferris@example.com");
+    /// # Ok(()) }
+    /// ```
+    pub fn email(&mut self, msg: &str) -> io::Result<String> {
+        let invalid = self.locale.enter_email;
+        self.prompt_with(msg, |answer| {
+            let answer = answer.trim();
+            if is_email(answer) {
+                Ok(answer.to_string())
+            } else {
+                Err(invalid)
+            }
+        })
+    }
+
+    /// Writes `msg` to the output. Then reads a URL, re-prompting until it
+    /// looks valid.
+    ///
+    /// Surrounding whitespace is trimmed, and the URL must be absolute, with a
+    /// scheme and a host, such as `https://example.com/docs`. By default the
+    /// check is a heuristic: the URL must start with a scheme made of ASCII
+    /// letters, digits, `+`, `-` and `.`, followed by `://` and a non-empty
+    /// host, and can't contain whitespace. With the `url` feature enabled the
+    /// URL is parsed with the [`url`](https://docs.rs/url) crate instead,
+    /// following the WHATWG URL standard. Either way the URL isn't checked to
+    /// be reachable. `Please enter a URL` is written each time an answer is
+    /// rejected. If the input ends before a valid answer is given, an error of
+    /// kind [`io::ErrorKind::UnexpectedEof`] is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # fn main() -> std::io::Result<()> {
+    /// use io_prompt_prototype::Prompter;
+    /// use std::io::Cursor;
+    ///
+    /// let input = Cursor::new(b"example.com\nhttps://example.com\n");
+    /// let mut prompter = Prompter::new(input, Vec::new());
+    /// assert_eq!(prompter.url("Homepage? >")?, "https://example.com");
+    /// # Ok(()) }
+    /// ```
+    pub fn url(&mut self, msg: &str) -> io::Result<String> {
+        let invalid = self.locale.enter_url;
+        self.prompt_with(msg, |answer| {
+            let answer = answer.trim();
+            if is_url(answer) {
+                Ok(answer.to_string())
+            } else {
+                Err(invalid)
+            }
+        })
+    }
+
+    /// Writes `msg` to the output. Then reads an IP address, re-prompting
+    /// until it parses.
+    ///
+    /// Surrounding whitespace is trimmed, and the address is parsed with the
+    /// [`FromStr`](std::str::FromStr) implementation of [`IpAddr`], which
+    /// accepts both IPv4 addresses such as `192.168.0.1` and IPv6 addresses
+    /// such as `::1`. IPv6 addresses are written without brackets here, and
+    /// without a zone such as `%eth0`. `Please enter an IP address` is written
+    /// each time an answer is rejected. If the input ends before a valid
+    /// answer is given, an error of kind [`io::ErrorKind::UnexpectedEof`] is
+    /// returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # fn main() -> std::io::Result<()> {
+    /// use io_prompt_prototype::Prompter;
+    /// use std::io::Cursor;
+    /// use std::net::Ipv6Addr;
+    ///
+    /// let input = Cursor::new(b"localhost\n::1\n");
+    /// let mut prompter = Prompter::new(input, Vec::new());
+    /// assert_eq!(prompter.ip("Address? >")?, Ipv6Addr::LOCALHOST);
+    /// # Ok(()) }
+    /// ```
+    pub fn ip(&mut self, msg: &str) -> io::Result<IpAddr> {
+        let invalid = self.locale.enter_ip;
+        self.prompt_with(msg, |answer| answer.trim().parse().map_err(|_| invalid))
+    }
+
+    /// Writes `msg` to the output. Then reads a socket address, an IP address
+    /// and a port, re-prompting until it parses.
+    ///
+    /// Surrounding whitespace is trimmed, and the address is parsed with the
+    /// [`FromStr`](std::str::FromStr) implementation of [`SocketAddr`]. The
+    /// port is required. IPv4 addresses are followed directly by the port, as
+    /// in `127.0.0.1:8080`. IPv6 addresses have to be enclosed in brackets,
+    /// as in `[::1]:8080`, since their colons would otherwise be ambiguous
+    /// with the port's: `::1:8080` is rejected. Host names such as
+    /// `localhost:8080` are rejected too, since resolving them requires a DNS
+    /// lookup. `Please enter an IP address and port` is written each time an
+    /// answer is rejected. If the input ends before a valid answer is given,
+    /// an error of kind [`io::ErrorKind::UnexpectedEof`] is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # fn main() -> std::io::Result<()> {
+    /// use io_prompt_prototype::Prompter;
+    /// use std::io::Cursor;
+    ///
+    /// let input = Cursor::new(b"::1:8080\n[::1]:8080\n");
+    /// let mut prompter = Prompter::new(input, Vec::new());
+    /// assert_eq!(prompter.socket_addr("Endpoint? >")?.port(), 8080);
+    /// # Ok(()) }
+    /// ```
+    pub fn socket_addr(&mut self, msg: &str) -> io::Result<SocketAddr> {
+        let invalid = self.locale.enter_socket_addr;
+        self.prompt_with(msg, |answer| answer.trim().parse().map_err(|_| invalid))
+    }
+
+    /// Writes `msg` to the output. Then reads a percentage, re-prompting until
+    /// it parses and lies between 0% and 100%.
+    ///
+    /// The percentage is returned as a fraction in `0.0..=1.0`. Surrounding
+    /// whitespace is trimmed, and three forms are accepted:
+    ///
+    /// - a number followed by `%`, such as `50%` or `12.5%`, which is divided
+    ///   by 100. Whitespace between the number and the `%` is allowed.
+    /// - a whole number, such as `50`, which is also a percentage, and divided
+    ///   by 100. `1` is therefore 1%, not 100%.
+    /// - a number with a decimal point, such as `0.5` or `1.0`, which is
+    ///   already a fraction, and returned as is. `12.5` is rejected, since it
+    ///   would be 1250%: it has to be written as `12.5%`.
+    ///
+    /// Numbers are parsed with the [`FromStr`](std::str::FromStr)
+    /// implementation of [`f64`], except that `inf` and `NaN` are rejected.
+    /// `Please enter a percentage between 0% and 100%, such as 50%` is written
+    /// each time an answer is rejected. If the input ends before a valid
+    /// answer is given, an error of kind [`io::ErrorKind::UnexpectedEof`] is
+    /// returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # fn main() -> std::io::Result<()> {
+    /// use io_prompt_prototype::Prompter;
+    /// use std::io::Cursor;
+    ///
+    /// let input = Cursor::new(b"150%\n75%\n");
+    /// let mut prompter = Prompter::new(input, Vec::new());
+    /// assert_eq!(prompter.percent("Brightness? >")?, 0.75);
+    /// # Ok(()) }
+    /// ```
+    pub fn percent(&mut self, msg: &str) -> io::Result<f64> {
+        let invalid = self.locale.enter_percent;
+        self.prompt_with(msg, |answer| parse_percent(answer.trim()).ok_or(invalid))
+    }
+
+    /// Writes `msg` to the output. Then reads an amount of money, re-prompting
+    /// until it parses.
+    ///
+    /// Surrounding whitespace is trimmed, and an amount consists of:
+    ///
+    /// - an optional `-`,
+    /// - an optional currency symbol, one of `$`, `€`, `£`, `¥` and `₹`,
+    ///   optionally followed by whitespace,
+    /// - a whole number of at least one digit, optionally with `,` separating
+    ///   each group of three digits, such as `1,234,567`. If separators are
+    ///   used, they have to be used throughout, so `1234,567` and `12,34` are
+    ///   rejected. This keeps `1,5`, which means 1.5 in many locales, from
+    ///   being read as 15.
+    /// - an optional `.` followed by at least one digit.
+    ///
+    /// So `$1,234.56`, `1234.56`, `-€ 20` and `£0.5` are accepted. Which
+    /// currency the symbol names isn't checked or returned.
+    ///
+    /// The amount is returned as the nearest [`f64`], which represents every
+    /// whole amount up to 2<sup>53</sup> exactly, but most fractions only
+    /// approximately: `0.1` is slightly more than one tenth. The error is far
+    /// below a cent, but it adds up when amounts are summed, and shows when
+    /// they're printed with full precision. Callers which need exact results
+    /// should convert to a whole number of the smallest unit first, such as
+    /// with `(amount * 100.0).round() as i64` to get cents.
+    ///
+    /// `Please enter an amount, such as $1,234.56` is written each time an
+    /// answer is rejected. If the input ends before a valid answer is given, an
+    /// error of kind [`io::ErrorKind::UnexpectedEof`] is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # fn main() -> std::io::Result<()> {
+    /// use io_prompt_prototype::Prompter;
+    /// use std::io::Cursor;
+    ///
+    /// let input = Cursor::new(b"12,34\n$1,234.56\n");
+    /// let mut prompter = Prompter::new(input, Vec::new());
+    /// let amount = prompter.money("Amount? >")?;
+    /// assert_eq!((amount * 100.0).round() as i64, 123456);
+    /// # Ok(()) }
+    /// ```
+    pub fn money(&mut self, msg: &str) -> io::Result<f64> {
+        let invalid = self.locale.enter_amount;
+        self.prompt_with(msg, |answer| parse_money(answer.trim()).ok_or(invalid))
+    }
+}
+
+/// The currency symbols which may precede an amount of money.
+const CURRENCY_SYMBOLS: [char; 5] = ['$', '€', '£', '¥', '₹'];
+
+/// Parses an amount of money such as `$1,234.56`.
+fn parse_money(s: &str) -> Option<f64> {
+    let (negative, s) = match s.strip_prefix('-') {
+        Some(s) => (true, s),
+        None => (false, s),
+    };
+    let s = match s.strip_prefix(CURRENCY_SYMBOLS) {
+        Some(s) => s.trim_start(),
+        None => s,
+    };
+    let (whole, fraction) = match s.split_once('.') {
+        Some((whole, fraction)) => (whole, Some(fraction)),
+        None => (s, None),
+    };
+    let is_digits = |s: &str| !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit());
+    let mut groups = whole.split(',');
+    let first = groups.next()?;
+    let grouped = whole.contains(',');
+    if !is_digits(first) || (grouped && first.len() > 3) {
+        return None;
+    }
+    if !groups.all(|group| group.len() == 3 && is_digits(group)) {
+        return None;
+    }
+    let mut number: String = whole.chars().filter(|&c| c != ',').collect();
+    if let Some(fraction) = fraction {
+        if !is_digits(fraction) {
+            return None;
+        }
+        number.push('.');
+        number.push_str(fraction);
+    }
+    let amount = parse_finite(&number)?;
+    Some(if negative { -amount } else { amount })
+}
+
+/// Parses a percentage such as `50%`, `50` or `0.5` into a fraction in
+/// `0.0..=1.0`.
+fn parse_percent(s: &str) -> Option<f64> {
+    let fraction = match s.strip_suffix('%') {
+        Some(percent) => parse_finite(percent.trim_end())? / 100.0,
+        None if s.contains('.') => parse_finite(s)?,
+        None => parse_finite(s)? / 100.0,
+    };
+    if (0.0..=1.0).contains(&fraction) {
+        Some(fraction)
+    } else {
+        None
+    }
+}
+
+/// Parses a finite number, rejecting `inf` and `NaN`.
+fn parse_finite(s: &str) -> Option<f64> {
+    s.parse().ok().filter(|n: &f64| n.is_finite())
+}
+
+/// Returns `true` if `s` looks like an email address.
+fn is_email(s: &str) -> bool {
+    if s.chars().any(char::is_whitespace) {
+        return false;
+    }
+    let (local, domain) = match s.split_once('@') {
+        Some(parts) => parts,
+        None => return false,
+    };
+    !local.is_empty()
+        && !domain.contains('@')
+        && domain.contains('.')
+        && !domain.starts_with('.')
+        && !domain.ends_with('.')
+}
+
+/// Returns `true` if `s` looks like an absolute URL with a host.
+#[cfg(not(feature = "url"))]
+fn is_url(s: &str) -> bool {
+    if s.chars().any(char::is_whitespace) {
+        return false;
+    }
+    let (scheme, rest) = match s.split_once("://") {
+        Some(parts) => parts,
+        None => return false,
+    };
+    let valid_scheme = scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+        && scheme
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'));
+    let host = rest.split(['/', '?', '#']).next().unwrap_or("");
+    valid_scheme && !host.is_empty()
+}
+
+/// Returns `true` if `s` parses as an absolute URL with a host.
+#[cfg(feature = "url")]
+fn is_url(s: &str) -> bool {
+    match url::Url::parse(s) {
+        Ok(url) => url.host_str().is_some_and(|host| !host.is_empty()),
+        Err(_) => false,
+    }
+}
+
+/// Prints to the standard output. Then reads an email address, re-prompting
+/// until it looks valid.
+///
+/// The validation is a heuristic rather than a full implementation of the
+/// email address syntax, see [`Prompter::email`] for what is checked. This
+/// panics on I/O errors, or if stdin is closed before a valid address is
+/// given.
+///
+/// [`Prompter::email`]: crate::Prompter::email
+///
+/// # Examples
+///
+/// ```no_run
+/// use io_prompt_prototype::prompt_email;
+///
+/// let email = prompt_email!("Email? >");
+/// println!("Sending a confirmation to {}", email);
+/// ```
+#[macro_export]
+macro_rules! prompt_email {
+    ($($arg:tt)*) => {
+        $crate::Prompter::stdio()
+            .email(&format!($($arg)*))
+            .unwrap_or_else(|e| panic!("failed prompting on stdin/stdout: {}", e))
+    };
+}
+
+/// Prints to the standard output. Then reads a URL, re-prompting until it
+/// looks valid.
+///
+/// Unless the `url` feature is enabled the validation is a heuristic, see
+/// [`Prompter::url`] for what is checked. This panics on I/O errors, or if
+/// stdin is closed before a valid URL is given.
+///
+/// [`Prompter::url`]: crate::Prompter::url
+///
+/// # Examples
+///
+/// ```no_run
+/// use io_prompt_prototype::prompt_url;
+///
+/// let homepage = prompt_url!("Homepage? >");
+/// println!("Linking to {}", homepage);
+/// ```
+#[macro_export]
+macro_rules! prompt_url {
+    ($($arg:tt)*) => {
+        $crate::Prompter::stdio()
+            .url(&format!($($arg)*))
+            .unwrap_or_else(|e| panic!("failed prompting on stdin/stdout: {}", e))
+    };
+}
+
+/// Prints to the standard output. Then reads an IPv4 or IPv6 address,
+/// re-prompting until it parses.
+///
+/// See [`Prompter::ip`] for the accepted syntax. This panics on I/O errors, or
+/// if stdin is closed before a valid address is given.
+///
+/// [`Prompter::ip`]: crate::Prompter::ip
+///
+/// # Examples
+///
+/// ```no_run
+/// use io_prompt_prototype::prompt_ip;
+///
+/// let addr = prompt_ip!("Address? >");
+/// println!("Pinging {}", addr);
+/// ```
+#[macro_export]
+macro_rules! prompt_ip {
+    ($($arg:tt)*) => {
+        $crate::Prompter::stdio()
+            .ip(&format!($($arg)*))
+            .unwrap_or_else(|e| panic!("failed prompting on stdin/stdout: {}", e))
+    };
+}
+
+/// Prints to the standard output. Then reads a socket address, such as
+/// `127.0.0.1:8080` or `[::1]:8080`, re-prompting until it parses.
+///
+/// IPv6 addresses have to be enclosed in brackets, see
+/// [`Prompter::socket_addr`] for the accepted syntax. This panics on I/O
+/// errors, or if stdin is closed before a valid address is given.
+///
+/// [`Prompter::socket_addr`]: crate::Prompter::socket_addr
+///
+/// # Examples
+///
+/// ```no_run
+/// use io_prompt_prototype::prompt_socket_addr;
+///
+/// let endpoint = prompt_socket_addr!("Endpoint? >");
+/// println!("Connecting to {}", endpoint);
+/// ```
+#[macro_export]
+macro_rules! prompt_socket_addr {
+    ($($arg:tt)*) => {
+        $crate::Prompter::stdio()
+            .socket_addr(&format!($($arg)*))
+            .unwrap_or_else(|e| panic!("failed prompting on stdin/stdout: {}", e))
+    };
+}
+
+/// Prints to the standard output. Then reads a percentage, such as `50%`, `50`
+/// or `0.5`, re-prompting until it lies between 0% and 100%.
+///
+/// The percentage is returned as an `f64` fraction in `0.0..=1.0`, so all
+/// three examples return `0.5`. See [`Prompter::percent`] for the accepted
+/// forms. This panics on I/O errors, or if stdin is closed before a valid
+/// percentage is given.
+///
+/// [`Prompter::percent`]: crate::Prompter::percent
+///
+/// # Examples
+///
+/// ```no_run
+/// use io_prompt_prototype::prompt_percent;
+///
+/// let brightness = prompt_percent!("Brightness? >");
+/// println!("Dimming to {:.0}%", brightness * 100.0);
+/// ```
+#[macro_export]
+macro_rules! prompt_percent {
+    ($($arg:tt)*) => {
+        $crate::Prompter::stdio()
+            .percent(&format!($($arg)*))
+            .unwrap_or_else(|e| panic!("failed prompting on stdin/stdout: {}", e))
+    };
+}
+
+/// Prints to the standard output. Then reads an amount of money, such as
+/// `$1,234.56` or `1234.56`, re-prompting until it parses.
+///
+/// A leading currency symbol and the thousands separators are stripped, and
+/// the amount is returned as an `f64`. See [`Prompter::money`] for the
+/// accepted formats, and for how precise the result is. This panics on I/O
+/// errors, or if stdin is closed before a valid amount is given.
+///
+/// [`Prompter::money`]: crate::Prompter::money
+///
+/// # Examples
+///
+/// ```no_run
+/// use io_prompt_prototype::prompt_money;
+///
+/// let amount = prompt_money!("Amount? >");
+/// let cents = (amount * 100.0).round() as i64;
+/// println!("Transferring {} cents", cents);
+/// ```
+#[macro_export]
+macro_rules! prompt_money {
+    ($($arg:tt)*) => {
+        $crate::Prompter::stdio()
+            .money(&format!($($arg)*))
+            .unwrap_or_else(|e| panic!("failed prompting on stdin/stdout: {}", e))
+    };
+}
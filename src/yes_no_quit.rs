@@ -0,0 +1,54 @@
+/// The answer to a yes/no/quit question.
+///
+/// Returned by [`prompt_yes_no_quit!`] and [`Prompter::yes_no_quit`]. `Quit`
+/// is meant to abort the whole operation the question is part of, rather than
+/// only skipping the step being asked about.
+///
+/// [`prompt_yes_no_quit!`]: crate::prompt_yes_no_quit
+/// [`Prompter::yes_no_quit`]: crate::Prompter::yes_no_quit
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum YesNoQuit {
+    /// The answer was `y` or `yes`.
+    Yes,
+    /// The answer was `n` or `no`.
+    No,
+    /// The answer was `q` or `quit`, or the input ended.
+    Quit,
+}
+
+/// Prints a yes/no/quit question to the standard output. Then reads a line of
+/// input and returns it as a [`YesNoQuit`].
+///
+/// The answer is matched case-insensitively, ignoring surrounding whitespace:
+///
+/// - `y` and `yes` return [`YesNoQuit::Yes`].
+/// - `n` and `no` return [`YesNoQuit::No`].
+/// - `q` and `quit` return [`YesNoQuit::Quit`]. So does closing stdin, for
+///   example by pressing Ctrl-D.
+///
+/// Any other answer causes the question to be asked again. This panics on I/O
+/// errors. See [`Prompter::yes_no_quit`] for the underlying implementation.
+///
+/// [`Prompter::yes_no_quit`]: crate::Prompter::yes_no_quit
+///
+/// # Examples
+///
+/// ```no_run
+/// use io_prompt_prototype::{prompt_yes_no_quit, YesNoQuit};
+///
+/// for file in ["a.txt", "b.txt"] {
+///     match prompt_yes_no_quit!("Delete {}? [y/n/q] >", file) {
+///         YesNoQuit::Yes => println!("Deleting {}", file),
+///         YesNoQuit::No => continue,
+///         YesNoQuit::Quit => break,
+///     }
+/// }
+/// ```
+#[macro_export]
+macro_rules! prompt_yes_no_quit {
+    ($($arg:tt)*) => {
+        $crate::Prompter::stdio()
+            .yes_no_quit(&format!($($arg)*))
+            .unwrap_or_else(|e| panic!("failed prompting on stdin/stdout: {}", e))
+    };
+}
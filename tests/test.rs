@@ -1 +1,3262 @@
+use std::env;
+use std::io::{self, Cursor, Write};
+use std::num::ParseIntError;
+use std::process::{Child, Command, Output, Stdio};
+use std::time::Duration;
+#[cfg(unix)]
+use std::time::Instant;
 
+use io_prompt_prototype::*;
+
+/// Returns `true` when running inside a child spawned by `run_child`.
+fn is_child() -> bool {
+    env::var_os("IO_PROMPT_CHILD").is_some()
+}
+
+/// Re-runs a single test in a child process with piped stdio.
+fn spawn_child(test: &str) -> Child {
+    Command::new(env::current_exe().unwrap())
+        .args([test, "--exact", "--nocapture", "--test-threads=1"])
+        .env("IO_PROMPT_CHILD", "1")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap()
+}
+
+/// Waits for a child spawned by `spawn_child`, asserting that it passed.
+fn wait_child(test: &str, child: Child) -> Output {
+    let output = child.wait_with_output().unwrap();
+    assert!(
+        output.status.success(),
+        "child test `{}` failed:\n{}",
+        test,
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(
+        String::from_utf8_lossy(&output.stdout).contains("1 passed"),
+        "child test `{}` did not run",
+        test
+    );
+    output
+}
+
+/// Re-runs a single test in a child process, feeding `input` to its stdin and
+/// capturing its stdout and stderr.
+fn run_child(test: &str, input: &[u8]) -> Output {
+    let mut child = spawn_child(test);
+    child.stdin.take().unwrap().write_all(input).unwrap();
+    wait_child(test, child)
+}
+
+#[test]
+fn eprompt_writes_to_stderr() {
+    if is_child() {
+        assert_eq!(eprompt!("favorite number? >"), "42");
+        return;
+    }
+    let output = run_child("eprompt_writes_to_stderr", b"42\n");
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(!stdout.contains("favorite number? >"));
+    assert!(stderr.contains("favorite number? >"));
+}
+
+/// A writer whose reader has gone away.
+struct ClosedPipe;
+
+impl Write for ClosedPipe {
+    fn write(&mut self, _: &[u8]) -> io::Result<usize> {
+        Err(io::ErrorKind::BrokenPipe.into())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Err(io::ErrorKind::BrokenPipe.into())
+    }
+}
+
+#[test]
+fn prompter_returns_broken_pipe_errors() {
+    let mut prompter = Prompter::new(Cursor::new("yes\n"), ClosedPipe);
+    let err = prompter.prompt("Continue? >").unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::BrokenPipe);
+}
+
+#[test]
+fn closed_stderr_is_reported_clearly() {
+    if is_child() {
+        std::panic::set_hook(Box::new(|_| {}));
+        let err = try_eprompt!("Name? >").unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::BrokenPipe);
+        println!("try_eprompt: {}", err);
+        let panic = std::panic::catch_unwind(|| eprompt!("Name? >")).unwrap_err();
+        println!("eprompt: {}", panic.downcast_ref::<String>().unwrap());
+        return;
+    }
+    let test = "closed_stderr_is_reported_clearly";
+    let (reader, writer) = io::pipe().unwrap();
+    drop(reader);
+    let mut child = Command::new(env::current_exe().unwrap())
+        .args([test, "--exact", "--nocapture", "--test-threads=1"])
+        .env("IO_PROMPT_CHILD", "1")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(writer)
+        .spawn()
+        .unwrap();
+    child.stdin.take().unwrap().write_all(b"Ferris\n").unwrap();
+    let output = wait_child(test, child);
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let closed = "stderr is a closed pipe, the program reading it has exited";
+    assert!(stdout.contains(&format!("try_eprompt: {}", closed)));
+    assert!(stdout.contains(&format!("eprompt: failed writing to stderr: {}", closed)));
+}
+
+#[test]
+#[cfg(unix)]
+fn tty_prompts_fall_back_to_stdio_without_a_terminal() {
+    if is_child() {
+        assert_eq!(read_line_tty().unwrap(), "first\n");
+        assert_eq!(prompt_tty!("Second? >"), "second");
+        return;
+    }
+    if std::fs::File::open("/dev/tty").is_ok() {
+        // The child would read from this terminal rather than its stdin.
+        return;
+    }
+    let output = run_child(
+        "tty_prompts_fall_back_to_stdio_without_a_terminal",
+        b"first\nsecond\n",
+    );
+    assert!(String::from_utf8(output.stdout)
+        .unwrap()
+        .contains("Second? >"));
+}
+
+/// A logger which records the prompts and answers logged on each thread.
+#[cfg(feature = "log")]
+struct TraceLogger;
+
+#[cfg(feature = "log")]
+thread_local! {
+    static TRACES: std::cell::RefCell<Vec<String>> = const { std::cell::RefCell::new(Vec::new()) };
+}
+
+#[cfg(feature = "log")]
+impl log::Log for TraceLogger {
+    fn enabled(&self, metadata: &log::Metadata<'_>) -> bool {
+        metadata.target() == "io_prompt_prototype"
+    }
+
+    fn log(&self, record: &log::Record<'_>) {
+        if self.enabled(record.metadata()) {
+            let trace = format!("{} {}", record.level(), record.args());
+            TRACES.with(|traces| traces.borrow_mut().push(trace));
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+/// Installs `TraceLogger`, and returns the traces logged on this thread so far.
+#[cfg(feature = "log")]
+fn take_traces() -> Vec<String> {
+    static INIT: std::sync::Once = std::sync::Once::new();
+    INIT.call_once(|| {
+        log::set_logger(&TraceLogger).unwrap();
+        log::set_max_level(log::LevelFilter::Debug);
+    });
+    TRACES.with(|traces| traces.take())
+}
+
+#[test]
+#[cfg(feature = "log")]
+fn prompts_and_answers_are_logged() {
+    take_traces();
+    let mut prompter = Prompter::new(Cursor::new("Ferris\n"), Vec::new());
+    assert_eq!(prompter.prompt("Name? >").unwrap(), "Ferris");
+    let mut input = Cursor::new("hunter2\nhunter2\n");
+    let password =
+        prompt_password_confirm_from(&mut input, &mut Vec::new(), "Password: >", "Confirm: >", 1);
+    assert_eq!(password.unwrap(), "hunter2");
+    assert_eq!(
+        take_traces(),
+        [
+            r#"DEBUG prompt: "Name? >""#,
+            r#"DEBUG answer: "Ferris""#,
+            r#"DEBUG prompt: "Password: >""#,
+            "DEBUG answer: <redacted>",
+            r#"DEBUG prompt: "Confirm: >""#,
+            "DEBUG answer: <redacted>",
+        ]
+    );
+}
+
+#[test]
+#[cfg(feature = "log")]
+fn prompt_macros_log_and_redact() {
+    if is_child() {
+        take_traces();
+        assert_eq!(prompt!("What's your {}? >", "name"), "Ferris");
+        assert_eq!(promptln!("Age?"), "7");
+        assert_eq!(prompt_password!("Password: >"), "hunter2");
+        assert_eq!(prompt_char!("Continue? (y/n) >"), 'y');
+        assert_eq!(
+            take_traces(),
+            [
+                r#"DEBUG prompt: "What's your name? >""#,
+                r#"DEBUG answer: "Ferris""#,
+                r#"DEBUG prompt: "Age?\n""#,
+                r#"DEBUG answer: "7""#,
+                r#"DEBUG prompt: "Password: >""#,
+                "DEBUG answer: <redacted>",
+                r#"DEBUG prompt: "Continue? (y/n) >""#,
+                r#"DEBUG answer: "y""#,
+            ]
+        );
+        return;
+    }
+    run_child("prompt_macros_log_and_redact", b"Ferris\n7\nhunter2\nyes\n");
+}
+
+#[test]
+fn scripted_answers_replace_stdio() {
+    if is_child() {
+        push_scripted_answer("Ferris");
+        push_scripted_answer("old");
+        push_scripted_answer("7");
+        push_scripted_answer("yes");
+        push_scripted_answer("hunter2");
+        assert!(is_scripted());
+        assert!(is_interactive());
+        assert_eq!(prompt!("Name? >"), "Ferris");
+        let age: u8 = prompt_number!("Age? >");
+        assert_eq!(age, 7);
+        assert!(confirm!("Continue? [y/N] >"));
+        assert_eq!(eprompt!("Password: >"), "hunter2");
+        assert_eq!(
+            take_scripted_output(),
+            "Name? >Age? >Please enter a number\nAge? >Continue? [y/N] >Password: >"
+        );
+        assert_eq!(read_line_opt().unwrap(), None);
+        end_script();
+        assert!(!is_scripted());
+        assert_eq!(prompt!("Real? >"), "stdin");
+        return;
+    }
+    let output = run_child("scripted_answers_replace_stdio", b"stdin\n");
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(!stdout.contains("Name? >"));
+    assert!(stdout.contains("Real? >"));
+}
+
+#[test]
+fn set_prompt_stream_redirects_prompts() {
+    if is_child() {
+        set_prompt_stream(Stream::Stderr);
+        assert_eq!(prompt!("first? >"), "1");
+        assert_eq!(try_promptln!("second?").unwrap(), "2");
+        set_prompt_stream(Stream::Stdout);
+        assert_eq!(prompt_stream(), Stream::Stdout);
+        assert_eq!(prompt!("third? >"), "3");
+        return;
+    }
+    let output = run_child("set_prompt_stream_redirects_prompts", b"1\n2\n3\n");
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("first? >second?\n"));
+    assert!(!stdout.contains("first? >") && !stdout.contains("second?"));
+    assert!(stdout.contains("third? >"));
+    assert!(!stderr.contains("third? >"));
+}
+
+#[test]
+fn set_prompt_stream_redirects_every_prompt() {
+    if is_child() {
+        set_prompt_stream(Stream::Stderr);
+        assert!(confirm!("sure?"));
+        assert_eq!(prompt_opt!("opt? >"), Some(String::from("two")));
+        assert_eq!(prompt_bytes!("bytes? >"), b"three");
+        assert_eq!(prompt_char!("char? >"), '4');
+        assert_eq!(prompt_toggle!("toggle: ", &["a", "b"]), 1);
+        assert_eq!(prompt_env_or!("IO_PROMPT_UNSET_VAR", "env? >"), "six");
+        return;
+    }
+    let input = b"y\ntwo\nthree\n4\nb\nsix\n";
+    let output = run_child("set_prompt_stream_redirects_every_prompt", input);
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    for prompt in [
+        "sure?", "opt? >", "bytes? >", "char? >", "toggle: ", "env? >",
+    ] {
+        assert!(stderr.contains(prompt), "{:?} not on stderr", prompt);
+        assert!(!stdout.contains(prompt), "{:?} on stdout", prompt);
+    }
+}
+
+#[test]
+fn prompt_macros_accept_a_display_value() {
+    if is_child() {
+        for answer in ["1", "2", "3", "4", "5", "6"] {
+            push_scripted_answer(answer);
+        }
+        let question = String::from("Name {}? >");
+        assert_eq!(prompt!(question), "1");
+        assert_eq!(prompt!("{} and {}? >", "a", 'b'), "2");
+        assert_eq!(promptln!(&question,), "3");
+        let answer = 42;
+        assert_eq!(try_eprompt!(answer).unwrap(), "4");
+        assert_eq!(prompt!("{{}}? >"), "5");
+        assert_eq!(try_promptln!().unwrap(), "6");
+        assert_eq!(
+            take_scripted_output(),
+            "Name {}? >a and b? >Name {}? >\n42{}? >\n"
+        );
+        return;
+    }
+    run_child("prompt_macros_accept_a_display_value", b"");
+}
+
+#[test]
+fn promptln_strips_trailing_newline() {
+    if is_child() {
+        assert_eq!(promptln!("favorite number? >"), "42");
+        assert_eq!(promptln!("favorite number? >"), "43");
+        return;
+    }
+    run_child("promptln_strips_trailing_newline", b"42\n43\r\n");
+}
+
+#[test]
+fn prompt_trimmed_strips_surrounding_whitespace() {
+    if is_child() {
+        assert_eq!(prompt_trimmed!("Username? >"), "hi");
+        assert_eq!(prompt_trimmed!("{}? >", "Username"), "ferris");
+        assert_eq!(prompt_trimmed!("Username? >"), "");
+        return;
+    }
+    let output = run_child(
+        "prompt_trimmed_strips_surrounding_whitespace",
+        b"  hi  \n\tferris \r\n   \n",
+    );
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("Username? >Username? >Username? >"));
+}
+
+#[test]
+fn epromptln_strips_trailing_newline() {
+    if is_child() {
+        let num: u16 = epromptln!("favorite number? >").parse().unwrap();
+        assert_eq!(num, 42);
+        return;
+    }
+    run_child("epromptln_strips_trailing_newline", b"42\n");
+}
+
+#[test]
+fn prompt_to_writes_to_writer() {
+    if is_child() {
+        let mut output = Vec::new();
+        let name = prompt_to!(&mut output, "{}? >", "Name");
+        assert_eq!(name, "Ferris");
+        let age = promptln_to!(&mut output, "Age?");
+        assert_eq!(age, "7");
+        assert_eq!(output, b"Name? >Age?\n");
+        return;
+    }
+    let output = run_child("prompt_to_writes_to_writer", b"Ferris\n7\n");
+    assert!(!String::from_utf8(output.stdout)
+        .unwrap()
+        .contains("Name? >"));
+}
+
+#[test]
+fn try_prompt_returns_line() {
+    if is_child() {
+        assert_eq!(try_prompt!("What's your name? >").unwrap(), "Ferris");
+        return;
+    }
+    let output = run_child("try_prompt_returns_line", b"Ferris\r\n");
+    assert!(String::from_utf8(output.stdout)
+        .unwrap()
+        .contains("What's your name? >"));
+}
+
+#[test]
+fn try_macros_return_empty_on_eof() {
+    if is_child() {
+        assert_eq!(try_prompt!("a >").unwrap(), "");
+        assert_eq!(try_promptln!("b >").unwrap(), "");
+        assert_eq!(try_eprompt!("c >").unwrap(), "");
+        assert_eq!(try_epromptln!("d >").unwrap(), "");
+        return;
+    }
+    let output = run_child("try_macros_return_empty_on_eof", b"");
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stdout.contains("a >") && stdout.contains("b >\n"));
+    assert!(stderr.contains("c >") && stderr.contains("d >\n"));
+}
+
+#[test]
+fn read_line_from_keeps_newline() {
+    let mut input = Cursor::new(b"hello\nworld\r\nend");
+    assert_eq!(read_line_from(&mut input).unwrap(), "hello\n");
+    assert_eq!(read_line_from(&mut input).unwrap(), "world\r\n");
+    assert_eq!(read_line_from(&mut input).unwrap(), "end");
+    assert_eq!(read_line_from(&mut input).unwrap(), "");
+}
+
+/// A reader whose first read is interrupted by a signal.
+struct InterruptedOnce<R> {
+    interrupted: bool,
+    inner: R,
+}
+
+impl<R: io::Read> io::Read for InterruptedOnce<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if !self.interrupted {
+            self.interrupted = true;
+            return Err(io::ErrorKind::Interrupted.into());
+        }
+        self.inner.read(buf)
+    }
+}
+
+#[test]
+fn read_line_retries_interrupted_reads() {
+    let mut input = io::BufReader::new(InterruptedOnce {
+        interrupted: false,
+        inner: Cursor::new(b"hello\n"),
+    });
+    assert_eq!(read_line_from(&mut input).unwrap(), "hello\n");
+}
+
+#[test]
+fn read_line_limited_rejects_long_lines() {
+    let long = "x".repeat(100_000);
+    let mut input = Cursor::new(format!("{}\nok\r\n", long));
+    let err = read_line_limited_from(&mut input, 16, Overflow::Error).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    assert_eq!(
+        read_line_limited_from(&mut input, 2, Overflow::Error).unwrap(),
+        "ok"
+    );
+}
+
+#[test]
+fn read_line_limited_accepts_usize_max() {
+    let mut input = Cursor::new("no limit\n");
+    let line = read_line_limited_from(&mut input, usize::MAX, Overflow::Error).unwrap();
+    assert_eq!(line, "no limit");
+}
+
+#[test]
+fn read_line_limited_truncates_on_char_boundary() {
+    let mut input = Cursor::new("ab\u{1F980}cd\nnext\n");
+    let line = read_line_limited_from(&mut input, 4, Overflow::Truncate).unwrap();
+    assert_eq!(line, "ab");
+    assert_eq!(read_line_trimmed_from(&mut input).unwrap(), "next");
+}
+
+#[test]
+fn read_lines_reads_exactly_n_lines() {
+    let mut input = Cursor::new("a\r\n\nc\nd\n");
+    assert_eq!(read_lines_from(&mut input, 3).unwrap(), ["a", "", "c"]);
+    assert_eq!(
+        read_lines_from(&mut input, 0).unwrap(),
+        Vec::<String>::new()
+    );
+    assert_eq!(read_line_trimmed_from(&mut input).unwrap(), "d");
+}
+
+#[test]
+fn read_lines_errors_on_early_eof() {
+    let mut input = Cursor::new("a\nb");
+    let err = read_lines_from(&mut input, 3).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+    assert_eq!(err.to_string(), "input ended after 2 of 3 lines");
+}
+
+#[test]
+fn read_line_bytes_accepts_invalid_utf8() {
+    let mut input = Cursor::new(b"\xff\xfe\x00\n\xc3\r\n\r");
+    assert_eq!(read_line_bytes_from(&mut input).unwrap(), b"\xff\xfe\x00");
+    assert_eq!(read_line_bytes_from(&mut input).unwrap(), b"\xc3");
+    assert_eq!(read_line_bytes_from(&mut input).unwrap(), b"");
+    assert_eq!(read_line_bytes_from(&mut input).unwrap(), b"");
+}
+
+#[test]
+fn read_line_lossy_replaces_invalid_utf8() {
+    let mut input = Cursor::new(&b"caf\xe9 au lait\n\xff\xfeok\ncr\xc3\xa8me\n\xe2\x82"[..]);
+    assert_eq!(
+        read_line_lossy_from(&mut input).unwrap(),
+        "caf\u{FFFD} au lait\n"
+    );
+    assert_eq!(
+        read_line_lossy_from(&mut input).unwrap(),
+        "\u{FFFD}\u{FFFD}ok\n"
+    );
+    assert_eq!(read_line_lossy_from(&mut input).unwrap(), "crème\n");
+    assert_eq!(read_line_lossy_from(&mut input).unwrap(), "\u{FFFD}");
+    assert_eq!(read_line_lossy_from(&mut input).unwrap(), "");
+
+    let mut input = Cursor::new(&b"caf\xe9\n"[..]);
+    let err = read_line_from(&mut input).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+}
+
+#[test]
+fn read_until_byte_splits_on_nul() {
+    let mut input = Cursor::new(b"a b\nc\0\xff\0tail");
+    assert_eq!(read_until_byte_from(&mut input, b'\0').unwrap(), b"a b\nc");
+    assert_eq!(read_until_byte_from(&mut input, b'\0').unwrap(), b"\xff");
+    assert_eq!(read_until_byte_from(&mut input, b'\0').unwrap(), b"tail");
+    assert_eq!(read_until_byte_from(&mut input, b'\0').unwrap(), b"");
+}
+
+#[test]
+fn read_until_byte_string_validates_each_record() {
+    let mut input = Cursor::new(b"caf\xc3\xa9\0\xc3\0\0end");
+    assert_eq!(
+        read_until_byte_string_from(&mut input, b'\0').unwrap(),
+        "café"
+    );
+    let err = read_until_byte_string_from(&mut input, b'\0').unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    assert_eq!(read_until_byte_string_from(&mut input, b'\0').unwrap(), "");
+    assert_eq!(
+        read_until_byte_string_from(&mut input, b'\0').unwrap(),
+        "end"
+    );
+}
+
+#[test]
+fn prompt_bytes_macro_reads_stdin() {
+    if is_child() {
+        assert_eq!(prompt_bytes!("Bytes? >"), b"caf\xe9");
+        return;
+    }
+    run_child("prompt_bytes_macro_reads_stdin", b"caf\xe9\n");
+}
+
+#[test]
+fn prompter_uses_injected_streams() {
+    let input = Cursor::new(b"Ferris\r\n42\n");
+    let mut prompter = Prompter::new(input, Vec::new());
+    assert_eq!(prompter.prompt("Name? >").unwrap(), "Ferris");
+    assert_eq!(prompter.promptln("Number? >").unwrap(), "42");
+    assert_eq!(prompter.prompt("Again? >").unwrap(), "");
+    let (_, output) = prompter.into_parts();
+    assert_eq!(output, b"Name? >Number? >\nAgain? >");
+}
+
+#[test]
+fn read_line_trimmed_strips_one_terminator() {
+    let trimmed = |input: &str| read_line_trimmed_from(&mut Cursor::new(input)).unwrap();
+    assert_eq!(trimmed("x\n"), "x");
+    assert_eq!(trimmed("x\r\n"), "x");
+    assert_eq!(trimmed("x"), "x");
+    assert_eq!(trimmed(""), "");
+    assert_eq!(trimmed("x \n"), "x ");
+    assert_eq!(trimmed("x\n\n"), "x");
+}
+
+#[test]
+fn windows_line_endings_parse_like_unix_ones() {
+    let mut input = Cursor::new(b"42\r\n7\r\n\r\n-1\r");
+    let mut parse = || read_line_trimmed_from(&mut input).unwrap().parse::<i32>();
+    assert_eq!(parse(), Ok(42));
+    assert_eq!(parse(), Ok(7));
+    assert!(parse().is_err());
+    assert_eq!(parse(), Ok(-1));
+    let mut input = Cursor::new(b"42\r\n");
+    assert_eq!(read_line_from(&mut input).unwrap(), "42\r\n");
+    let mut input = Cursor::new(b"42\r\n");
+    let line = read_line_limited_from(&mut input, 2, Overflow::Error).unwrap();
+    assert_eq!(line.parse::<i32>(), Ok(42));
+}
+
+#[cfg(windows)]
+#[test]
+fn ctrl_z_line_ends_input_on_windows() {
+    let mut input = Cursor::new(b"a\r\n\x1a\r\n");
+    assert_eq!(
+        read_line_opt_from(&mut input).unwrap().as_deref(),
+        Some("a")
+    );
+    assert_eq!(read_line_opt_from(&mut input).unwrap(), None);
+    let mut input = Cursor::new(b"\x1a\r\nb\r\n\x1a");
+    assert_eq!(read_line_from(&mut input).unwrap(), "");
+    assert_eq!(read_line_trimmed_from(&mut input).unwrap(), "b");
+    assert_eq!(read_line_trimmed_from(&mut input).unwrap(), "");
+    let mut input = Cursor::new(b"a\x1a\r\n\x1a\r\n");
+    assert_eq!(read_line_trimmed_from(&mut input).unwrap(), "a\x1a");
+    assert_eq!(
+        read_line_limited_from(&mut input, 8, Overflow::Error).unwrap(),
+        ""
+    );
+    let err = read_lines_from(&mut Cursor::new(b"a\n\x1a\nb\n"), 2).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+}
+
+#[cfg(not(windows))]
+#[test]
+fn ctrl_z_line_is_kept_outside_windows() {
+    let mut input = Cursor::new(b"\x1a\r\n\x1a");
+    assert_eq!(read_line_trimmed_from(&mut input).unwrap(), "\x1a");
+    assert_eq!(
+        read_line_opt_from(&mut input).unwrap().as_deref(),
+        Some("\x1a")
+    );
+}
+
+#[test]
+#[cfg(feature = "smallvec")]
+fn small_lines_stay_inline() {
+    let inline = "a".repeat(SMALL_LINE_CAPACITY);
+    let long = "b".repeat(SMALL_LINE_CAPACITY + 1);
+    let input = format!("yes\r\n{}\n{}\nlast", inline, long);
+    let mut input = Cursor::new(input);
+    let line = read_line_small_from(&mut input).unwrap();
+    assert_eq!((&*line, line.spilled()), ("yes", false));
+    let line = read_line_small_from(&mut input).unwrap();
+    assert_eq!((&*line, line.spilled()), (&*inline, false));
+    let line = read_line_small_from(&mut input).unwrap();
+    assert_eq!((&*line, line.spilled()), (&*long, true));
+    let line = read_line_small_from(&mut input).unwrap();
+    assert_eq!((&*line, line.spilled()), ("last", false));
+    assert_eq!(read_line_small_from(&mut input).unwrap().as_str(), "");
+}
+
+#[test]
+#[cfg(feature = "smallvec")]
+fn small_lines_are_trimmed_like_strings() {
+    for input in [
+        "a\n",
+        "a\r\n",
+        "a\r",
+        "a\u{2028}",
+        "a\u{2029}",
+        "a \n",
+        "a\u{2028}\n",
+        "",
+    ] {
+        let small = read_line_small_from(&mut Cursor::new(input)).unwrap();
+        let string = read_line_trimmed_from(&mut Cursor::new(input)).unwrap();
+        assert_eq!(small.as_str(), string, "{:?}", input);
+    }
+    let err = read_line_small_from(&mut Cursor::new(b"\xff\n")).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+}
+
+#[test]
+fn bom_is_stripped_from_first_answer_when_enabled() {
+    if is_child() {
+        set_strip_bom(true);
+        assert_eq!(read_line_trimmed().unwrap(), "42");
+        assert_eq!(prompt!("Next? >"), "\u{FEFF}43");
+        return;
+    }
+    let input = "\u{FEFF}42\n\u{FEFF}43\n";
+    run_child(
+        "bom_is_stripped_from_first_answer_when_enabled",
+        input.as_bytes(),
+    );
+}
+
+#[test]
+fn bom_is_kept_by_default() {
+    if is_child() {
+        assert_eq!(read_line_trimmed().unwrap(), "\u{FEFF}42");
+        return;
+    }
+    run_child("bom_is_kept_by_default", "\u{FEFF}42\n".as_bytes());
+}
+
+#[test]
+fn read_line_into_reuses_buffer() {
+    let mut input = Cursor::new("first line\n\nthird\r\n");
+    let mut line = String::with_capacity(64);
+    let capacity = line.capacity();
+    let mut lines = Vec::new();
+    loop {
+        let read = read_line_into_from(&mut input, &mut line).unwrap();
+        if read == 0 {
+            break;
+        }
+        lines.push((read, line.clone()));
+    }
+    assert_eq!(
+        lines,
+        [
+            (11, "first line".into()),
+            (1, "".into()),
+            (7, "third".into())
+        ]
+    );
+    assert_eq!(line, "");
+    assert_eq!(line.capacity(), capacity);
+}
+
+#[test]
+fn read_line_trimmed_recognizes_unicode_terminators() {
+    let trimmed = |input: &str| read_line_trimmed_from(&mut Cursor::new(input)).unwrap();
+    assert_eq!(trimmed("x\r"), "x");
+    assert_eq!(trimmed("x\u{2028}"), "x");
+    assert_eq!(trimmed("x\u{2029}"), "x");
+    assert_eq!(trimmed("x\r\r"), "x\r");
+    assert_eq!(trimmed("x\u{2028}\n"), "x\u{2028}");
+    assert_eq!(trimmed("x \r"), "x ");
+    assert_eq!(trimmed("x \u{2029}"), "x ");
+}
+
+#[test]
+fn confirm_accepts_yes_no_tokens() {
+    let confirm = |input: &str, default| {
+        let mut prompter = Prompter::new(Cursor::new(input), Vec::new());
+        prompter.confirm("Continue? >", default).unwrap()
+    };
+    assert!(confirm("y\n", None));
+    assert!(confirm(" YES \n", None));
+    assert!(!confirm("n\n", Some(true)));
+    assert!(!confirm("No\n", Some(true)));
+    assert!(!confirm("\n", None));
+    assert!(confirm("\n", Some(true)));
+    assert!(confirm("", Some(true)));
+}
+
+#[test]
+fn confirm_reprompts_on_unrecognized_input() {
+    let mut prompter = Prompter::new(Cursor::new("maybe\nyep\ny\n"), Vec::new());
+    assert!(prompter.confirm("Continue? >", None).unwrap());
+    let (_, output) = prompter.into_parts();
+    assert_eq!(output, b"Continue? >Continue? >Continue? >");
+}
+
+#[test]
+fn yes_no_quit_accepts_each_outcome() {
+    let answer = |input: &str| {
+        let mut prompter = Prompter::new(Cursor::new(input), Vec::new());
+        prompter.yes_no_quit("Proceed? [y/n/q] >").unwrap()
+    };
+    assert_eq!(answer("y\n"), YesNoQuit::Yes);
+    assert_eq!(answer(" Yes \n"), YesNoQuit::Yes);
+    assert_eq!(answer("n\n"), YesNoQuit::No);
+    assert_eq!(answer("NO\n"), YesNoQuit::No);
+    assert_eq!(answer("q\n"), YesNoQuit::Quit);
+    assert_eq!(answer("Quit\n"), YesNoQuit::Quit);
+    assert_eq!(answer(""), YesNoQuit::Quit);
+}
+
+#[test]
+fn yes_no_quit_reprompts_on_unrecognized_input() {
+    let mut prompter = Prompter::new(Cursor::new("maybe\n\nexit\nn\n"), Vec::new());
+    assert_eq!(prompter.yes_no_quit("Proceed? >").unwrap(), YesNoQuit::No);
+    let (_, output) = prompter.into_parts();
+    assert_eq!(output, b"Proceed? >Proceed? >Proceed? >Proceed? >");
+    let mut prompter = Prompter::new(Cursor::new("maybe\n"), Vec::new());
+    assert_eq!(prompter.yes_no_quit("Proceed? >").unwrap(), YesNoQuit::Quit);
+}
+
+#[test]
+fn prompt_yes_no_quit_macro_reads_stdin() {
+    if is_child() {
+        assert_eq!(prompt_yes_no_quit!("Delete {}? >", "a"), YesNoQuit::Yes);
+        assert_eq!(prompt_yes_no_quit!("Delete {}? >", "b"), YesNoQuit::Quit);
+        assert_eq!(prompt_yes_no_quit!("Delete {}? >", "c"), YesNoQuit::Quit);
+        return;
+    }
+    run_child("prompt_yes_no_quit_macro_reads_stdin", b"y\nwhat\nq\n");
+}
+
+#[test]
+fn prompt_bool_accepts_every_token() {
+    let truthy = ["y", "yes", "t", "true", "1", "on", "enable", "enabled"];
+    let falsy = ["n", "no", "f", "false", "0", "off", "disable", "disabled"];
+    for (tokens, expected) in [(truthy, true), (falsy, false)] {
+        for token in tokens {
+            for answer in [token.to_string(), format!(" {} ", token.to_uppercase())] {
+                let input = Cursor::new(format!("{}\n", answer));
+                let mut prompter = Prompter::new(input, Vec::new());
+                assert_eq!(
+                    prompter.prompt_bool("> ").unwrap(),
+                    expected,
+                    "{:?}",
+                    answer
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn prompt_bool_reprompts_and_accepts_custom_tokens() {
+    let mut prompter = Prompter::new(Cursor::new("\nmaybe\nyes\nNEIN\n"), Vec::new());
+    let answer = prompter.prompt_bool_with("Weiter? >", &["ja"], &["nein"]);
+    assert!(!answer.unwrap());
+    let (_, output) = prompter.into_parts();
+    assert_eq!(output, b"Weiter? >Weiter? >Weiter? >Weiter? >");
+}
+
+#[test]
+fn prompt_bool_macro_reads_stdin() {
+    if is_child() {
+        assert!(prompt_bool!("Verbose? >"));
+        assert!(prompt_bool!("Weiter? >", true = ["ja"], false = ["nein"]));
+        return;
+    }
+    run_child("prompt_bool_macro_reads_stdin", b"on\nmaybe\nJa\n");
+}
+
+#[test]
+fn prompt_confirmed_reprompts_when_rejected() {
+    let input = Cursor::new("10.0.0.l\nno\n10.0.0.1\nyes\n");
+    let mut prompter = Prompter::new(input, Vec::new());
+    assert_eq!(prompter.prompt_confirmed("IP? >").unwrap(), "10.0.0.1");
+    let (_, output) = prompter.into_parts();
+    assert_eq!(
+        String::from_utf8(output).unwrap(),
+        "IP? >You entered: 10.0.0.l. Is that correct? [y/N] \
+         IP? >You entered: 10.0.0.1. Is that correct? [y/N] "
+    );
+}
+
+#[test]
+fn prompt_confirmed_macro_reads_stdin() {
+    if is_child() {
+        assert_eq!(prompt_confirmed!("Email? >"), "b@example.com");
+        return;
+    }
+    run_child(
+        "prompt_confirmed_macro_reads_stdin",
+        b"a@example.com\n\nb@example.com\ny\n",
+    );
+}
+
+#[derive(Debug, PartialEq)]
+enum Mode {
+    Fast,
+    Slow,
+}
+
+impl std::str::FromStr for Mode {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "fast" => Ok(Mode::Fast),
+            "slow" => Ok(Mode::Slow),
+            _ => Err(()),
+        }
+    }
+}
+
+#[test]
+fn choice_lists_choices_on_error() {
+    let input = Cursor::new(" Fast\n\n fast \n");
+    let mut prompter = Prompter::new(input, Vec::new());
+    let mode: Mode = prompter.choice("Mode? >", &["fast", "slow"]).unwrap();
+    assert_eq!(mode, Mode::Fast);
+    let (_, output) = prompter.into_parts();
+    assert_eq!(
+        String::from_utf8(output).unwrap(),
+        "Mode? >Please choose one of: fast, slow\n\
+         Mode? >Please choose one of: fast, slow\n\
+         Mode? >"
+    );
+}
+
+#[test]
+fn prompt_choice_macro_reads_stdin() {
+    if is_child() {
+        let mode: Mode = prompt_choice!("Mode? >", &["fast", "slow"]);
+        assert_eq!(mode, Mode::Slow);
+        return;
+    }
+    run_child("prompt_choice_macro_reads_stdin", b"medium\nslow\n");
+}
+
+#[test]
+fn matching_returns_the_allowed_value() {
+    let envs = ["dev", "staging", "prod"];
+    let mut prompter = Prompter::new(Cursor::new(" prod \n"), Vec::new());
+    assert_eq!(prompter.matching("Env? >", &envs, false).unwrap(), "prod");
+    let mut prompter = Prompter::new(Cursor::new("STAGING\n"), Vec::new());
+    assert_eq!(
+        prompter.matching("Env? >", &envs, false).unwrap(),
+        "staging"
+    );
+}
+
+#[test]
+fn matching_rejects_other_answers() {
+    let input = Cursor::new("test\nDev\ndev\n");
+    let mut prompter = Prompter::new(input, Vec::new());
+    let env = prompter.matching("Env? >", &["dev", "prod"], true).unwrap();
+    assert_eq!(env, "dev");
+    let (_, output) = prompter.into_parts();
+    assert_eq!(
+        String::from_utf8(output).unwrap(),
+        "Env? >Please choose one of: dev, prod\n\
+         Env? >Please choose one of: dev, prod\n\
+         Env? >"
+    );
+}
+
+#[test]
+fn prompt_matching_macro_reads_stdin() {
+    if is_child() {
+        assert_eq!(prompt_matching!("Env? >", &["dev", "Prod"]), "Prod");
+        let tag = prompt_matching!("Tag? >", &["rc", "RC"], case_sensitive = true);
+        assert_eq!(tag, "RC");
+        return;
+    }
+    run_child("prompt_matching_macro_reads_stdin", b"qa\nprod\nRc\nRC\n");
+}
+
+#[test]
+fn confirm_macro_reads_stdin() {
+    if is_child() {
+        assert!(confirm!("Delete {}? [y/N] >", "file"));
+        assert!(confirm!("Continue? [Y/n] >", default = true));
+        return;
+    }
+    let output = run_child("confirm_macro_reads_stdin", b"yes\n\n");
+    assert!(String::from_utf8(output.stdout)
+        .unwrap()
+        .contains("Delete file? [y/N] >"));
+}
+
+#[test]
+fn registered_locale_is_used_by_confirm() {
+    if is_child() {
+        set_locale(Locale {
+            yes: &["o", "oui"],
+            no: &["n", "non"],
+            enter_number: "Veuillez saisir un nombre",
+            ..Locale::ENGLISH
+        });
+        assert!(confirm!("Supprimer ? [o/N] >"));
+        assert!(!confirm!("Continuer ? [o/N] >"));
+        let n: u8 = Prompter::stdio().number("Nombre ? >").unwrap();
+        assert_eq!(n, 7);
+        return;
+    }
+    let input = b"yes\nOUI\nnon\nsept\n7\n";
+    let output = run_child("registered_locale_is_used_by_confirm", input);
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("Supprimer ? [o/N] >Supprimer ? [o/N] >Continuer ? [o/N] >"));
+    assert!(stdout.contains("Nombre ? >Veuillez saisir un nombre\nNombre ? >"));
+}
+
+#[test]
+fn prompter_locale_overrides_messages() {
+    let locale = Locale {
+        confirm_answer: "Vous avez saisi : {}. Correct ? [o/N] ",
+        choose_one_of: "Choisissez parmi : {}",
+        yes: &["o"],
+        ..Locale::ENGLISH
+    };
+    let input = Cursor::new("Paris\no\nmedium\nslow\n");
+    let mut prompter = Prompter::new(input, Vec::new()).locale(locale);
+    assert_eq!(prompter.prompt_confirmed("Ville ? >").unwrap(), "Paris");
+    let mode: Mode = prompter.choice("Mode ? >", &["fast", "slow"]).unwrap();
+    assert_eq!(mode, Mode::Slow);
+    let (_, output) = prompter.into_parts();
+    assert_eq!(
+        String::from_utf8(output).unwrap(),
+        "Ville ? >Vous avez saisi : Paris. Correct ? [o/N] \
+         Mode ? >Choisissez parmi : fast, slow\nMode ? >"
+    );
+}
+
+#[test]
+fn locale_overrides_range_and_password_messages() {
+    if is_child() {
+        set_locale(Locale {
+            passwords_do_not_match: "Les mots de passe ne correspondent pas.",
+            ..Locale::ENGLISH
+        });
+        let mut input = Cursor::new("un\ndeux\ndeux\ndeux\n");
+        let mut output = Vec::new();
+        let password = prompt_password_confirm_from(
+            &mut input,
+            &mut output,
+            "Mot de passe : ",
+            "Encore : ",
+            2,
+        );
+        assert_eq!(password.unwrap(), "deux");
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "Mot de passe : Encore : Les mots de passe ne correspondent pas.\n\
+             Mot de passe : Encore : "
+        );
+        return;
+    }
+    run_child("locale_overrides_range_and_password_messages", b"");
+
+    let locale = Locale {
+        range_between: "une valeur entre {} et {}",
+        range_at_least: "une valeur d'au moins {}",
+        ..Locale::ENGLISH
+    };
+    let input = Cursor::new("0\n5\n-1\n3\n");
+    let mut prompter = Prompter::new(input, Vec::new()).locale(locale);
+    assert_eq!(prompter.range("Note ? >", 1..=10).unwrap(), 5);
+    assert_eq!(prompter.range("Âge ? >", 0..).unwrap(), 3);
+    let (_, output) = prompter.into_parts();
+    assert_eq!(
+        String::from_utf8(output).unwrap(),
+        "Note ? >une valeur entre 1 et 10\nNote ? >Âge ? >une valeur d'au moins 0\nÂge ? >"
+    );
+}
+
+#[test]
+fn prompt_repeat_collects_until_blank() {
+    let input = Cursor::new("a.example.com\n  b.example.com\t\nc.example.com\n\nignored\n");
+    let mut prompter = Prompter::new(input, Vec::new());
+    let hosts = prompter.prompt_repeat("Host? >").unwrap();
+    assert_eq!(hosts, ["a.example.com", "b.example.com", "c.example.com"]);
+    assert_eq!(prompter.prompt("Next? >").unwrap(), "ignored");
+    let (_, output) = prompter.into_parts();
+    assert_eq!(
+        String::from_utf8(output).unwrap(),
+        "Host? >".repeat(4) + "Next? >"
+    );
+}
+
+#[test]
+fn prompt_repeat_stops_on_immediate_blank_or_eof() {
+    let mut prompter = Prompter::new(Cursor::new("   \nlater\n"), Vec::new());
+    assert!(prompter.prompt_repeat("Host? >").unwrap().is_empty());
+    let mut prompter = Prompter::new(Cursor::new(""), Vec::new());
+    assert!(prompter.prompt_repeat("Host? >").unwrap().is_empty());
+    let mut prompter = Prompter::new(Cursor::new("only\n"), Vec::new());
+    assert_eq!(prompter.prompt_repeat("Host? >").unwrap(), ["only"]);
+}
+
+#[test]
+fn prompt_repeat_macro_reads_stdin() {
+    if is_child() {
+        assert_eq!(prompt_repeat!("Add {} >", "tag"), ["rust", "cli"]);
+        return;
+    }
+    let output = run_child("prompt_repeat_macro_reads_stdin", b"rust\ncli\n\n");
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("Add tag >Add tag >Add tag >"));
+}
+
+#[test]
+fn parse_strips_newline_before_parsing() {
+    let mut prompter = Prompter::new(Cursor::new("42\r\n-1\n"), Vec::new());
+    assert_eq!(prompter.parse::<u16>("Number? >").unwrap(), Ok(42));
+    assert!(prompter.parse::<u16>("Number? >").unwrap().is_err());
+}
+
+#[test]
+fn list_splits_trims_and_parses() {
+    let input = Cursor::new("1, 2 ,,3,\n4,x\na;b;\n");
+    let mut prompter = Prompter::new(input, Vec::new());
+    assert_eq!(prompter.list::<u32>("> ", ',').unwrap().unwrap(), [1, 2, 3]);
+    assert!(prompter.list::<u32>("> ", ',').unwrap().is_err());
+    let words: Vec<String> = prompter.list("> ", ';').unwrap().unwrap();
+    assert_eq!(words, ["a", "b"]);
+}
+
+#[test]
+fn prompt_list_macro_reads_stdin() {
+    if is_child() {
+        let nums: Vec<u8> = prompt_list!("Numbers? >", ',').unwrap();
+        assert_eq!(nums, [4, 2]);
+        return;
+    }
+    run_child("prompt_list_macro_reads_stdin", b"4,2,\n");
+}
+
+#[test]
+fn answer_keeps_parsed_value_and_text() {
+    let mut prompter = Prompter::new(Cursor::new(" 3.50\r\nnope\n"), Vec::new());
+    let price = prompter.answer::<f64>("Price? >").unwrap();
+    assert!(price.is_err());
+    let price = prompter.answer::<String>("Price? >").unwrap().unwrap();
+    assert_eq!(price.as_str(), "nope");
+    let mut prompter = Prompter::new(Cursor::new("0042\n"), Vec::new());
+    let num = prompter.answer::<u32>("Number? >").unwrap().unwrap();
+    assert_eq!(*num + 1, 43);
+    assert_eq!(num.as_str(), "0042");
+    assert_eq!(num.to_string(), "0042");
+    assert_eq!(num.into_parts(), (42, String::from("0042")));
+}
+
+#[test]
+fn prompt_answer_macro_reads_stdin() {
+    if is_child() {
+        let num: Answer<u8> = prompt_answer!("Number? >").unwrap();
+        assert_eq!((*num, num.as_str()), (9, "09"));
+        return;
+    }
+    run_child("prompt_answer_macro_reads_stdin", b"09\n");
+}
+
+#[test]
+fn parse_loop_reprompts_until_valid() {
+    let mut prompter = Prompter::new(Cursor::new("nope\n7\n"), Vec::new());
+    assert_eq!(prompter.parse_loop::<u8>("Number? >").unwrap(), 7);
+    let (_, output) = prompter.into_parts();
+    assert_eq!(
+        String::from_utf8(output).unwrap(),
+        "Number? >invalid digit found in string\nNumber? >"
+    );
+}
+
+#[test]
+fn parse_loop_errors_on_eof() {
+    let mut prompter = Prompter::new(Cursor::new("nope\n"), Vec::new());
+    let err = prompter.parse_loop::<u8>("Number? >").unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+}
+
+#[test]
+fn prompt_number_reprompts_until_number() {
+    if is_child() {
+        let age: u8 = prompt_number!("Age? >");
+        let height: f64 = prompt_number!("Height? >");
+        assert_eq!((age, height), (42, 1.5));
+        return;
+    }
+    let output = run_child("prompt_number_reprompts_until_number", b"old\n42\n1.5\n");
+    assert!(String::from_utf8(output.stdout)
+        .unwrap()
+        .contains("Age? >Please enter a number\nAge? >Height? >"));
+}
+
+#[test]
+fn max_retries_reports_last_answer() {
+    let input = Cursor::new("old\nolder\nancient\nFerris\n");
+    let mut prompter = Prompter::new(input, Vec::new()).max_retries(2);
+    let err = prompter
+        .prompt_until("Name? >", |s| s.starts_with('F'), None)
+        .unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    let exhausted = err
+        .get_ref()
+        .and_then(|err| err.downcast_ref::<RetriesExhausted>())
+        .unwrap();
+    assert_eq!(exhausted.attempts(), 3);
+    assert_eq!(exhausted.last_answer(), "ancient");
+    assert_eq!(
+        err.to_string(),
+        "no valid answer was given after 3 attempts, the last answer was \"ancient\""
+    );
+    let name = prompter.prompt_until("Name? >", |s| s.starts_with('F'), None);
+    assert_eq!(name.unwrap(), "Ferris");
+}
+
+#[test]
+fn max_retries_reports_parse_errors() {
+    let input = Cursor::new("old\nolder\nancient\n42\nold\nolder\nancient\n");
+    let mut prompter = Prompter::new(input, Vec::new()).max_retries(2);
+    let err = prompter.parse_loop::<u8>("Age? >").unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    let parse_err = err
+        .get_ref()
+        .and_then(|err| err.downcast_ref::<PromptParseError<ParseIntError>>())
+        .unwrap();
+    assert_eq!(parse_err.attempts(), 3);
+    assert_eq!(parse_err.input(), "ancient");
+    assert_eq!(
+        err.to_string(),
+        "invalid answer \"ancient\": invalid digit found in string"
+    );
+    assert_eq!(prompter.parse_loop::<u8>("Age? >").unwrap(), 42);
+
+    let err = prompter.number::<u8>("Age? >").unwrap_err();
+    let parse_err = err
+        .get_ref()
+        .and_then(|err| err.downcast_ref::<PromptParseError<ParseIntError>>())
+        .unwrap();
+    assert_eq!(parse_err.attempts(), 3);
+    assert_eq!(parse_err.input(), "ancient");
+}
+
+#[test]
+fn max_retries_macros_return_errors() {
+    if is_child() {
+        let err = prompt_until!("Name? >", |s: &str| !s.is_empty(), max_retries = 1);
+        assert_eq!(err.unwrap_err().kind(), io::ErrorKind::InvalidInput);
+        let port: io::Result<u16> = prompt_range!("Port? >", 1..=9, max_retries = 0);
+        assert!(port.is_err());
+        let num: u8 = prompt_parse_loop!(max_retries = 1, "Number? >").unwrap();
+        assert_eq!(num, 7);
+        return;
+    }
+    run_child("max_retries_macros_return_errors", b"\n\n10\nnope\n7\n");
+}
+
+#[test]
+fn range_reprompts_until_in_range() {
+    let input = Cursor::new("0\n65536\nhttp\n8080\n 80 \n");
+    let mut prompter = Prompter::new(input, Vec::new());
+    let port: u32 = prompter.range("Port? >", 1..=65535).unwrap();
+    assert_eq!(port, 8080);
+    let port: u32 = prompter.range("Port? >", 1..=65535).unwrap();
+    assert_eq!(port, 80);
+    let (_, output) = prompter.into_parts();
+    assert_eq!(
+        String::from_utf8(output).unwrap(),
+        "Port? >expected a value between 1 and 65535\n\
+         Port? >expected a value between 1 and 65535\n\
+         Port? >invalid digit found in string\n\
+         Port? >Port? >"
+    );
+}
+
+#[test]
+fn range_describes_open_bounds() {
+    let mut prompter = Prompter::new(Cursor::new("100\n5\n"), Vec::new());
+    assert_eq!(prompter.range("Percentage? >", ..100).unwrap(), 5);
+    let (_, output) = prompter.into_parts();
+    assert_eq!(
+        String::from_utf8(output).unwrap(),
+        "Percentage? >expected a value less than 100\nPercentage? >"
+    );
+}
+
+#[test]
+fn prompt_range_macro_reads_stdin() {
+    if is_child() {
+        let port: u16 = prompt_range!("Port? >", 1..=65535);
+        assert_eq!(port, 443);
+        return;
+    }
+    let output = run_child("prompt_range_macro_reads_stdin", b"0\n443\n");
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("Port? >expected a value between 1 and 65535\nPort? >"));
+}
+
+#[test]
+fn prompt_until_reprompts_with_invalid_message() {
+    let mut prompter = Prompter::new(Cursor::new("\nold\n42\n"), Vec::new());
+    let is_age = |s: &str| s.parse::<u8>().is_ok();
+    let age = prompter.prompt_until("Age? >", is_age, Some("Invalid age"));
+    assert_eq!(age.unwrap(), "42");
+    let (_, output) = prompter.into_parts();
+    assert_eq!(
+        String::from_utf8(output).unwrap(),
+        "Age? >Invalid age\nAge? >Invalid age\nAge? >"
+    );
+}
+
+#[test]
+fn prompt_until_errors_on_eof() {
+    let mut prompter = Prompter::new(Cursor::new("\n"), Vec::new());
+    let err = prompter
+        .prompt_until("Name? >", |s: &str| !s.is_empty(), None)
+        .unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+}
+
+#[test]
+fn prompt_until_macro_reads_stdin() {
+    if is_child() {
+        let name = prompt_until!("Name? >", |s: &str| !s.is_empty());
+        assert_eq!(name, "Ferris");
+        return;
+    }
+    let output = run_child("prompt_until_macro_reads_stdin", b"\nFerris\n");
+    assert!(String::from_utf8(output.stdout)
+        .unwrap()
+        .contains("Name? >Name? >"));
+}
+
+#[test]
+fn prompt_password_falls_back_to_read_line() {
+    if is_child() {
+        assert_eq!(prompt_password!("Password: >"), "hunter2");
+        return;
+    }
+    let output = run_child("prompt_password_falls_back_to_read_line", b"hunter2\r\n");
+    assert!(String::from_utf8(output.stdout)
+        .unwrap()
+        .contains("Password: >"));
+}
+
+#[test]
+fn prompt_default_handles_empty_and_whitespace() {
+    let mut prompter = Prompter::new(Cursor::new("\n   \n Ferris \n"), Vec::new());
+    assert_eq!(prompter.prompt_default("Name? ", "anon").unwrap(), "anon");
+    assert_eq!(prompter.prompt_default("Name? ", "anon").unwrap(), "anon");
+    let default = String::from("anon");
+    assert_eq!(
+        prompter.prompt_default("Name? ", default).unwrap(),
+        "Ferris"
+    );
+    let (_, output) = prompter.into_parts();
+    assert_eq!(output, "Name? [anon] ".repeat(3).as_bytes());
+}
+
+#[test]
+fn prompt_or_renders_no_hint() {
+    let mut prompter = Prompter::new(Cursor::new("\n"), Vec::new());
+    assert_eq!(prompter.prompt_or("Name? >", "anon").unwrap(), "anon");
+    let (_, output) = prompter.into_parts();
+    assert_eq!(output, b"Name? >");
+}
+
+#[test]
+#[cfg(unix)]
+fn prompt_timeout_returns_none_on_silent_pipe() {
+    if is_child() {
+        let start = Instant::now();
+        let answer = prompt_timeout("Still there? >", Duration::from_millis(100)).unwrap();
+        assert_eq!(answer, None);
+        assert!(start.elapsed() >= Duration::from_millis(100));
+        return;
+    }
+    let mut child = spawn_child("prompt_timeout_returns_none_on_silent_pipe");
+    let stdin = child.stdin.take();
+    wait_child("prompt_timeout_returns_none_on_silent_pipe", child);
+    drop(stdin);
+}
+
+#[test]
+#[cfg(unix)]
+fn prompt_timeout_returns_line() {
+    if is_child() {
+        let answer = prompt_timeout("Still there? >", Duration::from_secs(10)).unwrap();
+        assert_eq!(answer.as_deref(), Some("yes"));
+        return;
+    }
+    run_child("prompt_timeout_returns_line", b"yes\n");
+}
+
+#[test]
+#[cfg(unix)]
+fn read_line_timeout_returns_lines_read_together() {
+    if is_child() {
+        let dur = Duration::from_millis(500);
+        assert_eq!(read_line_timeout(dur).unwrap().as_deref(), Some("a"));
+        assert_eq!(read_line_timeout(dur).unwrap().as_deref(), Some("b"));
+        let canceled = std::sync::atomic::AtomicBool::new(false);
+        assert_eq!(
+            read_line_cancelable(&canceled).unwrap().as_deref(),
+            Some("c")
+        );
+        assert_eq!(read_line_timeout(dur).unwrap(), None);
+        return;
+    }
+    // The pipe stays open, so only the lines already written can be read.
+    let test = "read_line_timeout_returns_lines_read_together";
+    let mut child = spawn_child(test);
+    let mut stdin = child.stdin.take().unwrap();
+    stdin.write_all(b"a\nb\nc\n").unwrap();
+    wait_child(test, child);
+    drop(stdin);
+}
+
+#[test]
+#[cfg(unix)]
+fn prompt_timeout_and_prompt_share_piped_input() {
+    if is_child() {
+        set_strip_bom(true);
+        let dur = Duration::from_millis(500);
+        let answer = prompt_timeout("First? >", dur).unwrap();
+        assert_eq!(answer.as_deref(), Some("a"));
+        assert_eq!(prompt!("Second? >"), "b");
+        let answer = prompt_timeout("Third? >", dur).unwrap();
+        assert_eq!(answer.as_deref(), Some("c"));
+        assert_eq!(poll_line().unwrap().as_deref(), Some("d"));
+        assert_eq!(prompt!("Fifth? >"), "e");
+        return;
+    }
+    // The pipe stays open, so only the lines already written can be read.
+    let test = "prompt_timeout_and_prompt_share_piped_input";
+    let mut child = spawn_child(test);
+    let mut stdin = child.stdin.take().unwrap();
+    stdin.write_all(b"\xef\xbb\xbfa\nb\nc\nd\ne\n").unwrap();
+    wait_child(test, child);
+    drop(stdin);
+}
+
+#[test]
+#[cfg(unix)]
+fn prompt_deadline_in_the_past_returns_none_immediately() {
+    if is_child() {
+        let start = Instant::now();
+        assert_eq!(prompt_deadline("Task? >", start).unwrap(), None);
+        assert!(start.elapsed() < Duration::from_secs(1));
+        let deadline = Instant::now() + Duration::from_secs(10);
+        let answer = prompt_deadline("Task? >", deadline).unwrap();
+        assert_eq!(answer.as_deref(), Some("deploy"));
+        return;
+    }
+    let output = run_child(
+        "prompt_deadline_in_the_past_returns_none_immediately",
+        b"deploy\n",
+    );
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("Task? >Task? >"));
+}
+
+#[test]
+#[cfg(unix)]
+fn prompt_with_timeout_default_uses_default_once_input_ends() {
+    if is_child() {
+        let start = Instant::now();
+        let dur = Duration::from_secs(10);
+        assert_eq!(prompt_with_timeout_default!("Language? >", dur, "en"), "en");
+        assert!(start.elapsed() < Duration::from_secs(1));
+        return;
+    }
+    let output = run_child(
+        "prompt_with_timeout_default_uses_default_once_input_ends",
+        b"",
+    );
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("Language? > [en in 10s] \n"));
+}
+
+#[test]
+#[cfg(unix)]
+fn prompt_with_timeout_default_uses_piped_answer() {
+    use std::thread;
+
+    if is_child() {
+        let dur = Duration::from_millis(100);
+        assert_eq!(prompt_with_timeout_default!("Language?", dur, "en"), "fr");
+        assert_eq!(prompt_with_timeout_default!("Language?", dur, "en"), "en");
+        assert_eq!(prompt_with_timeout_default!("Language?", dur, "en"), "en");
+        return;
+    }
+    // The answer arrives after the timeout, which only applies to terminals.
+    let test = "prompt_with_timeout_default_uses_piped_answer";
+    let mut child = spawn_child(test);
+    let mut stdin = child.stdin.take().unwrap();
+    thread::sleep(Duration::from_millis(300));
+    stdin.write_all(b" fr \n\n").unwrap();
+    drop(stdin);
+    let output = wait_child(test, child);
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("Language? [en in 100ms] "));
+}
+
+#[test]
+#[cfg(unix)]
+fn poll_line_buffers_partial_lines() {
+    use std::io::{BufRead, BufReader};
+    use std::thread;
+
+    let next_line = || loop {
+        match poll_line() {
+            Ok(Some(line)) => return line,
+            Ok(None) => thread::sleep(Duration::from_millis(5)),
+            Err(err) => panic!("polling failed: {}", err),
+        }
+    };
+    if is_child() {
+        assert_eq!(poll_line().unwrap(), None);
+        eprintln!("ready");
+        let start = Instant::now();
+        while start.elapsed() < Duration::from_millis(200) {
+            assert_eq!(poll_line().unwrap(), None);
+            thread::sleep(Duration::from_millis(5));
+        }
+        eprintln!("partial");
+        assert_eq!(next_line(), "hello");
+        // Already buffered, so it's returned without reading again.
+        assert_eq!(poll_line().unwrap().as_deref(), Some("world"));
+        assert_eq!(next_line(), "last");
+        let err = poll_line().unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+        return;
+    }
+    let test = "poll_line_buffers_partial_lines";
+    let mut child = spawn_child(test);
+    let mut stdin = child.stdin.take().unwrap();
+    let mut stderr = BufReader::new(child.stderr.take().unwrap());
+    let mut wait_for = |signal: &str| {
+        let mut line = String::new();
+        while line.trim_end() != signal {
+            line.clear();
+            assert_ne!(stderr.read_line(&mut line).unwrap(), 0, "child exited");
+        }
+    };
+    wait_for("ready");
+    stdin.write_all(b"hel").unwrap();
+    wait_for("partial");
+    stdin.write_all(b"lo\nworld\nla").unwrap();
+    thread::sleep(Duration::from_millis(50));
+    stdin.write_all(b"st").unwrap();
+    drop(stdin);
+    wait_child(test, child);
+}
+
+#[test]
+fn select_returns_chosen_index() {
+    let mut prompter = Prompter::new(Cursor::new(" 3 \n"), Vec::new());
+    let index = prompter.select("Pick:", &["red", "green", "blue"]);
+    assert_eq!(index.unwrap(), 2);
+}
+
+#[test]
+fn select_reprompts_on_invalid_choice() {
+    let mut prompter = Prompter::new(Cursor::new("0\n4\nblue\n1\n"), Vec::new());
+    let index = prompter.select("Pick:", &["red", "green", "blue"]);
+    assert_eq!(index.unwrap(), 0);
+    let (_, output) = prompter.into_parts();
+    assert_eq!(
+        String::from_utf8(output).unwrap(),
+        "Pick:\n1) red\n2) green\n3) blue\n> > > > "
+    );
+}
+
+#[test]
+fn select_errors_without_options() {
+    let mut prompter = Prompter::new(Cursor::new("1\n"), Vec::new());
+    let options: [&str; 0] = [];
+    let err = prompter.select("Pick:", &options).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+}
+
+#[test]
+fn read_line_opt_distinguishes_eof() {
+    let mut input = Cursor::new("x\n\n");
+    assert_eq!(
+        read_line_opt_from(&mut input).unwrap().as_deref(),
+        Some("x")
+    );
+    assert_eq!(read_line_opt_from(&mut input).unwrap().as_deref(), Some(""));
+    assert_eq!(read_line_opt_from(&mut input).unwrap(), None);
+
+    let mut exhausted = Cursor::new("");
+    assert_eq!(read_line_opt_from(&mut exhausted).unwrap(), None);
+}
+
+#[test]
+fn read_all_lines_reads_until_eof() {
+    let mut input = Cursor::new("first\n second \r\n\nlast");
+    let lines = read_all_lines_from(&mut input).unwrap();
+    assert_eq!(lines, ["first", " second ", "", "last"]);
+    assert!(read_all_lines_from(&mut input).unwrap().is_empty());
+}
+
+#[test]
+fn read_line_detailed_reports_a_terminated_line() {
+    let mut input = Cursor::new("hello\r\n\n");
+    let read = read_line_detailed_from(&mut input).unwrap();
+    assert_eq!(
+        read,
+        LineRead {
+            line: "hello".into(),
+            bytes_read: 7,
+            hit_eof: false
+        }
+    );
+    let read = read_line_detailed_from(&mut input).unwrap();
+    assert_eq!((&*read.line, read.bytes_read, read.hit_eof), ("", 1, false));
+}
+
+#[test]
+fn read_line_detailed_reports_an_unterminated_last_line() {
+    let mut input = Cursor::new("first\nlast");
+    assert!(!read_line_detailed_from(&mut input).unwrap().hit_eof);
+    let read = read_line_detailed_from(&mut input).unwrap();
+    assert_eq!(
+        (&*read.line, read.bytes_read, read.hit_eof),
+        ("last", 4, true)
+    );
+}
+
+#[test]
+fn read_line_detailed_reports_empty_eof() {
+    let mut input = Cursor::new("");
+    for _ in 0..2 {
+        let read = read_line_detailed_from(&mut input).unwrap();
+        assert_eq!((&*read.line, read.bytes_read, read.hit_eof), ("", 0, true));
+    }
+}
+
+#[test]
+fn prompt_opt_macro_stops_on_eof() {
+    if is_child() {
+        let mut lines = Vec::new();
+        while let Some(line) = prompt_opt!("> ") {
+            lines.push(line);
+        }
+        assert_eq!(lines, ["a", "", "b"]);
+        return;
+    }
+    run_child("prompt_opt_macro_stops_on_eof", b"a\n\nb\n");
+}
+
+#[test]
+fn prompt_styled_emits_no_escape_codes_when_redirected() {
+    if is_child() {
+        assert_eq!(prompt_styled!(Style::Red, "Name? >"), "Ferris");
+        return;
+    }
+    let output = run_child(
+        "prompt_styled_emits_no_escape_codes_when_redirected",
+        b"Ferris\n",
+    );
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("Name? >"));
+    assert!(!stdout.contains('\x1b'));
+}
+
+#[test]
+#[cfg(feature = "color")]
+fn style_wraps_only_the_text() {
+    assert_eq!(Style::Green.apply("Name? >"), "\x1b[32mName? >\x1b[0m");
+    assert_eq!(Style::Bold.apply(""), "\x1b[1m\x1b[0m");
+}
+
+#[test]
+fn masked_password_echoes_one_star_per_char() {
+    let mut output = Vec::new();
+    let input = "añ\u{1F980}\r".as_bytes();
+    let password = read_password_masked_from(&mut Cursor::new(input), &mut output);
+    assert_eq!(password.unwrap(), "añ\u{1F980}");
+    assert_eq!(output, b"***\r\n");
+}
+
+#[test]
+fn password_confirm_reprompts_on_mismatch() {
+    let mut input = Cursor::new("hunter2\nhunter3\nhunter2\nhunter2\n");
+    let mut output = Vec::new();
+    let password =
+        prompt_password_confirm_from(&mut input, &mut output, "Password: >", "Confirm: >", 2);
+    assert_eq!(password.unwrap(), "hunter2");
+    assert_eq!(
+        String::from_utf8(output).unwrap(),
+        "Password: >Confirm: >Passwords do not match, please try again.\n\
+         Password: >Confirm: >"
+    );
+}
+
+#[test]
+fn secret_env_fallback_reads_env_when_piped() {
+    if is_child() {
+        env::set_var("IO_PROMPT_TEST_TOKEN", "s3cret");
+        let token = prompt_secret_env_fallback!("Token: >", "IO_PROMPT_TEST_TOKEN");
+        assert_eq!(token.unwrap(), "s3cret");
+        env::remove_var("IO_PROMPT_TEST_TOKEN");
+        let err = prompt_secret_env_fallback!("Token: >", "IO_PROMPT_TEST_TOKEN").unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::NotFound);
+        assert!(err.to_string().contains("IO_PROMPT_TEST_TOKEN"));
+        return;
+    }
+    let output = run_child("secret_env_fallback_reads_env_when_piped", b"typed\n");
+    assert!(!String::from_utf8(output.stdout)
+        .unwrap()
+        .contains("Token: >"));
+}
+
+#[test]
+fn prompt_env_or_prefers_a_set_variable() {
+    if is_child() {
+        env::set_var("IO_PROMPT_TEST_API_KEY", "from-env");
+        assert_eq!(
+            prompt_env_or!("IO_PROMPT_TEST_API_KEY", "Key? >"),
+            "from-env"
+        );
+        assert_eq!(
+            prompt_env_or!("IO_PROMPT_TEST_API_KEY", "Hidden key? >", hidden = true),
+            "from-env"
+        );
+        return;
+    }
+    let output = run_child("prompt_env_or_prefers_a_set_variable", b"typed\n");
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(!stdout.contains("Key? >"));
+    assert!(!stdout.contains("Hidden key? >"));
+}
+
+#[test]
+fn prompt_env_or_prompts_when_unset_or_empty() {
+    if is_child() {
+        env::remove_var("IO_PROMPT_TEST_REGION");
+        assert_eq!(
+            prompt_env_or!("IO_PROMPT_TEST_REGION", "Region? >"),
+            "eu-west-1"
+        );
+        env::set_var("IO_PROMPT_TEST_REGION", "");
+        let key = prompt_env_or!("IO_PROMPT_TEST_REGION", "Key? >", hidden = true);
+        assert_eq!(key, "s3cret");
+        let err = prompt_env_or("IO_PROMPT_TEST_REGION", "Region? >").unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+        return;
+    }
+    let output = run_child(
+        "prompt_env_or_prompts_when_unset_or_empty",
+        b"eu-west-1\ns3cret\n",
+    );
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("Region? >Key? >"));
+}
+
+#[test]
+fn secret_file_fallback_reads_the_file_when_piped() {
+    if is_child() {
+        let path = env::temp_dir().join(format!("io-prompt-secret-{}", std::process::id()));
+        std::fs::write(&path, "s3cret\n").unwrap();
+        let token = prompt_secret_file_fallback!("Token: >", &path);
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(token.unwrap(), "s3cret");
+
+        let err = prompt_secret_file_fallback!("Token: >", &path).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::NotFound);
+        assert!(err.to_string().contains(&*path.to_string_lossy()));
+        return;
+    }
+    let output = run_child("secret_file_fallback_reads_the_file_when_piped", b"typed\n");
+    assert!(!String::from_utf8(output.stdout)
+        .unwrap()
+        .contains("Token: >"));
+}
+
+#[test]
+fn secret_file_fallback_prompts_when_interactive() {
+    if is_child() {
+        push_scripted_answer("typed");
+        let token = prompt_secret_file_fallback!("Token: >", "/nonexistent/io-prompt-secret");
+        assert_eq!(token.unwrap(), "typed");
+        assert_eq!(take_scripted_output(), "Token: >");
+        end_script();
+        return;
+    }
+    run_child("secret_file_fallback_prompts_when_interactive", b"");
+}
+
+#[test]
+fn password_confirm_gives_up_after_attempts() {
+    let mut input = Cursor::new("a\nb\nc\nd\n");
+    let mut output = Vec::new();
+    let err = prompt_password_confirm_from(&mut input, &mut output, "> ", "> ", 2).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+}
+
+/// A global allocator which checks the memory freed for a probe string.
+///
+/// Every allocation is zeroed, so the whole block can be read back when it's
+/// freed.
+#[cfg(feature = "zeroize")]
+mod freed {
+    use std::alloc::{GlobalAlloc, Layout, System};
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    pub(crate) const PROBE: &[u8] = b"zeroize-probe-5f3a";
+    pub(crate) static WATCHING: AtomicBool = AtomicBool::new(false);
+    pub(crate) static FOUND: AtomicBool = AtomicBool::new(false);
+
+    struct Probe;
+
+    unsafe impl GlobalAlloc for Probe {
+        unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+            System.alloc_zeroed(layout)
+        }
+
+        unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+            if WATCHING.load(Ordering::SeqCst) {
+                let block = std::slice::from_raw_parts(ptr, layout.size());
+                if block.windows(PROBE.len()).any(|w| w == PROBE) {
+                    FOUND.store(true, Ordering::SeqCst);
+                }
+            }
+            System.dealloc(ptr, layout)
+        }
+    }
+
+    #[global_allocator]
+    static ALLOCATOR: Probe = Probe;
+}
+
+#[test]
+#[cfg(feature = "zeroize")]
+fn secrets_are_cleared_before_being_freed() {
+    use std::io::BufReader;
+    use std::sync::atomic::Ordering;
+
+    // Long enough for the secret to outgrow its initial buffer, read through a
+    // small buffer so it arrives in many chunks.
+    let input = [freed::PROBE, &[b'x'; 300], b"\n", freed::PROBE, b"\n"].concat();
+    let mut reader = BufReader::with_capacity(16, &input[..]);
+
+    freed::WATCHING.store(true, Ordering::SeqCst);
+    let secret = read_secret_from(&mut reader).unwrap();
+    assert_eq!(secret.expose().len(), freed::PROBE.len() + 300);
+    drop(secret);
+    assert!(!freed::FOUND.load(Ordering::SeqCst));
+
+    // Taking the string out opts out of clearing, which the probe notices.
+    let secret = read_secret_from(&mut reader).unwrap();
+    drop(secret.into_inner());
+    freed::WATCHING.store(false, Ordering::SeqCst);
+    assert!(freed::FOUND.load(Ordering::SeqCst));
+}
+
+#[test]
+#[cfg(feature = "zeroize")]
+fn secrets_are_read_like_trimmed_lines() {
+    let mut input = Cursor::new("hunter2\r\n\nlast");
+    let secret = read_secret_from(&mut input).unwrap();
+    assert_eq!(secret.expose(), "hunter2");
+    assert_eq!(format!("{:?}", secret), "Secret(<redacted>)");
+    assert_eq!(read_secret_from(&mut input).unwrap().expose(), "");
+    assert_eq!(read_secret_from(&mut input).unwrap().into_inner(), "last");
+    let err = read_secret_from(&mut Cursor::new(b"\xff\n")).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+}
+
+#[test]
+fn masked_password_handles_backspace() {
+    let mut output = Vec::new();
+    let input = b"ab\x7f\x7f\x7fc\x1b[Dd\n";
+    let password = read_password_masked_from(&mut Cursor::new(input), &mut output);
+    assert_eq!(password.unwrap(), "dc");
+    assert_eq!(output, b"**\x08 \x08\x08 \x08*\x08**\x08\r\n");
+}
+
+#[test]
+fn masked_password_can_reveal_the_last_character() {
+    let mut output = Vec::new();
+    let input = b"ab\x7fc\x1b[Dd\r";
+    let password =
+        read_password_masked_with_from(&mut Cursor::new(input), &mut output, Masking::RevealLast);
+    assert_eq!(password.unwrap(), "adc");
+    assert_eq!(
+        String::from_utf8(output).unwrap(),
+        // Each key conceals the character revealed before it. `d` is inserted
+        // before `c`, so `c` is redrawn masked after it.
+        "a\x08*b\x08*\x08 \x08\
+         c\x08*\x08\
+         d*\x08\x08*\r\n"
+    );
+
+    let mut output = Vec::new();
+    let input = &mut Cursor::new(b"xy\x03");
+    let err = read_password_masked_with_from(input, &mut output, Masking::RevealLast).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::Interrupted);
+    assert_eq!(output, b"x\x08*y\x08*");
+}
+
+#[test]
+fn masked_password_errors_on_ctrl_c() {
+    let mut output = Vec::new();
+    let err = read_password_masked_from(&mut Cursor::new(b"ab\x03"), &mut output).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::Interrupted);
+}
+
+#[test]
+fn prompt_password_masked_falls_back_to_read_line() {
+    if is_child() {
+        assert_eq!(prompt_password_masked!("PIN: >"), "1234");
+        return;
+    }
+    let output = run_child("prompt_password_masked_falls_back_to_read_line", b"1234\n");
+    assert!(!String::from_utf8(output.stdout).unwrap().contains('*'));
+}
+
+#[test]
+fn multiline_stops_at_sentinel() {
+    let mut input = Cursor::new("one\r\n  two\n\n.\nafter\n");
+    assert_eq!(
+        read_multiline_from(&mut input, ".").unwrap(),
+        "one\n  two\n"
+    );
+    assert_eq!(read_line_trimmed_from(&mut input).unwrap(), "after");
+}
+
+#[test]
+fn multiline_treats_eof_as_sentinel() {
+    let mut input = Cursor::new("one\ntwo");
+    assert_eq!(read_multiline_from(&mut input, "END").unwrap(), "one\ntwo");
+}
+
+#[test]
+fn multiline_allows_empty_body() {
+    let mut prompter = Prompter::new(Cursor::new(".\n"), Vec::new());
+    assert_eq!(prompter.prompt_multiline("Text:", ".").unwrap(), "");
+    let mut prompter = Prompter::new(Cursor::new(""), Vec::new());
+    assert_eq!(prompter.prompt_multiline("Text:", ".").unwrap(), "");
+    let (_, output) = prompter.into_parts();
+    assert_eq!(output, b"Text:\n");
+}
+
+#[test]
+fn prompt_multiline_macro_uses_custom_sentinel() {
+    if is_child() {
+        assert_eq!(prompt_multiline!("Text:"), "a\nb");
+        assert_eq!(prompt_multiline!("Text:", sentinel = "EOF"), ".\nc");
+        return;
+    }
+    run_child(
+        "prompt_multiline_macro_uses_custom_sentinel",
+        b"a\nb\n.\n.\nc\nEOF\n",
+    );
+}
+
+#[test]
+fn history_recalls_previous_answers() {
+    let input = Cursor::new(&b"first\rsecond\r\x1b[A\x1b[A\x1b[A\r\x1b[A\x1b[B\r"[..]);
+    let mut prompter = HistoryPrompter::new(input, Vec::new());
+    assert_eq!(prompter.prompt("> ").unwrap(), "first");
+    assert_eq!(prompter.prompt("> ").unwrap(), "second");
+    assert_eq!(prompter.prompt("> ").unwrap(), "first");
+    assert_eq!(prompter.prompt("> ").unwrap(), "");
+    assert_eq!(prompter.history(), ["first", "second", "first"]);
+}
+
+#[test]
+fn history_restores_draft_and_redraws() {
+    let input = Cursor::new(&b"ab\rx\x1bOA\x1bOBy\r"[..]);
+    let mut prompter = HistoryPrompter::new(input, Vec::new());
+    assert_eq!(prompter.prompt("> ").unwrap(), "ab");
+    assert_eq!(prompter.prompt("> ").unwrap(), "xy");
+    let (_, output) = prompter.into_parts();
+    assert_eq!(
+        String::from_utf8(output).unwrap(),
+        "> ab\r\n> x\r> ab\x1b[K\r> x\x1b[Ky\r\n"
+    );
+}
+
+fn complete_commands(line: &str) -> Vec<String> {
+    ["build", "bench", "check", "clean", "clippy"]
+        .iter()
+        .filter(|c| c.starts_with(line))
+        .map(|c| c.to_string())
+        .collect()
+}
+
+#[test]
+fn complete_extends_common_prefix() {
+    let mut output = Vec::new();
+    let mut input = Cursor::new("ch\t\rcl\ti\t\r");
+    let line = prompt_complete_from(&mut input, &mut output, "$ ", complete_commands);
+    assert_eq!(line.unwrap(), "check");
+    let line = prompt_complete_from(&mut input, &mut output, "$ ", complete_commands);
+    assert_eq!(line.unwrap(), "clippy");
+}
+
+#[test]
+fn complete_lists_ambiguous_candidates() {
+    let mut output = Vec::new();
+    let mut input = Cursor::new("b\t\r");
+    let line = prompt_complete_from(&mut input, &mut output, "$ ", complete_commands);
+    assert_eq!(line.unwrap(), "b");
+    assert_eq!(
+        String::from_utf8(output).unwrap(),
+        "$ b\r\nbuild  bench\r\n$ b\r\n"
+    );
+}
+
+#[test]
+fn prompt_cancelable_returns_interrupted_on_ctrl_c() {
+    let mut output = Vec::new();
+    let mut input = Cursor::new(b"delete\x03\x03yes\r");
+    let err = prompt_cancelable_from(&mut input, &mut output, "> ").unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::Interrupted);
+    let err = prompt_cancelable_from(&mut input, &mut output, "> ").unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::Interrupted);
+    let line = prompt_cancelable_from(&mut input, &mut output, "> ").unwrap();
+    assert_eq!(line, "yes");
+    assert_eq!(String::from_utf8(output).unwrap(), "> delete> > yes\r\n");
+}
+
+#[test]
+fn prompt_cancelable_falls_back_to_read_line() {
+    if is_child() {
+        assert_eq!(prompt_cancelable!("{}? >", "Name").unwrap(), "Ferris\x03");
+        return;
+    }
+    let output = run_child("prompt_cancelable_falls_back_to_read_line", b"Ferris\x03\n");
+    assert!(String::from_utf8(output.stdout)
+        .unwrap()
+        .contains("Name? >"));
+}
+
+/// Reads a line with `prompt_complete_from`, without completing anything.
+fn edit_line(input: &[u8]) -> (String, String) {
+    let mut output = Vec::new();
+    let line = prompt_complete_from(&mut Cursor::new(input), &mut output, "> ", |_| Vec::new());
+    (line.unwrap(), String::from_utf8(output).unwrap())
+}
+
+#[test]
+fn editing_moves_the_cursor() {
+    let (line, _) = edit_line(b"hllo\x1b[H\x1b[Ce\x1b[F!\r");
+    assert_eq!(line, "hello!");
+    let (line, _) = edit_line(b"xhello\x1bOH\x1b[3~\x7f\x1b[4~\x1b[D\x1b[D\x1b[3~\r");
+    assert_eq!(line, "helo");
+    let (line, _) = edit_line(b"ab\x1b[C\x1b[C\x1b[3~\x1b[1~\x1b[D\x7fc\r");
+    assert_eq!(line, "cab");
+}
+
+#[test]
+fn editing_redraws_the_tail() {
+    let (line, output) = edit_line(b"ac\x1b[Db\x1b[D\x7f\r");
+    assert_eq!(line, "bc");
+    assert_eq!(output, "> ac\x08bc\x08\x08\x08bc \x08\x08\x08\r\n");
+}
+
+#[test]
+fn editing_is_utf8_aware() {
+    let (line, output) = edit_line("aéb\x1b[D\x7f\x1b[Hü\x1b[3~\r".as_bytes());
+    assert_eq!(line, "üb");
+    assert_eq!(
+        output,
+        "> aéb\x08\x08b \x08\x08\x08üab\x08\x08b \x08\x08\r\n"
+    );
+}
+
+#[test]
+fn prompt_complete_falls_back_to_read_line() {
+    if is_child() {
+        let line = prompt_complete("$ ", complete_commands).unwrap();
+        assert_eq!(line, "b\t");
+        return;
+    }
+    run_child("prompt_complete_falls_back_to_read_line", b"b\t\n");
+}
+
+#[test]
+fn prompt_with_displays_errors_and_reprompts() {
+    let parse_pair = |s: &str| -> Result<(i32, i32), Box<dyn std::error::Error>> {
+        let (x, y) = s.split_once(',').ok_or("expected x,y")?;
+        Ok((x.trim().parse()?, y.trim().parse()?))
+    };
+    let mut prompter = Prompter::new(Cursor::new("1\n1,a\n3, -4\n"), Vec::new());
+    assert_eq!(
+        prompter.prompt_with("Point? >", parse_pair).unwrap(),
+        (3, -4)
+    );
+    let (_, output) = prompter.into_parts();
+    assert_eq!(
+        String::from_utf8(output).unwrap(),
+        "Point? >expected x,y\nPoint? >invalid digit found in string\nPoint? >"
+    );
+}
+
+#[test]
+fn prompt_raw_keeps_newline() {
+    if is_child() {
+        assert_eq!(prompt_raw!("Line? >"), "raw \r\n");
+        assert_eq!(prompt_raw!("Line? >"), "raw\n");
+        assert_eq!(prompt_raw!("Line? >"), "");
+        return;
+    }
+    run_child("prompt_raw_keeps_newline", b"raw \r\nraw\n");
+}
+
+#[test]
+fn panic_message_includes_io_error() {
+    if is_child() {
+        let payload = std::panic::catch_unwind(|| prompt!("Name? >")).unwrap_err();
+        let msg = payload.downcast::<String>().unwrap();
+        assert_eq!(
+            *msg,
+            "failed reading from stdin: stream did not contain valid UTF-8"
+        );
+        return;
+    }
+    run_child("panic_message_includes_io_error", b"\xff\n");
+}
+
+#[cfg(feature = "async")]
+#[tokio::test(flavor = "current_thread")]
+async fn read_line_async_strips_newline() {
+    let mut input: &[u8] = b"hello\r\nworld";
+    assert_eq!(read_line_async_from(&mut input).await.unwrap(), "hello");
+    assert_eq!(read_line_async_from(&mut input).await.unwrap(), "world");
+    assert_eq!(read_line_async_from(&mut input).await.unwrap(), "");
+}
+
+#[cfg(feature = "async")]
+#[test]
+fn prompt_async_reads_stdin() {
+    if is_child() {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap();
+        let first = runtime.block_on(prompt_async("First? >")).unwrap();
+        let second = runtime.block_on(prompt_async("Second? >")).unwrap();
+        assert_eq!((first.as_str(), second.as_str()), ("one", "two"));
+        return;
+    }
+    let output = run_child("prompt_async_reads_stdin", b"one\ntwo\n");
+    assert!(String::from_utf8(output.stdout)
+        .unwrap()
+        .contains("First? >Second? >"));
+}
+
+#[cfg(feature = "async")]
+#[test]
+fn prompt_async_honors_prompt_stream() {
+    if is_child() {
+        set_prompt_stream(Stream::Stderr);
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap();
+        let answer = runtime.block_on(prompt_async("Name? >")).unwrap();
+        assert_eq!(answer, "Ferris");
+        return;
+    }
+    let output = run_child("prompt_async_honors_prompt_stream", b"Ferris\n");
+    assert!(String::from_utf8(output.stderr)
+        .unwrap()
+        .contains("Name? >"));
+    assert!(!String::from_utf8(output.stdout)
+        .unwrap()
+        .contains("Name? >"));
+}
+
+#[test]
+fn read_char_decodes_multibyte_chars() {
+    let mut input = Cursor::new("\u{1F980}é".as_bytes());
+    assert_eq!(read_char_from(&mut input).unwrap(), '\u{1F980}');
+    assert_eq!(read_char_from(&mut input).unwrap(), 'é');
+    let err = read_char_from(&mut input).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+}
+
+#[test]
+fn prompt_char_takes_first_char_of_piped_line() {
+    if is_child() {
+        assert_eq!(prompt_char!("Continue? (y/n) >"), 'y');
+        assert_eq!(prompt_char!("Continue? (y/n) >"), '\n');
+        return;
+    }
+    run_child("prompt_char_takes_first_char_of_piped_line", b"yes\n\n");
+}
+
+#[test]
+fn prompt_builder_trims_and_defaults() {
+    let prompt = PromptBuilder::new()
+        .message("Name? >")
+        .default("Ferris")
+        .trim(true)
+        .build();
+    let mut prompter = Prompter::new(Cursor::new("  Corro  \n   \n"), Vec::new());
+    assert_eq!(prompt.ask_with(&mut prompter).unwrap(), "Corro");
+    assert_eq!(prompt.ask_with(&mut prompter).unwrap(), "Ferris");
+    assert_eq!(prompt.ask_with(&mut prompter).unwrap(), "Ferris");
+    let (_, output) = prompter.into_parts();
+    assert_eq!(
+        output,
+        b"Name? > [Ferris] Name? > [Ferris] Name? > [Ferris] "
+    );
+}
+
+#[test]
+fn prompt_builder_writes_suffix_once() {
+    for message in ["Name", "Name ", "Name>", "Name > "] {
+        let prompt = PromptBuilder::new().message(message).suffix("> ").build();
+        let mut prompter = Prompter::new(Cursor::new("\n"), Vec::new());
+        prompt.ask_with(&mut prompter).unwrap();
+        assert_eq!(prompter.into_parts().1, b"Name> ", "{:?}", message);
+    }
+    let prompt = PromptBuilder::new().message("Name: ").suffix("").build();
+    let mut prompter = Prompter::new(Cursor::new("\n"), Vec::new());
+    prompt.ask_with(&mut prompter).unwrap();
+    assert_eq!(prompter.into_parts().1, b"Name:");
+}
+
+#[test]
+fn prompt_fmt_macro_adds_suffix() {
+    if is_child() {
+        assert_eq!(prompt_fmt!("What's your {}", "name"), "Ferris");
+        assert_eq!(prompt_fmt!(suffix = ": ", "Age"), "7");
+        return;
+    }
+    let output = run_child("prompt_fmt_macro_adds_suffix", b"Ferris\n7\n");
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("What's your name> Age: "));
+}
+
+#[test]
+fn prompt_builder_gives_up_after_max_retries() {
+    let prompt = PromptBuilder::new()
+        .message("Age? >")
+        .validate(|s| s.parse::<u8>().is_ok())
+        .invalid("Invalid age")
+        .max_retries(1)
+        .build();
+    let mut prompter = Prompter::new(Cursor::new("old\nolder\n42\n"), Vec::new());
+    let err = prompt.ask_with(&mut prompter).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    assert_eq!(prompt.ask_with(&mut prompter).unwrap(), "42");
+    let (_, output) = prompter.into_parts();
+    assert_eq!(
+        String::from_utf8(output).unwrap(),
+        "Age? >Invalid age\nAge? >Invalid age\nAge? >"
+    );
+}
+
+#[test]
+fn disabling_auto_flush_leaves_prompts_buffered() {
+    let output = io::BufWriter::new(Vec::new());
+    let mut prompter = Prompter::new(Cursor::new("1\n2\n3\n"), output).auto_flush(false);
+    assert_eq!(prompter.prompt("a? >").unwrap(), "1");
+    let prompt = PromptBuilder::new().message("b? >").build();
+    assert_eq!(prompt.ask_with(&mut prompter).unwrap(), "2");
+    let (input, mut output) = prompter.into_parts();
+    assert!(output.get_ref().is_empty());
+    output.flush().unwrap();
+    assert_eq!(output.get_ref(), b"a? >b? >");
+
+    let mut prompter = Prompter::new(input, output);
+    let prompt = PromptBuilder::new()
+        .message("c? >")
+        .auto_flush(false)
+        .build();
+    assert_eq!(prompt.ask_with(&mut prompter).unwrap(), "3");
+    let (_, output) = prompter.into_parts();
+    assert_eq!(output.get_ref(), b"a? >b? >");
+    assert_eq!(output.buffer(), b"c? >");
+}
+
+#[test]
+fn retry_delay_is_only_applied_before_reprompts() {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    let delays = Rc::new(RefCell::new(Vec::new()));
+    let recorded = Rc::clone(&delays);
+    let prompt = PromptBuilder::new()
+        .message("Port? >")
+        .validate(|s| s.parse::<u16>().is_ok())
+        .max_retries(2)
+        .retry_delay(Duration::from_millis(10))
+        .sleep_with(move |delay| recorded.borrow_mut().push(delay))
+        .build();
+
+    let mut prompter = Prompter::new(Cursor::new("80\n"), Vec::new());
+    assert_eq!(prompt.ask_with(&mut prompter).unwrap(), "80");
+    assert!(delays.borrow().is_empty());
+
+    let mut prompter = Prompter::new(Cursor::new("a\nb\n8080\n"), Vec::new());
+    assert_eq!(prompt.ask_with(&mut prompter).unwrap(), "8080");
+    assert_eq!(*delays.borrow(), [Duration::from_millis(10); 2]);
+
+    delays.borrow_mut().clear();
+    let mut prompter = Prompter::new(Cursor::new("a\nb\nc\nd\n"), Vec::new());
+    assert!(prompt.ask_with(&mut prompter).is_err());
+    assert_eq!(delays.borrow().len(), 2);
+}
+
+#[test]
+fn bell_is_only_rung_on_terminals() {
+    let prompt = PromptBuilder::new()
+        .message("Port? >")
+        .validate(|s| s.parse::<u16>().is_ok())
+        .invalid("Please enter a port")
+        .bell_on_error(true)
+        .build();
+
+    let mut prompter = Prompter::new(Cursor::new("http\n80\n"), Vec::new()).terminal(true);
+    prompt.ask_with(&mut prompter).unwrap();
+    let (_, output) = prompter.into_parts();
+    assert_eq!(output, b"Port? >\x07Please enter a port\nPort? >");
+
+    let mut prompter = Prompter::new(Cursor::new("http\n80\n"), Vec::new());
+    prompt.ask_with(&mut prompter).unwrap();
+    let (_, output) = prompter.into_parts();
+    assert_eq!(output, b"Port? >Please enter a port\nPort? >");
+
+    let quiet = PromptBuilder::new().validate(|s| !s.is_empty()).build();
+    let mut prompter = Prompter::new(Cursor::new("\nx\n"), Vec::new()).terminal(true);
+    quiet.ask_with(&mut prompter).unwrap();
+    assert!(!prompter.into_parts().1.contains(&b'\x07'));
+}
+
+#[test]
+fn control_chars_are_stripped_or_rejected() {
+    assert_eq!(ControlChars::Strip.apply("a\x1bb\0c").unwrap(), "abc");
+    let err = ControlChars::Reject.apply("ab\x1b[31m").unwrap_err();
+    assert_eq!((err.control_char(), err.index()), ('\x1b', 2));
+    assert_eq!(
+        err.to_string(),
+        "answer contains control character U+001B at byte 2"
+    );
+    assert_eq!(ControlChars::Reject.apply("plain").unwrap(), "plain");
+}
+
+#[test]
+fn prompt_sanitized_macro_reads_stdin() {
+    if is_child() {
+        assert_eq!(prompt_sanitized!("Name? >").unwrap(), "Ferris");
+        let rejected = prompt_sanitized!("Name? >", policy = ControlChars::Reject);
+        assert_eq!(rejected.unwrap_err().index(), 3);
+        return;
+    }
+    run_child(
+        "prompt_sanitized_macro_reads_stdin",
+        b"Fer\x1bris\nFer\x1bris\r\n",
+    );
+}
+
+#[test]
+fn is_interactive_is_false_when_piped() {
+    if is_child() {
+        assert!(!is_interactive());
+        return;
+    }
+    run_child("is_interactive_is_false_when_piped", b"");
+}
+
+#[test]
+fn raw_mode_guard_restores_on_panic() {
+    use std::panic;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Arc;
+
+    let restored = Arc::new(AtomicBool::new(false));
+    let flag = restored.clone();
+    let result = panic::catch_unwind(move || {
+        let _guard = RawModeGuard::new(move || {
+            flag.store(true, Ordering::SeqCst);
+            Ok(())
+        });
+        panic!("read failed");
+    });
+    assert!(result.is_err());
+    assert!(restored.load(Ordering::SeqCst));
+}
+
+#[test]
+fn raw_mode_guard_restores_once() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    let restores = Arc::new(AtomicUsize::new(0));
+    let count = restores.clone();
+    let guard = RawModeGuard::new(move || {
+        count.fetch_add(1, Ordering::SeqCst);
+        Ok(())
+    });
+    guard.restore().unwrap();
+    assert_eq!(restores.load(Ordering::SeqCst), 1);
+}
+
+/// Creates an empty, uniquely named directory for a test.
+fn temp_dir(test: &str) -> std::path::PathBuf {
+    let dir = env::temp_dir().join(format!("io-prompt-{}-{}", test, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+#[test]
+fn path_reprompts_until_path_exists() {
+    let dir = temp_dir("path");
+    let file = dir.join("file.txt");
+    std::fs::write(&file, "").unwrap();
+    let missing = dir.join("missing");
+    let input = format!(
+        "{}\n{}\n{}\n{}\n",
+        missing.display(),
+        file.display(),
+        dir.display(),
+        file.display()
+    );
+    let mut prompter = Prompter::new(Cursor::new(input), Vec::new());
+    assert_eq!(prompter.dir_path("Dir? >").unwrap(), dir);
+    assert_eq!(prompter.file_path("File? >").unwrap(), file);
+    let (_, output) = prompter.into_parts();
+    assert_eq!(
+        String::from_utf8(output).unwrap(),
+        format!(
+            "Dir? >{} does not exist\nDir? >{} is not a directory\nDir? >File? >",
+            missing.display(),
+            file.display()
+        )
+    );
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn path_expands_home_directory() {
+    let home = match env::var_os(if cfg!(windows) { "USERPROFILE" } else { "HOME" }) {
+        Some(home) => std::path::PathBuf::from(home),
+        None => return,
+    };
+    let mut prompter = Prompter::new(Cursor::new("~\n"), Vec::new());
+    assert_eq!(prompter.dir_path("Dir? >").unwrap(), home);
+}
+
+#[test]
+fn prompt_path_macro_reads_stdin() {
+    if is_child() {
+        assert_eq!(prompt_path!("Path? >"), std::path::Path::new("src"));
+        assert_eq!(
+            prompt_path_file!("File? >"),
+            std::path::Path::new("Cargo.toml")
+        );
+        return;
+    }
+    run_child("prompt_path_macro_reads_stdin", b"\nsrc\nCargo.toml\n");
+}
+
+#[test]
+fn email_accepts_plausible_addresses() {
+    let email = |answer: &str| {
+        let mut prompter = Prompter::new(Cursor::new(format!("{}\n", answer)), Vec::new());
+        prompter.email("Email? >").ok()
+    };
+    assert_eq!(
+        email("ferris@example.com").as_deref(),
+        Some("ferris@example.com")
+    );
+    assert_eq!(
+        email(" a.b+c@mail.example.org ").as_deref(),
+        Some("a.b+c@mail.example.org")
+    );
+    for rejected in [
+        "",
+        "ferris",
+        "@example.com",
+        "ferris@",
+        "ferris@localhost",
+        "ferris@@example.com",
+        "fer@ris@example.com",
+        "ferris@.example.com",
+        "ferris@example.com.",
+        "fer ris@example.com",
+    ] {
+        assert_eq!(email(rejected), None, "{:?} was accepted", rejected);
+    }
+}
+
+#[test]
+fn url_accepts_absolute_urls() {
+    let url = |answer: &str| {
+        let mut prompter = Prompter::new(Cursor::new(format!("{}\n", answer)), Vec::new());
+        prompter.url("URL? >").ok()
+    };
+    for accepted in [
+        "https://example.com",
+        "http://localhost:8080/path?q=1#top",
+        "git+ssh://git@github.com/rust-lang/rust",
+    ] {
+        assert_eq!(url(accepted).as_deref(), Some(accepted));
+    }
+    for rejected in [
+        "",
+        "example.com",
+        "https://",
+        "://example.com",
+        "1http://example.com",
+        "https://exa mple.com",
+        "mailto:ferris@example.com",
+    ] {
+        assert_eq!(url(rejected), None, "{:?} was accepted", rejected);
+    }
+}
+
+#[test]
+fn prompt_email_and_url_macros_reprompt() {
+    if is_child() {
+        assert_eq!(prompt_email!("Email? >"), "ferris@example.com");
+        assert_eq!(prompt_url!("URL? >"), "https://example.com");
+        return;
+    }
+    let input = b"ferris\nferris@example.com\nexample.com\nhttps://example.com\n";
+    let output = run_child("prompt_email_and_url_macros_reprompt", input);
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("Email? >Please enter an email address\nEmail? >"));
+    assert!(stdout.contains("URL? >Please enter a URL\nURL? >"));
+}
+
+#[test]
+fn ip_accepts_v4_and_v6() {
+    use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+    let ip = |answer: &str| {
+        let mut prompter = Prompter::new(Cursor::new(format!("{}\n", answer)), Vec::new());
+        prompter.ip("Address? >").ok()
+    };
+    assert_eq!(
+        ip("192.168.0.1"),
+        Some(IpAddr::V4(Ipv4Addr::new(192, 168, 0, 1)))
+    );
+    assert_eq!(ip(" ::1 "), Some(IpAddr::V6(Ipv6Addr::LOCALHOST)));
+    assert_eq!(
+        ip("2001:db8::8a2e:370:7334"),
+        Some("2001:db8::8a2e:370:7334".parse().unwrap())
+    );
+    for rejected in [
+        "",
+        "localhost",
+        "256.0.0.1",
+        "1.2.3",
+        "[::1]",
+        "127.0.0.1:80",
+    ] {
+        assert_eq!(ip(rejected), None, "{:?} was accepted", rejected);
+    }
+}
+
+#[test]
+fn socket_addr_requires_a_port_and_brackets_for_v6() {
+    use std::net::SocketAddr;
+
+    let socket_addr = |answer: &str| {
+        let mut prompter = Prompter::new(Cursor::new(format!("{}\n", answer)), Vec::new());
+        prompter.socket_addr("Endpoint? >").ok()
+    };
+    let v4: SocketAddr = "127.0.0.1:8080".parse().unwrap();
+    assert_eq!(socket_addr("127.0.0.1:8080"), Some(v4));
+    let v6 = socket_addr("[::1]:443").unwrap();
+    assert!(v6.is_ipv6());
+    assert_eq!(v6.port(), 443);
+    for rejected in [
+        "",
+        "127.0.0.1",
+        "::1:443",
+        "[::1]",
+        "localhost:80",
+        "1.2.3.4:99999",
+    ] {
+        assert_eq!(socket_addr(rejected), None, "{:?} was accepted", rejected);
+    }
+}
+
+#[test]
+fn prompt_ip_and_socket_addr_macros_reprompt() {
+    if is_child() {
+        assert_eq!(prompt_ip!("Address? >").to_string(), "10.0.0.1");
+        assert_eq!(prompt_socket_addr!("Endpoint? >").to_string(), "[::1]:22");
+        return;
+    }
+    let input = b"10.0.0\n10.0.0.1\n::1:22\n[::1]:22\n";
+    let output = run_child("prompt_ip_and_socket_addr_macros_reprompt", input);
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("Address? >Please enter an IP address\nAddress? >"));
+    assert!(stdout.contains("Endpoint? >Please enter an IP address and port\nEndpoint? >"));
+}
+
+#[test]
+fn duration_parses_each_unit() {
+    let duration = |answer: &str| {
+        let mut prompter = Prompter::new(Cursor::new(format!("{}\n", answer)), Vec::new());
+        prompter.duration("Timeout? >").ok()
+    };
+    assert_eq!(duration("250ms"), Some(Duration::from_millis(250)));
+    assert_eq!(duration("30s"), Some(Duration::from_secs(30)));
+    assert_eq!(duration("5m"), Some(Duration::from_secs(5 * 60)));
+    assert_eq!(duration("2h"), Some(Duration::from_secs(2 * 60 * 60)));
+    assert_eq!(duration("1d"), Some(Duration::from_secs(24 * 60 * 60)));
+    assert_eq!(duration("90"), Some(Duration::from_secs(90)));
+    assert_eq!(duration(" 10 m "), Some(Duration::from_secs(10 * 60)));
+    assert_eq!(duration("0s"), Some(Duration::ZERO));
+}
+
+#[test]
+fn duration_rejects_invalid_strings() {
+    let input = Cursor::new("\nm\n-5s\n1.5h\n1h30m\n5 minutes\n10x\n99999999999999999999d\n3s\n");
+    let mut prompter = Prompter::new(input, Vec::new());
+    assert_eq!(
+        prompter.duration("Timeout? >").unwrap(),
+        Duration::from_secs(3)
+    );
+    let (_, output) = prompter.into_parts();
+    let rejections = String::from_utf8(output)
+        .unwrap()
+        .matches("Please enter a duration, such as 30s, 5m or 2h\n")
+        .count();
+    assert_eq!(rejections, 8);
+}
+
+#[test]
+fn prompt_duration_macro_reprompts() {
+    if is_child() {
+        assert_eq!(prompt_duration!("Timeout? >"), Duration::from_secs(120));
+        return;
+    }
+    let output = run_child("prompt_duration_macro_reprompts", b"two minutes\n2m\n");
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("Timeout? >Please enter a duration, such as 30s, 5m or 2h\nTimeout? >"));
+}
+
+#[cfg(feature = "json")]
+#[derive(serde::Deserialize, Debug, PartialEq)]
+struct Server {
+    host: String,
+    port: u16,
+}
+
+#[test]
+#[cfg(feature = "json")]
+fn json_reads_values_spanning_lines() {
+    let input = Cursor::new("{\"host\": \"localhost\", \"port\": 80}\n{\n  \"host\": \"example.com\",\n  \"port\": 443\n}\n");
+    let mut prompter = Prompter::new(input, Vec::new());
+    let server: Server = prompter.json("Server? >").unwrap();
+    assert_eq!(server.host, "localhost");
+    let server: Server = prompter.json("Server? >").unwrap();
+    assert_eq!(
+        server,
+        Server {
+            host: "example.com".into(),
+            port: 443
+        }
+    );
+    let (_, output) = prompter.into_parts();
+    assert_eq!(output, b"Server? >Server? >");
+}
+
+#[test]
+#[cfg(feature = "json")]
+fn json_reprompts_on_invalid_values() {
+    let input = Cursor::new(
+        "{host: 1}\n{\"host\": \"a\", \"port\": 99999}\n{\"host\": \"a\", \"port\": 8080}\n",
+    );
+    let mut prompter = Prompter::new(input, Vec::new());
+    let server: Server = prompter.json("Server? >").unwrap();
+    assert_eq!(server.port, 8080);
+    let (_, output) = prompter.into_parts();
+    let output = String::from_utf8(output).unwrap();
+    assert_eq!(output.matches("Please enter valid JSON: ").count(), 2);
+    assert!(output.ends_with("\nServer? >"));
+
+    let mut prompter = Prompter::new(Cursor::new("{\"host\":\n"), Vec::new());
+    let err = prompter.json::<Server>("Server? >").unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+}
+
+#[test]
+#[cfg(feature = "json")]
+fn prompt_json_macro_reads_from_stdin() {
+    if is_child() {
+        let tags: Vec<String> = prompt_json!("Tags? >");
+        assert_eq!(tags, ["a", "b"]);
+        return;
+    }
+    run_child("prompt_json_macro_reads_from_stdin", b"[\"a\",\n\"b\"]\n");
+}
+
+#[test]
+fn prefill_starts_with_initial_text() {
+    let mut output = Vec::new();
+    let line = prompt_prefill_from(&mut Cursor::new(b"/src\r"), &mut output, "Dir? >", "~");
+    assert_eq!(line.unwrap(), "~/src");
+    assert_eq!(output, b"\rDir? >~\x1b[K/src\r\n");
+}
+
+#[test]
+fn prompt_prefill_appends_piped_input() {
+    if is_child() {
+        assert_eq!(
+            prompt_prefill("Branch? >", "feature/").unwrap(),
+            "feature/login"
+        );
+        assert_eq!(prompt_prefill("Branch? >", "main").unwrap(), "main");
+        return;
+    }
+    run_child("prompt_prefill_appends_piped_input", b"login\n\n");
+}
+
+#[test]
+fn redraw_is_called_around_the_read() {
+    use std::cell::RefCell;
+    use std::io::{BufRead, Read};
+
+    /// Logs each read and write into a shared list of events.
+    struct Logged<'a>(Cursor<&'a [u8]>, &'a RefCell<Vec<String>>);
+
+    impl Read for Logged<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            self.0.read(buf)
+        }
+    }
+
+    impl BufRead for Logged<'_> {
+        fn fill_buf(&mut self) -> io::Result<&[u8]> {
+            self.1.borrow_mut().push("read".into());
+            self.0.fill_buf()
+        }
+
+        fn consume(&mut self, amt: usize) {
+            self.0.consume(amt)
+        }
+    }
+
+    impl Write for Logged<'_> {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            let written = String::from_utf8_lossy(buf);
+            self.1.borrow_mut().push(format!("write {:?}", written));
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    let events = RefCell::new(Vec::new());
+    let mut input = Logged(Cursor::new(b"yes\n"), &events);
+    let mut output = Logged(Cursor::new(b""), &events);
+    let answer = prompt_redraw_from(&mut input, &mut output, "Continue? >", |event| {
+        events.borrow_mut().push(format!("{:?}", event))
+    });
+    assert_eq!(answer.unwrap(), "yes");
+    assert_eq!(
+        events.into_inner(),
+        ["Clear", "write \"Continue? >\"", "read", "Restore"]
+    );
+
+    let events = RefCell::new(Vec::new());
+    let mut output = Vec::new();
+    let err = prompt_redraw_from(&mut Cursor::new(b"\xff\n"), &mut output, "> ", |event| {
+        events.borrow_mut().push(event)
+    })
+    .unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    assert_eq!(events.into_inner(), [Redraw::Clear, Redraw::Restore]);
+}
+
+#[test]
+fn tee_writes_prompts_to_both_writers() {
+    let input = Cursor::new("eighty\n80\nFerris\n");
+    let mut prompter = Prompter::tee(input, Vec::new(), Vec::new());
+    assert_eq!(prompter.parse_loop::<u16>("Port? >").unwrap(), 80);
+    let (_, output) = prompter.into_parts();
+    let (terminal, log) = output.into_parts();
+    assert_eq!(terminal, log);
+    assert!(terminal.starts_with(b"Port? >"));
+    assert!(terminal.ends_with(b"Port? >"));
+
+    let input = Cursor::new("8080\nFerris\n");
+    let mut prompter = Prompter::tee(input, Vec::new(), Vec::new()).log_answers(true);
+    prompter.parse_loop::<u16>("Port? >").unwrap();
+    prompter.prompt("Name? >").unwrap();
+    let (_, output) = prompter.into_parts();
+    let (terminal, log) = output.into_parts();
+    assert_eq!(terminal, b"Port? >Name? >");
+    assert_eq!(log, b"Port? >8080\nName? >Ferris\n");
+}
+
+#[test]
+fn percent_accepts_whole_numbers_percentages_and_fractions() {
+    let percent = |answer: &str| {
+        let mut prompter = Prompter::new(Cursor::new(format!("{}\n", answer)), Vec::new());
+        prompter.percent("Brightness? >").ok()
+    };
+    assert_eq!(percent("50"), Some(0.5));
+    assert_eq!(percent("50%"), Some(0.5));
+    assert_eq!(percent(" 50 % "), Some(0.5));
+    assert_eq!(percent("0.5"), Some(0.5));
+    assert_eq!(percent("12.5%"), Some(0.125));
+    assert_eq!(percent("1"), Some(0.01));
+    assert_eq!(percent("1.0"), Some(1.0));
+    assert_eq!(percent("100%"), Some(1.0));
+    for rejected in [
+        "150%", "150", "12.5", "-1%", "bright", "", "%", "NaN%", "inf",
+    ] {
+        assert_eq!(percent(rejected), None, "{:?} was accepted", rejected);
+    }
+}
+
+#[test]
+fn money_strips_currency_symbols_and_separators() {
+    let money = |answer: &str| {
+        let mut prompter = Prompter::new(Cursor::new(format!("{}\n", answer)), Vec::new());
+        prompter.money("Amount? >").ok()
+    };
+    assert_eq!(money("$1,234.56"), Some(1234.56));
+    assert_eq!(money("1234.56"), Some(1234.56));
+    assert_eq!(money(" € 1,000,000 "), Some(1_000_000.0));
+    assert_eq!(money("-$20"), Some(-20.0));
+    assert_eq!(money("£0.5"), Some(0.5));
+    assert_eq!(money("999,999"), Some(999_999.0));
+    for rejected in [
+        "lots", "", "$", "1,2", "1234,567", "1,234,56", ",123", "1.", ".5", "1.2.3", "$ -5", "1e3",
+        "NaN", "inf", "12 34", "--5",
+    ] {
+        assert_eq!(money(rejected), None, "{:?} was accepted", rejected);
+    }
+}
+
+#[test]
+fn prompt_money_macro_reprompts_on_garbage() {
+    if is_child() {
+        assert_eq!(prompt_money!("Amount? >"), 1234.56);
+        return;
+    }
+    let output = run_child(
+        "prompt_money_macro_reprompts_on_garbage",
+        b"lots\n1,23\n$1,234.56\n",
+    );
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let reprompt = "Please enter an amount, such as $1,234.56\nAmount? >";
+    assert_eq!(stdout.matches(reprompt).count(), 2);
+}
+
+#[test]
+fn prompt_percent_macro_reprompts() {
+    if is_child() {
+        assert_eq!(prompt_percent!("Brightness? >"), 0.4);
+        return;
+    }
+    let output = run_child("prompt_percent_macro_reprompts", b"150%\nbright\n40%\n");
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let reprompt = "Please enter a percentage between 0% and 100%, such as 50%\nBrightness? >";
+    assert_eq!(stdout.matches(reprompt).count(), 2);
+}
+
+#[test]
+fn map_normalizes_answers_before_they_are_validated() {
+    let prompt = PromptBuilder::new()
+        .message("Code? >")
+        .trim(true)
+        .map(|s| s.to_uppercase())
+        .validate(|s| s.starts_with("EU-"))
+        .default("eu-1")
+        .build();
+    let mut prompter = Prompter::new(Cursor::new(" us-1 \n eu-2 \n"), Vec::new());
+    assert_eq!(prompt.ask_with(&mut prompter).unwrap(), "EU-2");
+    let mut prompter = Prompter::new(Cursor::new("\n"), Vec::new());
+    assert_eq!(prompt.ask_with(&mut prompter).unwrap(), "eu-1");
+}
+
+#[test]
+fn default_map_applies_to_prompts_without_their_own() {
+    if is_child() {
+        set_default_map(Some(|s| s.to_uppercase()));
+        let plain = PromptBuilder::new().message("a? >").build();
+        assert_eq!(plain.ask().unwrap(), "FERRIS");
+        let own = PromptBuilder::new().message("b? >").map(|s| s).build();
+        assert_eq!(own.ask().unwrap(), "ferris");
+        return;
+    }
+    run_child(
+        "default_map_applies_to_prompts_without_their_own",
+        b"ferris\nferris\n",
+    );
+}
+
+#[test]
+fn escalated_hint_is_only_shown_after_the_threshold() {
+    let prompt = PromptBuilder::new()
+        .message("Pin? >")
+        .validate(|s| s.len() == 4)
+        .invalid("Invalid pin")
+        .escalated_hint(2, "A pin has 4 digits")
+        .max_retries(3)
+        .build();
+
+    let mut prompter = Prompter::new(Cursor::new("1\n12\n123\n1234\n"), Vec::new());
+    assert_eq!(prompt.ask_with(&mut prompter).unwrap(), "1234");
+    let output = String::from_utf8(prompter.into_parts().1).unwrap();
+    assert_eq!(
+        output,
+        "Pin? >Invalid pin\nPin? >Invalid pin\nPin? >A pin has 4 digits\nPin? >"
+    );
+
+    let mut prompter = Prompter::new(Cursor::new("1\n2\n3\n4\n5\n"), Vec::new());
+    let err = prompt.ask_with(&mut prompter).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    let output = String::from_utf8(prompter.into_parts().1).unwrap();
+    assert_eq!(output.matches("Invalid pin").count(), 2);
+    assert_eq!(output.matches("A pin has 4 digits").count(), 2);
+}
+
+#[test]
+fn prompt_retry_with_hint_macro_gives_up_after_max_retries() {
+    if is_child() {
+        let pin = prompt_retry_with_hint!(
+            "Pin? >",
+            |s: &str| s.len() == 4,
+            invalid = "Invalid pin",
+            after = 1,
+            hint = "A pin has 4 digits",
+            max_retries = 1,
+        );
+        assert!(pin.is_err());
+        return;
+    }
+    let output = run_child(
+        "prompt_retry_with_hint_macro_gives_up_after_max_retries",
+        b"1\n2\n",
+    );
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("Pin? >Invalid pin\nPin? >A pin has 4 digits\n"));
+}
+
+#[test]
+#[cfg(unix)]
+fn read_line_cancelable_returns_none_once_canceled() {
+    use std::io::{BufRead, BufReader};
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Arc;
+    use std::thread;
+
+    if is_child() {
+        let canceled = Arc::new(AtomicBool::new(false));
+        let cancel = Arc::clone(&canceled);
+        eprintln!("ready");
+        let canceler = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(200));
+            cancel.store(true, Ordering::SeqCst);
+        });
+        let start = Instant::now();
+        assert_eq!(read_line_cancelable(&canceled).unwrap(), None);
+        assert!(start.elapsed() >= Duration::from_millis(200));
+        canceler.join().unwrap();
+        assert_eq!(read_line_cancelable(&canceled).unwrap(), None);
+
+        canceled.store(false, Ordering::SeqCst);
+        eprintln!("canceled");
+        // The partial line read before the cancel was discarded.
+        assert_eq!(read_line_cancelable(&canceled).unwrap().unwrap(), "next");
+        return;
+    }
+    let test = "read_line_cancelable_returns_none_once_canceled";
+    let mut child = spawn_child(test);
+    let mut stdin = child.stdin.take().unwrap();
+    let mut stderr = BufReader::new(child.stderr.take().unwrap());
+    let mut wait_for = |signal: &str| {
+        let mut line = String::new();
+        while line.trim_end() != signal {
+            line.clear();
+            assert_ne!(stderr.read_line(&mut line).unwrap(), 0, "child exited");
+        }
+    };
+    wait_for("ready");
+    stdin.write_all(b"partial").unwrap();
+    wait_for("canceled");
+    stdin.write_all(b"next\n").unwrap();
+    drop(stdin);
+    wait_child(test, child);
+}
+
+#[test]
+fn parse_errors_carry_the_offending_input() {
+    let mut prompter = Prompter::new(Cursor::new("12a\r\n"), Vec::new());
+    let err = prompter.parse::<u16>("Number? >").unwrap().unwrap_err();
+    assert_eq!(err.input(), "12a");
+    assert_eq!(err.attempts(), 1);
+    assert_eq!(err.error(), &"12a".parse::<u16>().unwrap_err());
+    assert!(err.to_string().contains("\"12a\""));
+}
+
+#[test]
+fn prompt_parse_macro_returns_the_offending_input() {
+    if is_child() {
+        let err = prompt_parse!("Port? >").map(|port: u16| port).unwrap_err();
+        assert_eq!(err.input(), "http");
+        let boxed: Box<dyn std::error::Error + Send + Sync> = Box::new(err);
+        assert!(boxed.to_string().starts_with("invalid answer \"http\""));
+        return;
+    }
+    run_child("prompt_parse_macro_returns_the_offending_input", b"http\n");
+}
+
+#[test]
+#[cfg(feature = "time")]
+fn date_rejects_malformed_and_out_of_calendar_dates() {
+    use time::{Date, Month};
+
+    let date = |answer: &str| {
+        let mut prompter = Prompter::new(Cursor::new(format!("{}\n", answer)), Vec::new());
+        prompter.date("Date? >").ok()
+    };
+    let may = Date::from_calendar_date(2024, Month::May, 17).unwrap();
+    assert_eq!(date("2024-05-17"), Some(may));
+    assert_eq!(date(" 2024-02-29 ").unwrap().month(), Month::February);
+    for rejected in [
+        "2023-02-30",
+        "2023-02-29",
+        "2023-13-01",
+        "2023-00-10",
+        "2024-5-17",
+        "17/05/2024",
+        "2024-05-17T10:00",
+        "+2024-05-17",
+        "tomorrow",
+        "",
+    ] {
+        assert_eq!(date(rejected), None, "{:?} was accepted", rejected);
+    }
+}
+
+#[test]
+#[cfg(feature = "time")]
+fn prompt_date_macro_reprompts() {
+    if is_child() {
+        assert_eq!(prompt_date!("Date? >").to_string(), "2023-03-01");
+        return;
+    }
+    let output = run_child(
+        "prompt_date_macro_reprompts",
+        b"2023-02-30\nMarch 1st\n2023-03-01\n",
+    );
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let reprompt = "Please enter a date as YYYY-MM-DD, such as 2024-05-17\nDate? >";
+    assert_eq!(stdout.matches(reprompt).count(), 2);
+}
+
+#[test]
+fn multi_prompt_reprompts_per_field() {
+    if is_child() {
+        let (name, age, tall) = multi_prompt! {
+            "Name? >" => String,
+            "Age? >" => u8,
+            "Tall? >" => bool,
+        };
+        assert_eq!((name.as_str(), age, tall), ("Ferris", 7, true));
+        return;
+    }
+    let output = run_child(
+        "multi_prompt_reprompts_per_field",
+        b"Ferris\nold\n7\ntrue\n",
+    );
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("Name? >Age? >invalid digit found in string\nAge? >Tall? >"));
+}
+
+#[test]
+fn hidden_reads_end_with_a_newline() {
+    if is_child() {
+        let mut output = Vec::new();
+        read_password_masked_from(&mut Cursor::new("pin\r"), &mut output).unwrap();
+        assert!(output.ends_with(b"***\r\n"));
+
+        // No newline after a canceled read.
+        let mut output = Vec::new();
+        read_password_masked_from(&mut Cursor::new("pin\x03"), &mut output).unwrap_err();
+        assert_eq!(output, b"***");
+
+        set_password_newline(false);
+        let mut output = Vec::new();
+        read_password_masked_from(&mut Cursor::new("pin\r"), &mut output).unwrap();
+        assert_eq!(output, b"***");
+        return;
+    }
+    run_child("hidden_reads_end_with_a_newline", b"");
+}
+
+#[test]
+fn suggestions_are_rendered_once_before_the_default_and_suffix() {
+    let prompt = PromptBuilder::new()
+        .message("Port? >")
+        .suggestions(&["8080", "3000"])
+        .default("80")
+        .suffix(">")
+        .build();
+    let mut prompter = Prompter::new(Cursor::new("443\n"), Vec::new());
+    assert_eq!(prompt.ask_with(&mut prompter).unwrap(), "443");
+    let output = String::from_utf8(prompter.into_parts().1).unwrap();
+    assert_eq!(output, "Port? (e.g. 8080, 3000) [80]>");
+
+    let prompt = PromptBuilder::new()
+        .message("Port ")
+        .suggestions(&["1"])
+        .build();
+    let mut prompter = Prompter::new(Cursor::new("2\n"), Vec::new());
+    prompt.ask_with(&mut prompter).unwrap();
+    assert_eq!(prompter.into_parts().1, b"Port (e.g. 1) ");
+}
+
+#[test]
+fn prompt_with_suggestions_macro_shows_suggestions_once() {
+    if is_child() {
+        assert_eq!(prompt_with_suggestions!(&["8080", "3000"], "Port >"), "80");
+        return;
+    }
+    let output = run_child(
+        "prompt_with_suggestions_macro_shows_suggestions_once",
+        b"80\n",
+    );
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(stdout.matches("Port (e.g. 8080, 3000) >").count(), 1);
+    assert_eq!(stdout.matches("8080").count(), 1);
+}
+
+#[test]
+fn prompter_reuses_its_line_buffer() {
+    let input: String = (0..1000).map(|i| format!("answer {}\n", i)).collect();
+    let mut prompter = Prompter::with_capacity(Cursor::new(input), io::sink(), 32);
+    let capacity = prompter.capacity();
+    assert!(capacity >= 32);
+    for i in 0..500 {
+        assert_eq!(prompter.prompt_ref("> ").unwrap(), format!("answer {}", i));
+        assert_eq!(prompter.capacity(), capacity);
+    }
+    for i in 500..1000 {
+        assert_eq!(prompter.prompt("> ").unwrap(), format!("answer {}", i));
+        assert_eq!(prompter.capacity(), capacity);
+    }
+
+    let long = "x".repeat(100);
+    let mut prompter = Prompter::new(Cursor::new(format!("{}\nshort\n", long)), io::sink());
+    assert_eq!(prompter.promptln("> ").unwrap(), long);
+    let grown = prompter.capacity();
+    assert!(grown >= 100);
+    assert_eq!(prompter.prompt_ref("> ").unwrap(), "short");
+    assert_eq!(prompter.capacity(), grown);
+}
+
+#[test]
+fn select_map_returns_the_chosen_entry() {
+    use std::collections::BTreeMap;
+
+    let sizes = BTreeMap::from([("small", 1), ("large", 4), ("medium", 2)]);
+    let mut prompter = Prompter::new(Cursor::new("2\n"), Vec::new());
+    assert_eq!(
+        prompter.select_map("Size:", &sizes).unwrap(),
+        (&"medium", &2)
+    );
+    let output = String::from_utf8(prompter.into_parts().1).unwrap();
+    assert_eq!(output, "Size:\n1) large: 4\n2) medium: 2\n3) small: 1\n> ");
+
+    let mut prompter = Prompter::new(Cursor::new("0\n4\nsmall\n3\n"), Vec::new());
+    assert_eq!(
+        prompter.select_map("Size:", &sizes).unwrap(),
+        (&"small", &1)
+    );
+    let output = String::from_utf8(prompter.into_parts().1).unwrap();
+    assert!(output.ends_with("> > > > "));
+
+    let empty: BTreeMap<u8, u8> = BTreeMap::new();
+    let mut prompter = Prompter::new(Cursor::new("1\n"), Vec::new());
+    assert!(prompter.select_map("Size:", &empty).is_err());
+}
+
+#[test]
+fn prompt_indexed_map_macro_reprompts_out_of_range() {
+    use std::collections::BTreeMap;
+
+    if is_child() {
+        let map = BTreeMap::from([(2, "two"), (1, "one")]);
+        assert_eq!(prompt_indexed_map!("Pick:", &map), (&2, &"two"));
+        return;
+    }
+    let output = run_child("prompt_indexed_map_macro_reprompts_out_of_range", b"3\n2\n");
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("Pick:\n1) 1: one\n2) 2: two\n> > "));
+}
+
+#[test]
+fn select_fuzzy_returns_a_unique_match() {
+    let crates = ["serde", "serde_json", "tokio", "tokio-util", "regex"];
+    let mut prompter = Prompter::new(Cursor::new("RGX\n"), Vec::new());
+    assert_eq!(prompter.select_fuzzy("Crate:", &crates).unwrap(), 4);
+    let output = String::from_utf8(prompter.into_parts().1).unwrap();
+    assert_eq!(output, "Crate:\n> ");
+
+    let mut prompter = Prompter::new(Cursor::new(" tokio \n"), Vec::new());
+    assert_eq!(prompter.select_fuzzy("Crate:", &crates).unwrap(), 2);
+    let mut prompter = Prompter::new(Cursor::new("json\n"), Vec::new());
+    assert_eq!(prompter.select_fuzzy("Crate:", &crates).unwrap(), 1);
+
+    let empty: [&str; 0] = [];
+    let mut prompter = Prompter::new(Cursor::new("serde\n"), Vec::new());
+    assert!(prompter.select_fuzzy("Crate:", &empty).is_err());
+}
+
+#[test]
+fn select_fuzzy_reprompts_on_an_ambiguous_query() {
+    let crates = ["tokio-util", "serde_json", "tokio-stream", "serde", "tokio"];
+    let input = Cursor::new("se\nxyz\nsrdj\n");
+    let mut prompter = Prompter::new(input, Vec::new());
+    assert_eq!(prompter.select_fuzzy("Crate:", &crates).unwrap(), 1);
+    let output = String::from_utf8(prompter.into_parts().1).unwrap();
+    assert_eq!(
+        output,
+        "Crate:\n> Several options match:\n  - serde\n  - serde_json\n\
+         > No option matches xyz\n> "
+    );
+
+    let mut prompter = Prompter::new(Cursor::new("tok\n"), Vec::new()).max_retries(0);
+    assert!(prompter.select_fuzzy("Crate:", &crates).is_err());
+}
+
+#[test]
+fn select_fuzzy_macro_uses_stdio() {
+    if is_child() {
+        assert_eq!(select_fuzzy!("Pick:", &["alpha", "beta", "gamma"]), 2);
+        return;
+    }
+    let output = run_child("select_fuzzy_macro_uses_stdio", b"a\ngam\n");
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("Pick:\n> Several options match:\n  - beta\n  - alpha\n  - gamma\n> "));
+}
+
+#[test]
+fn prompt_until_complete_reads_until_brackets_balance() {
+    fn balanced(text: &str) -> bool {
+        let mut depth = 0i32;
+        for c in text.chars() {
+            match c {
+                '(' | '[' | '{' => depth += 1,
+                ')' | ']' | '}' => depth -= 1,
+                _ => {}
+            }
+        }
+        depth <= 0
+    }
+
+    let input = Cursor::new("{\n  \"a\": [1,\n    2]\n}\nnext\n");
+    let mut prompter = Prompter::new(input, Vec::new());
+    let text = prompter.prompt_until_complete("JSON:", balanced).unwrap();
+    assert_eq!(text, "{\n  \"a\": [1,\n    2]\n}");
+    assert_eq!(prompter.prompt("> ").unwrap(), "next");
+    let output = String::from_utf8(prompter.into_parts().1).unwrap();
+    assert_eq!(output, "JSON:\n> ");
+
+    let mut input = Cursor::new("(first\nsecond");
+    let text = read_until_complete_from(&mut input, balanced).unwrap();
+    assert_eq!(text, "(first\nsecond");
+    assert!(!balanced(&text));
+
+    let mut input = Cursor::new("");
+    assert_eq!(read_until_complete_from(&mut input, balanced).unwrap(), "");
+}
+
+#[test]
+fn prompt_toggle_cycles_on_enter_until_confirmed() {
+    let levels = ["low", "med", "high"];
+    let mut output = Vec::new();
+    let mut input = Cursor::new(&b"\r\rx\x1b[A y"[..]);
+    let level = prompt_toggle_from(&mut input, &mut output, "Level: ", &levels, 0).unwrap();
+    assert_eq!(level, 2);
+    assert_eq!(
+        String::from_utf8(output).unwrap(),
+        "\rLevel: low\x1b[K\rLevel: med\x1b[K\rLevel: high\x1b[K\r\n"
+    );
+
+    let mut output = Vec::new();
+    let mut input = Cursor::new(&b"\r\rY"[..]);
+    let level = prompt_toggle_from(&mut input, &mut output, "Level: ", &levels, 1).unwrap();
+    assert_eq!(level, 0);
+
+    let mut input = Cursor::new(&b"\r\r"[..]);
+    let err = prompt_toggle_from(&mut input, &mut Vec::new(), "> ", &levels, 0).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+    let mut input = Cursor::new(&b"\r\x03"[..]);
+    let err = prompt_toggle_from(&mut input, &mut Vec::new(), "> ", &levels, 0).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::Interrupted);
+    let err =
+        prompt_toggle_from(&mut Cursor::new(" "), &mut Vec::new(), "> ", &levels, 3).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+}
+
+#[test]
+fn prompt_toggle_macro_reads_a_line_when_piped() {
+    if is_child() {
+        assert_eq!(prompt_toggle!("Level: ", &["low", "med", "high"]), 0);
+        assert_eq!(prompt_toggle!("Level: ", &["low", "med"], default = 1), 1);
+        assert_eq!(prompt_toggle!("Level: ", &["low", "med", "high"]), 2);
+        return;
+    }
+    let output = run_child(
+        "prompt_toggle_macro_reads_a_line_when_piped",
+        b"\n\n HIGH \n",
+    );
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("Level: Level: Level: "));
+}